@@ -0,0 +1,307 @@
+//! # BBS Cryptosuite
+//!
+//! The [`bbs-2023`] Data Integrity cryptosuite, providing unlinkable
+//! selective disclosure using BBS signatures over BLS12-381.
+//!
+//! - The issuer adds a base proof to a document using [`add_base_proof`],
+//!   signing each of the document's canonical statements. JSON Pointers
+//!   identify the mandatory statements the holder must always disclose.
+//! - The holder uses [`derive_proof`] to select the statements to disclose,
+//!   creating a new document secured by a derived proof. Derived proofs are
+//!   unlinkable: two proofs derived from the same base proof cannot be
+//!   correlated.
+//! - The verifier checks the derived proof using [`verify_derived_proof`]
+//!   and the issuer's BLS12-381 G2 public key.
+//!
+//! Documents are converted to RDF offline, so may only reference the
+//! contexts bundled with [`crate::rdf`].
+//!
+//! [`bbs-2023`]: https://www.w3.org/TR/vc-di-bbs
+
+mod proof_value;
+mod signature;
+
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow, bail};
+use hmac::{Hmac, Mac};
+use multibase::Base;
+use rand::RngCore;
+use rand::rngs::OsRng;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use self::proof_value::{BaseProof, DerivedProof};
+pub use self::signature::{PublicKey, SecretKey, proof_gen, proof_verify, sign, verify};
+use crate::{Proof, rdf};
+
+/// The name of the cryptosuite.
+pub const CRYPTOSUITE: &str = "bbs-2023";
+
+/// Proof type for Data Integrity proofs.
+const DATA_INTEGRITY_PROOF: &str = "DataIntegrityProof";
+
+/// Options used when creating a base proof.
+#[derive(Clone, Debug, Default)]
+pub struct BaseOptions {
+    /// JSON Pointers to the parts of the document the holder must always
+    /// disclose.
+    pub mandatory_pointers: Vec<String>,
+
+    /// The key used to randomize blank node labels. A random key is
+    /// generated when not set.
+    pub hmac_key: Option<[u8; 32]>,
+}
+
+/// Add a `bbs-2023` base proof to a JSON-LD document.
+///
+/// The proof configuration provides the proof's properties (type,
+/// cryptosuite, verification method, purpose, etc.) and is returned with
+/// the base proof value on the secured document.
+///
+/// # Errors
+///
+/// Returns an error if the document already has a proof, the configuration
+/// is not for a `bbs-2023` Data Integrity proof, a mandatory pointer does
+/// not match the document, or the document cannot be canonicalized.
+pub fn add_base_proof(
+    document: &Value, config: &Proof, secret_key: &SecretKey, options: &BaseOptions,
+) -> Result<Value> {
+    if !document.is_object() {
+        bail!("document must be a JSON object");
+    }
+    if document.get("proof").is_some() {
+        bail!("document is already secured");
+    }
+    check_config(config)?;
+
+    let hmac_key = options.hmac_key.unwrap_or_else(|| {
+        let mut key = [0; 32];
+        OsRng.fill_bytes(&mut key);
+        key
+    });
+
+    // group the canonical statements into mandatory and non-mandatory
+    let grouped = rdf::canonicalize_and_group(
+        document,
+        shuffled_labels(&hmac_key),
+        &[&options.mandatory_pointers],
+    )?;
+    let mandatory = &grouped.groups[0];
+
+    let header = [
+        proof_hash(document, config)?,
+        Sha256::digest(mandatory.matching.values().cloned().collect::<String>()).to_vec(),
+    ]
+    .concat();
+    let messages = mandatory.non_matching.values().map(String::as_bytes).collect::<Vec<_>>();
+
+    let public_key = secret_key.public_key();
+    let base_proof = BaseProof {
+        signature: sign(secret_key, &public_key, &header, &messages)?,
+        header,
+        public_key: public_key.to_bytes().to_vec(),
+        hmac_key: hmac_key.to_vec(),
+        mandatory_pointers: options.mandatory_pointers.clone(),
+    };
+
+    let mut proof = config.clone();
+    proof.proof_value = Some(base_proof.encode()?);
+    let mut secured = document.clone();
+    secured["proof"] = serde_json::to_value(&proof)?;
+    Ok(secured)
+}
+
+/// Derive a proof from a document secured with a `bbs-2023` base proof,
+/// disclosing the mandatory statements and those selected by the holder's
+/// JSON Pointers.
+///
+/// Returns the document reduced to the disclosed parts and secured by the
+/// derived proof. The presentation header is bound to the derived proof
+/// and is typically used by the verifier to prevent replay.
+///
+/// # Errors
+///
+/// Returns an error if the document does not have a `bbs-2023` base proof,
+/// a pointer does not match the document, or nothing is disclosed.
+pub fn derive_proof(
+    document: &Value, selective_pointers: &[String], presentation_header: &[u8],
+) -> Result<Value> {
+    let mut proof: Proof = match document.get("proof") {
+        Some(proof @ Value::Object(_)) => serde_json::from_value(proof.clone())?,
+        Some(_) => bail!("document must have a single proof"),
+        None => bail!("document has no proof"),
+    };
+    check_config(&proof)?;
+    let Some(proof_value) = &proof.proof_value else {
+        bail!("proof value is missing");
+    };
+    let base = BaseProof::decode(proof_value)?;
+
+    let mut unsecured = document.clone();
+    if let Value::Object(map) = &mut unsecured {
+        map.remove("proof");
+    }
+
+    let mandatory_pointers = &base.mandatory_pointers;
+    let combined_pointers = [mandatory_pointers.as_slice(), selective_pointers].concat();
+    let grouped = rdf::canonicalize_and_group(
+        &unsecured,
+        shuffled_labels(&base.hmac_key),
+        &[mandatory_pointers, selective_pointers, &combined_pointers],
+    )?;
+    let (mandatory, selective, combined) =
+        (&grouped.groups[0], &grouped.groups[1], &grouped.groups[2]);
+
+    // mandatory statements are identified by their index in the disclosed
+    // statements, selected statements by their index in the signed messages
+    let position = |indexes: &[usize], index: &usize| indexes.iter().position(|i| i == index);
+    let combined_indexes = combined.matching.keys().copied().collect::<Vec<_>>();
+    let mandatory_indexes = mandatory
+        .matching
+        .keys()
+        .filter_map(|index| position(&combined_indexes, index))
+        .collect::<Vec<_>>();
+    let non_mandatory_indexes = mandatory.non_matching.keys().copied().collect::<Vec<_>>();
+    let selective_indexes = selective
+        .matching
+        .keys()
+        .filter_map(|index| position(&non_mandatory_indexes, index))
+        .collect::<Vec<_>>();
+
+    let messages = mandatory.non_matching.values().map(String::as_bytes).collect::<Vec<_>>();
+    let bbs_proof = proof_gen(
+        &PublicKey::from_bytes(&base.public_key)?,
+        &base.signature,
+        &base.header,
+        presentation_header,
+        &messages,
+        &selective_indexes,
+    )?;
+
+    // map the verifier's canonical blank node labels to the signed labels
+    let label_map = rdf::canonical_labels(&combined.deskolemized)?
+        .into_iter()
+        .map(|(input, c14n)| {
+            let label = grouped
+                .label_map
+                .get(&input)
+                .ok_or_else(|| anyhow!("blank node {input} is not in the label map"))?;
+            Ok((c14n, label.clone()))
+        })
+        .collect::<Result<_>>()?;
+
+    let Some(mut reveal) = rdf::select_json_ld(&unsecured, &combined_pointers)? else {
+        bail!("derived proof must disclose at least one statement");
+    };
+
+    let derived = DerivedProof {
+        proof: bbs_proof,
+        label_map,
+        mandatory_indexes,
+        selective_indexes,
+        presentation_header: presentation_header.to_vec(),
+    };
+    proof.proof_value = Some(derived.encode()?);
+    reveal["proof"] = serde_json::to_value(&proof)?;
+    Ok(reveal)
+}
+
+/// Verify a `bbs-2023` derived proof over a document (without its proof)
+/// using the issuer's public key.
+///
+/// # Errors
+///
+/// Returns an error if the proof is a base proof or is not valid for the
+/// document.
+pub fn verify_derived_proof(document: &Value, proof: &Proof, public_key: &PublicKey) -> Result<()> {
+    check_config(proof)?;
+    let Some(proof_value) = &proof.proof_value else {
+        bail!("proof value is missing");
+    };
+    if proof_value::is_base_proof(proof_value) {
+        bail!("base proofs cannot be verified: the holder must derive a proof");
+    }
+    let derived = DerivedProof::decode(proof_value)?;
+
+    // relabel the document's blank nodes with the signed labels
+    let quads = rdf::to_rdf(&rdf::expand(document)?)?;
+    let (_, nquads) = rdf::label_replacement_canonicalize(&quads, |canonical| {
+        canonical
+            .iter()
+            .map(|(input, c14n)| {
+                let label = derived
+                    .label_map
+                    .get(c14n)
+                    .ok_or_else(|| anyhow!("blank node {c14n} is not in the label map"))?;
+                Ok((input.clone(), label.clone()))
+            })
+            .collect()
+    })?;
+
+    let mut mandatory = String::new();
+    let mut disclosed = Vec::new();
+    for (index, nquad) in nquads.iter().enumerate() {
+        if derived.mandatory_indexes.contains(&index) {
+            mandatory.push_str(nquad);
+        } else {
+            disclosed.push(nquad.as_bytes());
+        }
+    }
+
+    let header = [proof_hash(document, proof)?, Sha256::digest(mandatory).to_vec()].concat();
+    proof_verify(
+        public_key,
+        &derived.proof,
+        &header,
+        &derived.presentation_header,
+        &disclosed,
+        &derived.selective_indexes,
+    )
+}
+
+// Check the proof (configuration) is a `bbs-2023` Data Integrity proof.
+fn check_config(proof: &Proof) -> Result<()> {
+    if proof.type_ != DATA_INTEGRITY_PROOF || proof.cryptosuite.as_deref() != Some(CRYPTOSUITE) {
+        bail!("proof must be a `{CRYPTOSUITE}` {DATA_INTEGRITY_PROOF}");
+    }
+    Ok(())
+}
+
+// The hash of the canonicalized proof configuration: the proof without its
+// value, sharing the document's `@context`.
+fn proof_hash(document: &Value, proof: &Proof) -> Result<Vec<u8>> {
+    let mut config = serde_json::to_value(proof)?;
+    if let Value::Object(map) = &mut config {
+        map.remove("proofValue");
+        if let Some(context) = document.get("@context") {
+            map.insert("@context".to_string(), context.clone());
+        }
+    }
+    Ok(Sha256::digest(rdf::canonicalize(&config)?).to_vec())
+}
+
+// Label blank nodes `b0`, `b1`, etc. in the order of the HMAC of their
+// canonical labels, so the labels do not reveal the document's structure.
+fn shuffled_labels(
+    hmac_key: &[u8],
+) -> impl FnOnce(&HashMap<String, String>) -> Result<HashMap<String, String>> + '_ {
+    move |canonical| {
+        let mut hmac_ids = HashMap::new();
+        for (input, c14n) in canonical {
+            let mut mac = Hmac::<Sha256>::new_from_slice(hmac_key)?;
+            mac.update(c14n.as_bytes());
+            hmac_ids.insert(input.clone(), Base::Base64Url.encode(mac.finalize().into_bytes()));
+        }
+
+        let mut sorted = hmac_ids.values().cloned().collect::<Vec<_>>();
+        sorted.sort();
+        Ok(hmac_ids
+            .into_iter()
+            .map(|(input, id)| {
+                let index = sorted.binary_search(&id).unwrap_or_default();
+                (input, format!("b{index}"))
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,168 @@
+//! # Proof Values
+//!
+//! Serialization of `bbs-2023` base and derived proof values: a three-byte
+//! header identifying the proof type followed by the CBOR-encoded proof
+//! components, multibase-encoded using base64url (no padding).
+
+use std::collections::BTreeMap;
+
+use anyhow::{Result, anyhow, bail};
+use ciborium::Value;
+use multibase::Base;
+
+/// Header for a base proof (baseline feature option).
+const BASE_HEADER: [u8; 3] = [0xd9, 0x5d, 0x02];
+
+/// Header for a derived proof (baseline feature option).
+const DERIVED_HEADER: [u8; 3] = [0xd9, 0x5d, 0x03];
+
+/// The components of a base proof, created by the issuer.
+#[derive(Clone, Debug)]
+pub struct BaseProof {
+    pub signature: Vec<u8>,
+    pub header: Vec<u8>,
+    pub public_key: Vec<u8>,
+    pub hmac_key: Vec<u8>,
+    pub mandatory_pointers: Vec<String>,
+}
+
+impl BaseProof {
+    pub fn encode(&self) -> Result<String> {
+        let components = vec![
+            Value::Bytes(self.signature.clone()),
+            Value::Bytes(self.header.clone()),
+            Value::Bytes(self.public_key.clone()),
+            Value::Bytes(self.hmac_key.clone()),
+            Value::Array(self.mandatory_pointers.iter().cloned().map(Value::Text).collect()),
+        ];
+        encode(BASE_HEADER, components)
+    }
+
+    pub fn decode(proof_value: &str) -> Result<Self> {
+        let Ok([signature, header, public_key, hmac_key, mandatory_pointers]) =
+            <[Value; 5]>::try_from(decode(BASE_HEADER, proof_value)?)
+        else {
+            bail!("base proof must have 5 components");
+        };
+        Ok(Self {
+            signature: bytes(signature)?,
+            header: bytes(header)?,
+            public_key: bytes(public_key)?,
+            hmac_key: bytes(hmac_key)?,
+            mandatory_pointers: array(mandatory_pointers)?
+                .into_iter()
+                .map(|pointer| pointer.into_text().map_err(|_| anyhow!("pointer must be text")))
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+/// The components of a derived proof, created by the holder.
+#[derive(Clone, Debug)]
+pub struct DerivedProof {
+    pub proof: Vec<u8>,
+
+    /// Map from canonical blank node labels (`c14n<n>`) to the labels used
+    /// when signing (`b<n>`).
+    pub label_map: BTreeMap<String, String>,
+
+    pub mandatory_indexes: Vec<usize>,
+    pub selective_indexes: Vec<usize>,
+    pub presentation_header: Vec<u8>,
+}
+
+impl DerivedProof {
+    pub fn encode(&self) -> Result<String> {
+        // labels are compressed to their numeric suffixes
+        let label_map = self
+            .label_map
+            .iter()
+            .map(|(c14n, label)| {
+                let key = label_index(c14n, "c14n")?;
+                let value = label_index(label, "b")?;
+                Ok((Value::Integer(key.into()), Value::Integer(value.into())))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let indexes =
+            |indexes: &[usize]| indexes.iter().map(|&i| Value::Integer(i.into())).collect();
+        let components = vec![
+            Value::Bytes(self.proof.clone()),
+            Value::Map(label_map),
+            Value::Array(indexes(&self.mandatory_indexes)),
+            Value::Array(indexes(&self.selective_indexes)),
+            Value::Bytes(self.presentation_header.clone()),
+        ];
+        encode(DERIVED_HEADER, components)
+    }
+
+    pub fn decode(proof_value: &str) -> Result<Self> {
+        let Ok([proof, label_map, mandatory_indexes, selective_indexes, presentation_header]) =
+            <[Value; 5]>::try_from(decode(DERIVED_HEADER, proof_value)?)
+        else {
+            bail!("derived proof must have 5 components");
+        };
+
+        let label_map = label_map
+            .into_map()
+            .map_err(|_| anyhow!("label map must be a map"))?
+            .into_iter()
+            .map(|(key, value)| Ok((format!("c14n{}", index(key)?), format!("b{}", index(value)?))))
+            .collect::<Result<_>>()?;
+        let indexes = |value: Value| array(value)?.into_iter().map(index).collect::<Result<_>>();
+
+        Ok(Self {
+            proof: bytes(proof)?,
+            label_map,
+            mandatory_indexes: indexes(mandatory_indexes)?,
+            selective_indexes: indexes(selective_indexes)?,
+            presentation_header: bytes(presentation_header)?,
+        })
+    }
+}
+
+/// Whether a proof value is a base proof.
+pub fn is_base_proof(proof_value: &str) -> bool {
+    multibase::decode(proof_value).is_ok_and(|(_, bytes)| bytes.starts_with(&BASE_HEADER))
+}
+
+// Encode the proof components with the header.
+fn encode(header: [u8; 3], components: Vec<Value>) -> Result<String> {
+    let mut bytes = header.to_vec();
+    ciborium::into_writer(&Value::Array(components), &mut bytes)?;
+    Ok(multibase::encode(Base::Base64Url, bytes))
+}
+
+// Decode a proof value's components, checking its header.
+fn decode(header: [u8; 3], proof_value: &str) -> Result<Vec<Value>> {
+    let (base, bytes) = multibase::decode(proof_value)?;
+    if base != Base::Base64Url {
+        bail!("proof value must be base64url-encoded");
+    }
+    let Some(cbor) = bytes.strip_prefix(&header) else {
+        bail!("proof value has an unexpected header");
+    };
+    let value: Value = ciborium::from_reader(cbor)?;
+    array(value)
+}
+
+fn bytes(value: Value) -> Result<Vec<u8>> {
+    value.into_bytes().map_err(|_| anyhow!("proof component must be a byte string"))
+}
+
+fn array(value: Value) -> Result<Vec<Value>> {
+    value.into_array().map_err(|_| anyhow!("proof component must be an array"))
+}
+
+fn index(value: Value) -> Result<usize> {
+    let integer = value.into_integer().map_err(|_| anyhow!("index must be an integer"))?;
+    Ok(usize::try_from(integer)?)
+}
+
+// The numeric suffix of a blank node label.
+fn label_index(label: &str, prefix: &str) -> Result<usize> {
+    label
+        .strip_prefix(prefix)
+        .and_then(|index| index.parse().ok())
+        .ok_or_else(|| anyhow!("unexpected blank node label: {label}"))
+}
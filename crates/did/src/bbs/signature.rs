@@ -0,0 +1,504 @@
+//! # BBS Signatures
+//!
+//! The [BBS Signature Scheme] using the `BLS12-381-SHA-256` ciphersuite:
+//! signing a set of messages, and generating and verifying zero-knowledge
+//! proofs of knowledge of a signature that disclose a subset of the messages.
+//!
+//! [BBS Signature Scheme]: https://datatracker.ietf.org/doc/draft-irtf-cfrg-bbs-signatures
+
+use std::sync::LazyLock;
+
+use anyhow::{Result, anyhow, bail};
+use blstrs::{G1Affine, G1Projective, G2Affine, G2Projective, Scalar, pairing};
+use ff::{Field, PrimeField};
+use group::{Curve, Group};
+use multibase::Base;
+use rand::RngCore;
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+
+/// The ciphersuite's API identifier, prefixing its domain separation tags.
+const API_ID: &[u8] = b"BBS_BLS12381G1_XMD:SHA-256_SSWU_RO_H2G_HM2S_";
+
+/// Multicodec prefix for a BLS12-381 G2 public key.
+const BLS12_381_G2_CODEC: [u8; 2] = [0xeb, 0x01];
+
+/// The length of the output of `expand_message` used to derive scalars and
+/// generators.
+const EXPAND_LEN: usize = 48;
+
+/// The length of an encoded scalar.
+const SCALAR_LEN: usize = 32;
+
+/// The length of a compressed G1 point.
+const G1_LEN: usize = 48;
+
+/// The length of a signature: a G1 point and a scalar.
+const SIGNATURE_LEN: usize = G1_LEN + SCALAR_LEN;
+
+/// The base point used in signing, derived from the ciphersuite's seed.
+static P1: LazyLock<G1Projective> =
+    LazyLock::new(|| create_generators(1, &[API_ID, b"BP_MESSAGE_GENERATOR_SEED"].concat())[0]);
+
+/// A BBS secret key.
+///
+/// The key is held in its big-endian encoding, which is overwritten when the
+/// key is dropped.
+pub struct SecretKey([u8; SCALAR_LEN]);
+
+impl SecretKey {
+    /// Derive a secret key from secret key material (at least 32 bytes of
+    /// randomness) and optional key information, using the scheme's `KeyGen`
+    /// operation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key material is too short or the key
+    /// information too long.
+    pub fn generate(key_material: &[u8], key_info: &[u8]) -> Result<Self> {
+        if key_material.len() < 32 {
+            bail!("key material must be at least 32 bytes");
+        }
+        let info_len = u16::try_from(key_info.len())?.to_be_bytes();
+        let dst = [API_ID, b"KEYGEN_DST_"].concat();
+        let secret = hash_to_scalar(&[key_material, &info_len, key_info], &dst);
+        if secret.is_zero_vartime() {
+            bail!("key material produced an invalid secret key");
+        }
+        Ok(Self(secret.to_bytes_be()))
+    }
+
+    /// Generate a random secret key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the generated key is not valid.
+    pub fn random() -> Result<Self> {
+        let mut key_material = [0; 32];
+        OsRng.fill_bytes(&mut key_material);
+        Self::generate(&key_material, &[])
+    }
+
+    /// Decode a secret key from its 32-byte big-endian encoding.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a valid secret key.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let secret = decode_scalar(bytes)?;
+        if secret.is_zero_vartime() {
+            bail!("secret key cannot be zero");
+        }
+        Ok(Self(secret.to_bytes_be()))
+    }
+
+    /// The 32-byte big-endian encoding of the secret key.
+    #[must_use]
+    pub const fn to_bytes(&self) -> [u8; SCALAR_LEN] {
+        self.0
+    }
+
+    /// The public key corresponding to the secret key.
+    #[must_use]
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(G2Projective::generator() * self.scalar())
+    }
+
+    // The secret key as a scalar. The encoding was checked when the key was
+    // created.
+    fn scalar(&self) -> Scalar {
+        Option::from(Scalar::from_bytes_be(&self.0)).unwrap_or(Scalar::ZERO)
+    }
+}
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+/// A BBS public key: a point on the BLS12-381 G2 subgroup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublicKey(G2Projective);
+
+impl PublicKey {
+    /// Decode a public key from its 96-byte compressed encoding.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a valid public key.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let point = Option::<G2Affine>::from(G2Affine::from_compressed(bytes.try_into()?))
+            .ok_or_else(|| anyhow!("invalid public key"))?;
+        let point = G2Projective::from(point);
+        if bool::from(point.is_identity()) {
+            bail!("invalid public key");
+        }
+        Ok(Self(point))
+    }
+
+    /// The 96-byte compressed encoding of the public key.
+    #[must_use]
+    pub fn to_bytes(&self) -> [u8; 96] {
+        self.0.to_compressed()
+    }
+
+    /// Decode a public key from a base58btc multikey.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the multikey is not a BLS12-381 G2 key.
+    pub fn from_multikey(multikey: &str) -> Result<Self> {
+        let (base, bytes) = multibase::decode(multikey)?;
+        if base != Base::Base58Btc {
+            bail!("unsupported multibase encoding");
+        }
+        let Some(key) = bytes.strip_prefix(&BLS12_381_G2_CODEC) else {
+            bail!("multikey is not a BLS12-381 G2 key");
+        };
+        Self::from_bytes(key)
+    }
+
+    /// Encode the public key as a base58btc multikey.
+    #[must_use]
+    pub fn to_multikey(&self) -> String {
+        multibase::encode(Base::Base58Btc, [&BLS12_381_G2_CODEC[..], &self.to_bytes()].concat())
+    }
+}
+
+/// Sign messages, returning the 80-byte signature.
+///
+/// The header is signed together with the messages and must be provided to
+/// verify the signature or any proof derived from it.
+///
+/// # Errors
+///
+/// Returns an error if the signature cannot be computed.
+pub fn sign(
+    secret_key: &SecretKey, public_key: &PublicKey, header: &[u8], messages: &[impl AsRef<[u8]>],
+) -> Result<Vec<u8>> {
+    let scalars = messages_to_scalars(messages);
+    let (q1, h) = message_generators(scalars.len());
+    let domain = calculate_domain(public_key, &q1, &h, header);
+
+    let mut input = [secret_key.to_bytes(), domain.to_bytes_be()].concat();
+    for scalar in &scalars {
+        input.extend(scalar.to_bytes_be());
+    }
+    let e = hash_to_scalar(&[&input], &hash_to_scalar_dst());
+    input.zeroize();
+
+    let b = commitment(&q1, &h, domain, scalars.iter().enumerate());
+    let inverse = Option::<Scalar>::from((secret_key.scalar() + e).invert())
+        .ok_or_else(|| anyhow!("signature cannot be computed"))?;
+    let a = b * inverse;
+    Ok([a.to_compressed().as_slice(), &e.to_bytes_be()].concat())
+}
+
+/// Verify a signature over messages.
+///
+/// # Errors
+///
+/// Returns an error if the signature is not valid.
+pub fn verify(
+    public_key: &PublicKey, signature: &[u8], header: &[u8], messages: &[impl AsRef<[u8]>],
+) -> Result<()> {
+    let (a, e) = decode_signature(signature)?;
+    let scalars = messages_to_scalars(messages);
+    let (q1, h) = message_generators(scalars.len());
+    let domain = calculate_domain(public_key, &q1, &h, header);
+    let b = commitment(&q1, &h, domain, scalars.iter().enumerate());
+
+    // e(A, W + BP2 * e) = e(B, BP2)
+    let lhs = pairing(&a.to_affine(), &(public_key.0 + G2Projective::generator() * e).to_affine());
+    if lhs != pairing(&b.to_affine(), &G2Affine::from(G2Projective::generator())) {
+        bail!("signature is not valid");
+    }
+    Ok(())
+}
+
+/// Generate a proof of knowledge of a signature that discloses the messages
+/// at the (zero-based) disclosed indexes.
+///
+/// The presentation header is bound to the proof and must be provided to
+/// verify it.
+///
+/// # Errors
+///
+/// Returns an error if the signature cannot be decoded or a disclosed index
+/// is out of range.
+#[allow(clippy::many_single_char_names)]
+pub fn proof_gen(
+    public_key: &PublicKey, signature: &[u8], header: &[u8], presentation_header: &[u8],
+    messages: &[impl AsRef<[u8]>], disclosed_indexes: &[usize],
+) -> Result<Vec<u8>> {
+    let (a, e) = decode_signature(signature)?;
+    let scalars = messages_to_scalars(messages);
+    let disclosed = sorted_indexes(disclosed_indexes, scalars.len())?;
+    let undisclosed = (0..scalars.len()).filter(|i| !disclosed.contains(i)).collect::<Vec<_>>();
+
+    let (q1, h) = message_generators(scalars.len());
+    let domain = calculate_domain(public_key, &q1, &h, header);
+
+    let random = || Scalar::random(OsRng);
+    let (r1, r2, e_tilde, r1_tilde, r3_tilde) = (random(), random(), random(), random(), random());
+    let m_tilde = undisclosed.iter().map(|_| random()).collect::<Vec<_>>();
+
+    let b = commitment(&q1, &h, domain, scalars.iter().enumerate());
+    let d = b * r2;
+    let a_bar = a * (r1 * r2);
+    let b_bar = d * r1 - a_bar * e;
+    let t1 = a_bar * e_tilde + d * r1_tilde;
+    let t2 = undisclosed.iter().zip(&m_tilde).fold(d * r3_tilde, |t2, (&j, m)| t2 + h[j] * m);
+
+    let disclosed_scalars = disclosed.iter().map(|&i| (i, scalars[i])).collect::<Vec<_>>();
+    let challenge = calculate_challenge(
+        [&a_bar, &b_bar, &d, &t1, &t2],
+        domain,
+        &disclosed_scalars,
+        presentation_header,
+    );
+
+    let r3 = Option::<Scalar>::from(r2.invert()).ok_or_else(|| anyhow!("invalid random scalar"))?;
+    let mut proof = Vec::new();
+    for point in [a_bar, b_bar, d] {
+        proof.extend(point.to_compressed());
+    }
+    for scalar in [e_tilde + e * challenge, r1_tilde - r1 * challenge, r3_tilde - r3 * challenge] {
+        proof.extend(scalar.to_bytes_be());
+    }
+    for (&j, m) in undisclosed.iter().zip(m_tilde) {
+        proof.extend((m + scalars[j] * challenge).to_bytes_be());
+    }
+    proof.extend(challenge.to_bytes_be());
+    Ok(proof)
+}
+
+/// Verify a proof of knowledge of a signature, given the disclosed messages
+/// and their (zero-based) indexes in the signed messages.
+///
+/// # Errors
+///
+/// Returns an error if the proof is not valid.
+pub fn proof_verify(
+    public_key: &PublicKey, proof: &[u8], header: &[u8], presentation_header: &[u8],
+    disclosed_messages: &[impl AsRef<[u8]>], disclosed_indexes: &[usize],
+) -> Result<()> {
+    const FIXED_LEN: usize = 3 * G1_LEN + 4 * SCALAR_LEN;
+    if proof.len() < FIXED_LEN || !(proof.len() - FIXED_LEN).is_multiple_of(SCALAR_LEN) {
+        bail!("proof has an invalid length");
+    }
+    if disclosed_messages.len() != disclosed_indexes.len() {
+        bail!("each disclosed message must have an index");
+    }
+    let count = disclosed_indexes.len() + (proof.len() - FIXED_LEN) / SCALAR_LEN;
+    if disclosed_indexes.windows(2).any(|pair| pair[0] >= pair[1])
+        || disclosed_indexes.last().is_some_and(|&i| i >= count)
+    {
+        bail!("disclosed indexes must be unique, sorted and in range");
+    }
+    let undisclosed = (0..count).filter(|i| !disclosed_indexes.contains(i)).collect::<Vec<_>>();
+
+    let (points, scalars) = proof.split_at(3 * G1_LEN);
+    let [a_bar, b_bar, d] = [0, 1, 2].map(|i| decode_point(&points[i * G1_LEN..(i + 1) * G1_LEN]));
+    let (a_bar, b_bar, d) = (a_bar?, b_bar?, d?);
+    let scalars = scalars.chunks(SCALAR_LEN).map(decode_scalar).collect::<Result<Vec<_>>>()?;
+    let (e_hat, r1_hat, r3_hat) = (scalars[0], scalars[1], scalars[2]);
+    let (commitments, challenge) = (&scalars[3..scalars.len() - 1], scalars[scalars.len() - 1]);
+
+    let (q1, h) = message_generators(count);
+    let domain = calculate_domain(public_key, &q1, &h, header);
+    let disclosed_scalars = disclosed_indexes
+        .iter()
+        .copied()
+        .zip(messages_to_scalars(disclosed_messages))
+        .collect::<Vec<_>>();
+
+    let t1 = b_bar * challenge + a_bar * e_hat + d * r1_hat;
+    let bv = commitment(&q1, &h, domain, disclosed_scalars.iter().map(|(i, m)| (*i, m)));
+    let t2 = undisclosed
+        .iter()
+        .zip(commitments)
+        .fold(bv * challenge + d * r3_hat, |t2, (&j, m)| t2 + h[j] * m);
+
+    let expected = calculate_challenge(
+        [&a_bar, &b_bar, &d, &t1, &t2],
+        domain,
+        &disclosed_scalars,
+        presentation_header,
+    );
+    if expected != challenge {
+        bail!("proof is not valid");
+    }
+
+    // e(Abar, W) = e(Bbar, BP2)
+    let lhs = pairing(&a_bar.to_affine(), &public_key.0.to_affine());
+    if lhs != pairing(&b_bar.to_affine(), &G2Affine::from(G2Projective::generator())) {
+        bail!("proof is not valid");
+    }
+    Ok(())
+}
+
+// The domain separation tag used for hashing to scalars.
+fn hash_to_scalar_dst() -> Vec<u8> {
+    [API_ID, b"H2S_"].concat()
+}
+
+// Map messages to scalars by hashing.
+fn messages_to_scalars(messages: &[impl AsRef<[u8]>]) -> Vec<Scalar> {
+    let dst = [API_ID, b"MAP_MSG_TO_SCALAR_AS_HASH_"].concat();
+    messages.iter().map(|message| hash_to_scalar(&[message.as_ref()], &dst)).collect()
+}
+
+// The generators for `count` messages: `Q_1` and `H_1` to `H_count`.
+fn message_generators(count: usize) -> (G1Projective, Vec<G1Projective>) {
+    let mut generators =
+        create_generators(count + 1, &[API_ID, b"MESSAGE_GENERATOR_SEED"].concat());
+    let q1 = generators.remove(0);
+    (q1, generators)
+}
+
+// Deterministically create generators from a seed by hashing to G1.
+fn create_generators(count: usize, seed: &[u8]) -> Vec<G1Projective> {
+    let seed_dst = [API_ID, b"SIG_GENERATOR_SEED_"].concat();
+    let generator_dst = [API_ID, b"SIG_GENERATOR_DST_"].concat();
+
+    let mut v = expand_message(&[seed], &seed_dst);
+    (1..=count)
+        .map(|i| {
+            v = expand_message(&[&v, &i2osp(i)], &seed_dst);
+            G1Projective::hash_to_curve(&v, &generator_dst, &[])
+        })
+        .collect()
+}
+
+// The domain: a hash binding the public key, generators and header.
+fn calculate_domain(
+    public_key: &PublicKey, q1: &G1Projective, h: &[G1Projective], header: &[u8],
+) -> Scalar {
+    let mut input = public_key.to_bytes().to_vec();
+    input.extend(i2osp(h.len()));
+    for point in std::iter::once(q1).chain(h) {
+        input.extend(point.to_compressed());
+    }
+    input.extend(API_ID);
+    input.extend(i2osp(header.len()));
+    input.extend(header);
+    hash_to_scalar(&[&input], &hash_to_scalar_dst())
+}
+
+// The challenge for a proof, binding the proof's commitments, the disclosed
+// messages and the presentation header.
+fn calculate_challenge(
+    points: [&G1Projective; 5], domain: Scalar, disclosed: &[(usize, Scalar)],
+    presentation_header: &[u8],
+) -> Scalar {
+    let mut input = i2osp(disclosed.len()).to_vec();
+    for (index, scalar) in disclosed {
+        input.extend(i2osp(*index));
+        input.extend(scalar.to_bytes_be());
+    }
+    for point in points {
+        input.extend(point.to_compressed());
+    }
+    input.extend(domain.to_bytes_be());
+    input.extend(i2osp(presentation_header.len()));
+    input.extend(presentation_header);
+    hash_to_scalar(&[&input], &hash_to_scalar_dst())
+}
+
+// `P1 + Q_1 * domain + H_i * m_i + ...` for the indexed message scalars.
+fn commitment<'a>(
+    q1: &G1Projective, h: &[G1Projective], domain: Scalar,
+    scalars: impl Iterator<Item = (usize, &'a Scalar)>,
+) -> G1Projective {
+    scalars.fold(*P1 + q1 * domain, |b, (i, m)| b + h[i] * m)
+}
+
+// Sort and check disclosed indexes.
+fn sorted_indexes(indexes: &[usize], count: usize) -> Result<Vec<usize>> {
+    let mut sorted = indexes.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    if sorted.last().is_some_and(|&i| i >= count) {
+        bail!("disclosed index is out of range");
+    }
+    Ok(sorted)
+}
+
+// Decode a signature into its point and scalar.
+fn decode_signature(signature: &[u8]) -> Result<(G1Projective, Scalar)> {
+    if signature.len() != SIGNATURE_LEN {
+        bail!("signature has an invalid length");
+    }
+    let a = decode_point(&signature[..G1_LEN])?;
+    let e = decode_scalar(&signature[G1_LEN..])?;
+    if e.is_zero_vartime() {
+        bail!("invalid signature");
+    }
+    Ok((a, e))
+}
+
+// Decode a compressed G1 point, rejecting the identity.
+fn decode_point(bytes: &[u8]) -> Result<G1Projective> {
+    let point = Option::<G1Affine>::from(G1Affine::from_compressed(bytes.try_into()?))
+        .ok_or_else(|| anyhow!("invalid G1 point"))?;
+    let point = G1Projective::from(point);
+    if bool::from(point.is_identity()) {
+        bail!("invalid G1 point");
+    }
+    Ok(point)
+}
+
+// Decode a big-endian scalar, rejecting values not less than the group order.
+fn decode_scalar(bytes: &[u8]) -> Result<Scalar> {
+    Option::from(Scalar::from_bytes_be(bytes.try_into()?)).ok_or_else(|| anyhow!("invalid scalar"))
+}
+
+// Hash to a scalar: interpret the output of `expand_message` as a big-endian
+// integer and reduce it modulo the group order.
+fn hash_to_scalar(message: &[&[u8]], dst: &[u8]) -> Scalar {
+    let bytes = expand_message(message, dst);
+
+    // reduce in 128-bit limbs, each less than the group order
+    let shift = Scalar::from_u128(u128::MAX) + Scalar::ONE;
+    bytes.chunks(16).fold(Scalar::ZERO, |scalar, limb| {
+        let mut be = [0; 16];
+        be.copy_from_slice(limb);
+        scalar * shift + Scalar::from_u128(u128::from_be_bytes(be))
+    })
+}
+
+// `expand_message_xmd` using SHA-256 (RFC 9380), producing `EXPAND_LEN`
+// bytes. The domain separation tag must be at most 255 bytes.
+fn expand_message(message: &[&[u8]], dst: &[u8]) -> [u8; EXPAND_LEN] {
+    #[allow(clippy::cast_possible_truncation)]
+    let dst_prime = [dst, &[dst.len() as u8]].concat();
+
+    let mut hasher = Sha256::new().chain_update([0; 64]);
+    for part in message {
+        hasher.update(part);
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    let b0 = hasher
+        .chain_update((EXPAND_LEN as u16).to_be_bytes())
+        .chain_update([0])
+        .chain_update(&dst_prime)
+        .finalize();
+
+    let b1 = Sha256::new().chain_update(b0).chain_update([1]).chain_update(&dst_prime).finalize();
+    let xored = b0.iter().zip(&b1).map(|(x, y)| x ^ y).collect::<Vec<_>>();
+    let b2 =
+        Sha256::new().chain_update(xored).chain_update([2]).chain_update(&dst_prime).finalize();
+
+    let mut uniform = [0; EXPAND_LEN];
+    uniform[..32].copy_from_slice(&b1);
+    uniform[32..].copy_from_slice(&b2[..EXPAND_LEN - 32]);
+    uniform
+}
+
+// Encode an integer as 8 big-endian bytes.
+const fn i2osp(n: usize) -> [u8; 8] {
+    (n as u64).to_be_bytes()
+}
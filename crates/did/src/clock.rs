@@ -0,0 +1,120 @@
+//! # Clock and Identifier Sources
+//!
+//! Abstractions over the current time and the generation of unique
+//! identifiers so DID documents and logs can be built (and resolved)
+//! deterministically.
+//!
+//! Builders and resolvers default to [`SystemClock`] and [`RandomIds`]. Use
+//! [`FixedClock`] or [`SteppingClock`] and [`SequentialIds`] to produce
+//! reproducible output, for example in golden tests.
+
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use uuid::Uuid;
+
+/// A source of the current time.
+pub trait Clock: Send + Sync {
+    /// The current date and time (UTC).
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// A source of unique identifiers.
+pub trait IdSource: Send + Sync {
+    /// Generate a new identifier.
+    fn new_id(&self) -> Uuid;
+}
+
+/// A [`Clock`] that reads the system time.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// An [`IdSource`] that generates random (v4) UUIDs.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RandomIds;
+
+impl IdSource for RandomIds {
+    fn new_id(&self) -> Uuid {
+        Uuid::new_v4()
+    }
+}
+
+/// A [`Clock`] that always returns the same time.
+#[derive(Clone, Copy, Debug)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// A [`Clock`] that starts at a fixed time and advances by a fixed step each
+/// time it is read.
+///
+/// Stepping ensures successive log entries have monotonically increasing
+/// version times.
+#[derive(Debug)]
+pub struct SteppingClock {
+    start: DateTime<Utc>,
+    step: TimeDelta,
+    ticks: AtomicI64,
+}
+
+impl SteppingClock {
+    /// Create a clock starting at `start` and advancing one second per read.
+    #[must_use]
+    pub const fn new(start: DateTime<Utc>) -> Self {
+        Self::with_step(start, TimeDelta::seconds(1))
+    }
+
+    /// Create a clock starting at `start` and advancing by `step` per read.
+    #[must_use]
+    pub const fn with_step(start: DateTime<Utc>, step: TimeDelta) -> Self {
+        Self {
+            start,
+            step,
+            ticks: AtomicI64::new(0),
+        }
+    }
+}
+
+impl Clock for SteppingClock {
+    fn now(&self) -> DateTime<Utc> {
+        let ticks = self.ticks.fetch_add(1, Ordering::SeqCst);
+        i32::try_from(ticks)
+            .ok()
+            .and_then(|t| self.step.checked_mul(t))
+            .and_then(|delta| self.start.checked_add_signed(delta))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+}
+
+/// An [`IdSource`] that generates sequential UUIDs starting at `1`.
+#[derive(Debug, Default)]
+pub struct SequentialIds {
+    next: AtomicU64,
+}
+
+impl SequentialIds {
+    /// Create a new sequential identifier source.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            next: AtomicU64::new(0),
+        }
+    }
+}
+
+impl IdSource for SequentialIds {
+    fn new_id(&self) -> Uuid {
+        let next = self.next.fetch_add(1, Ordering::SeqCst) + 1;
+        Uuid::from_u128(u128::from(next))
+    }
+}
@@ -0,0 +1,257 @@
+//! # Cryptosuites
+//!
+//! Key-specific operations for the JCS- and RDFC-based Data Integrity
+//! cryptosuites:
+//!
+//! - [`eddsa-jcs-2022`](https://www.w3.org/TR/vc-di-eddsa/#eddsa-jcs-2022)
+//!   and [`eddsa-rdfc-2022`](https://www.w3.org/TR/vc-di-eddsa/#eddsa-rdfc-2022)
+//!   for Ed25519 keys.
+//! - [`ecdsa-jcs-2019`](https://www.w3.org/TR/vc-di-ecdsa/#ecdsa-jcs-2019)
+//!   and [`ecdsa-rdfc-2019`](https://www.w3.org/TR/vc-di-ecdsa/#ecdsa-rdfc-2019)
+//!   for P-256 (SHA-256) and P-384 (SHA-384) keys.
+
+use anyhow::{Result, bail};
+use credibil_ecc::Algorithm;
+use credibil_jose::PublicKeyJwk;
+use multibase::Base;
+use p256::ecdsa::signature::Verifier;
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256, Sha384};
+
+use crate::rdf;
+
+/// Multicodec prefix for an Ed25519 public key.
+const ED25519_CODEC: [u8; 2] = [0xed, 0x01];
+
+/// Multicodec prefix for a (compressed) P-256 public key.
+const P256_CODEC: [u8; 2] = [0x80, 0x24];
+
+/// Multicodec prefix for a (compressed) P-384 public key.
+const P384_CODEC: [u8; 2] = [0x81, 0x24];
+
+/// The type of key used to create a Data Integrity proof. The key type
+/// determines the cryptosuite and the hash function used to construct the
+/// signing payload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofKey {
+    /// Ed25519 key (`eddsa-jcs-2022` or `eddsa-rdfc-2022`).
+    Ed25519,
+
+    /// P-256 key (`ecdsa-jcs-2019` or `ecdsa-rdfc-2019` with SHA-256).
+    P256,
+
+    /// P-384 key (`ecdsa-jcs-2019` or `ecdsa-rdfc-2019` with SHA-384).
+    P384,
+}
+
+impl ProofKey {
+    /// The proof key type for a signer's algorithm.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the algorithm is not supported by a JCS
+    /// cryptosuite.
+    pub fn from_algorithm(algorithm: &Algorithm) -> Result<Self> {
+        match algorithm {
+            Algorithm::EdDSA => Ok(Self::Ed25519),
+            Algorithm::ES256 => Ok(Self::P256),
+            Algorithm::ES384 => Ok(Self::P384),
+            _ => bail!("signing algorithm must be EdDSA, ES256 or ES384"),
+        }
+    }
+
+    /// The proof key type for a multibase-encoded (multikey) public key,
+    /// determined by the key's multicodec prefix.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is not base58btc multibase-encoded or the
+    /// key type is not supported.
+    pub fn from_multikey(multikey: &str) -> Result<Self> {
+        let bytes = decode(multikey)?;
+        match bytes.get(..2) {
+            Some(prefix) if prefix == ED25519_CODEC => Ok(Self::Ed25519),
+            Some(prefix) if prefix == P256_CODEC => Ok(Self::P256),
+            Some(prefix) if prefix == P384_CODEC => Ok(Self::P384),
+            _ => bail!("unsupported multikey type"),
+        }
+    }
+
+    /// The proof key type for a `did:key` verification method (of the form
+    /// `did:key:<multikey>#<multikey>`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the verification method has no key fragment or the
+    /// key type is not supported.
+    pub fn from_verification_method(verification_method: &str) -> Result<Self> {
+        let Some((_, multikey)) = verification_method.split_once('#') else {
+            bail!("verification method id has an unexpected format");
+        };
+        Self::from_multikey(multikey)
+    }
+
+    /// The name of the JCS cryptosuite for the key type.
+    #[must_use]
+    pub const fn jcs_cryptosuite(&self) -> &'static str {
+        match self {
+            Self::Ed25519 => "eddsa-jcs-2022",
+            Self::P256 | Self::P384 => "ecdsa-jcs-2019",
+        }
+    }
+
+    /// The name of the RDFC cryptosuite for the key type.
+    #[must_use]
+    pub const fn rdfc_cryptosuite(&self) -> &'static str {
+        match self {
+            Self::Ed25519 => "eddsa-rdfc-2022",
+            Self::P256 | Self::P384 => "ecdsa-rdfc-2019",
+        }
+    }
+
+    /// The JWS `alg` for signatures made with the key type.
+    #[must_use]
+    pub const fn jws_algorithm(&self) -> &'static str {
+        match self {
+            Self::Ed25519 => "EdDSA",
+            Self::P256 => "ES256",
+            Self::P384 => "ES384",
+        }
+    }
+
+    /// Hash data using the cryptosuite's hash function.
+    #[must_use]
+    pub fn hash(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Ed25519 | Self::P256 => Sha256::digest(data).to_vec(),
+            Self::P384 => Sha384::digest(data).to_vec(),
+        }
+    }
+
+    /// Generate the bytes to sign for a JCS cryptosuite: the hash of the
+    /// canonicalized proof configuration followed by the hash of the
+    /// canonicalized document.
+    ///
+    /// The proof configuration should not contain a `proofValue`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proof configuration or document cannot be
+    /// canonicalized.
+    pub fn jcs_payload(
+        &self, config: &impl Serialize, document: &impl Serialize,
+    ) -> Result<Vec<u8>> {
+        let config_hash = self.hash(serde_json_canonicalizer::to_string(config)?.as_bytes());
+        let data_hash = self.hash(serde_json_canonicalizer::to_string(document)?.as_bytes());
+        Ok([config_hash, data_hash].concat())
+    }
+
+    /// Generate the bytes to sign for an RDFC cryptosuite: the hash of the
+    /// RDFC-1.0 canonicalized proof configuration followed by the hash of the
+    /// RDFC-1.0 canonicalized document.
+    ///
+    /// The proof configuration should not contain a `proofValue` and should
+    /// share the document's `@context`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proof configuration or document cannot be
+    /// expanded or canonicalized.
+    pub fn rdfc_payload(&self, config: &Value, document: &Value) -> Result<Vec<u8>> {
+        let config_hash = self.hash(rdf::canonicalize(config)?.as_bytes());
+        let data_hash = self.hash(rdf::canonicalize(document)?.as_bytes());
+        Ok([config_hash, data_hash].concat())
+    }
+
+    /// Encode a signer's public key as a multikey.
+    ///
+    /// ECDSA keys may be SEC1 compressed or uncompressed and are encoded in
+    /// compressed form.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is not a valid key of this type.
+    pub fn multikey(&self, public_key: &[u8]) -> Result<String> {
+        let compressed = match self {
+            Self::Ed25519 => return PublicKeyJwk::from_bytes(public_key)?.to_multibase(),
+            Self::P256 => {
+                let key = p256::PublicKey::from_sec1_bytes(public_key)?;
+                [&P256_CODEC[..], key.to_encoded_point(true).as_bytes()].concat()
+            }
+            Self::P384 => {
+                let key = p384::PublicKey::from_sec1_bytes(public_key)?;
+                [&P384_CODEC[..], key.to_encoded_point(true).as_bytes()].concat()
+            }
+        };
+        Ok(multibase::encode(Base::Base58Btc, compressed))
+    }
+
+    /// Encode a JWK public key as a multikey. Ed25519 (`OKP`), P-256 and
+    /// P-384 (`EC`) keys are supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key type is not supported or the key is not
+    /// valid.
+    pub fn jwk_multikey(jwk: &PublicKeyJwk) -> Result<String> {
+        let value = serde_json::to_value(jwk)?;
+        let field = |name: &str| value.get(name).and_then(Value::as_str);
+
+        let proof_key = match (field("kty"), field("crv")) {
+            (Some("OKP"), Some("Ed25519")) => return jwk.to_multibase(),
+            (Some("EC"), Some("P-256")) => Self::P256,
+            (Some("EC"), Some("P-384")) => Self::P384,
+            _ => bail!("unsupported JWK key type"),
+        };
+        let (Some(x), Some(y)) = (field("x"), field("y")) else {
+            bail!("EC key is missing a coordinate");
+        };
+
+        // uncompressed SEC1 point
+        let point = [vec![0x04], Base::Base64Url.decode(x)?, Base::Base64Url.decode(y)?].concat();
+        proof_key.multikey(&point)
+    }
+
+    /// Verify a signature over the payload using a multikey public key of
+    /// this type.
+    ///
+    /// ECDSA signatures are the fixed-size concatenation of `r` and `s`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is not of this type or the signature is
+    /// invalid.
+    pub fn verify(&self, multikey: &str, payload: &[u8], signature: &[u8]) -> Result<()> {
+        if Self::from_multikey(multikey)? != *self {
+            bail!("verification key is not a {self:?} key");
+        }
+
+        match self {
+            Self::Ed25519 => {
+                PublicKeyJwk::from_multibase(multikey)?.verify_bytes(payload, signature)?;
+            }
+            Self::P256 => {
+                let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(&decode(multikey)?[2..])?;
+                let signature = p256::ecdsa::Signature::from_slice(signature)?;
+                key.verify(payload, &signature)?;
+            }
+            Self::P384 => {
+                let key = p384::ecdsa::VerifyingKey::from_sec1_bytes(&decode(multikey)?[2..])?;
+                let signature = p384::ecdsa::Signature::from_slice(signature)?;
+                key.verify(payload, &signature)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Decode a base58btc multibase string.
+fn decode(multikey: &str) -> Result<Vec<u8>> {
+    let (base, bytes) = multibase::decode(multikey)?;
+    if base != Base::Base58Btc {
+        bail!("unsupported multibase encoding");
+    }
+    Ok(bytes)
+}
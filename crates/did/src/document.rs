@@ -15,7 +15,7 @@ use serde_json::Value;
 
 use crate::KeyFormat;
 use crate::service::{Service, ServiceBuilder};
-use crate::verification::{VerificationMethod, VerificationMethodBuilder};
+use crate::verification::{KeyPurpose, VerificationMethod, VerificationMethodBuilder};
 
 // TODO: set context based on key format:
 // - Ed25519VerificationKey2020	https://w3id.org/security/suites/ed25519-2020/v1
@@ -133,6 +133,33 @@ impl Document {
     pub fn verification_method(&self, id: &str) -> Option<&VerificationMethod> {
         self.verification_method.as_ref()?.iter().find(|vm| vm.id == id)
     }
+
+    /// Retrieve a verification method by its ID, provided it is authorized for
+    /// `purpose` (its verification relationship).
+    ///
+    /// The method may be embedded in the relationship or referenced by (full
+    /// or relative) ID. Every method in the document's `verification_method`
+    /// is authorized for [`KeyPurpose::VerificationMethod`].
+    #[must_use]
+    pub fn authorized_method(&self, id: &str, purpose: &KeyPurpose) -> Option<&VerificationMethod> {
+        let relationship = match purpose {
+            KeyPurpose::VerificationMethod => return self.verification_method(id),
+            KeyPurpose::Authentication => &self.authentication,
+            KeyPurpose::AssertionMethod => &self.assertion_method,
+            KeyPurpose::KeyAgreement => &self.key_agreement,
+            KeyPurpose::CapabilityInvocation => &self.capability_invocation,
+            KeyPurpose::CapabilityDelegation => &self.capability_delegation,
+        };
+        let relative = id.strip_prefix(&self.id);
+
+        relationship.as_ref()?.iter().find_map(|kind| match kind {
+            Kind::Object(vm) if vm.id == id => Some(vm),
+            Kind::String(reference) if reference == id || relative == Some(reference.as_str()) => {
+                self.verification_method(id)
+            }
+            _ => None,
+        })
+    }
 }
 
 /// DID Document builder.
@@ -186,6 +213,12 @@ impl DocumentBuilder<FromScratch> {
     }
 
     /// Creates a new `DocumentBuilder` from an existing `Document`.
+    ///
+    /// Changes are applied on top of the existing document: verification
+    /// methods, relationships and services are added to those it already
+    /// has, while `alsoKnownAs` and `controller` are only replaced when set.
+    /// Metadata is reset, keeping the document's `created` time, unless set
+    /// using [`DocumentBuilder::metadata`].
     #[must_use]
     pub const fn from(document: Document) -> DocumentBuilder<FromDocument> {
         DocumentBuilder {
@@ -413,12 +446,12 @@ impl DocumentBuilder<FromScratch> {
     ///
     /// Will fail if the document is missing required fields or if
     /// verification methods are not properly set up.
-    pub(crate) fn build(self, did: impl Into<String>) -> Result<Document> {
+    pub(crate) fn build(self, did: impl Into<String>, now: DateTime<Utc>) -> Result<Document> {
         let document = Document {
             id: did.into(),
             ..Document::default()
         };
-        self.inner_build(document)
+        self.inner_build(document, now)
     }
 }
 
@@ -430,14 +463,14 @@ impl DocumentBuilder<FromDocument> {
     ///
     /// Will fail if the document is missing required fields or if
     /// verification methods are not properly set up.
-    pub(crate) fn build(self) -> Result<Document> {
+    pub(crate) fn build(self, now: DateTime<Utc>) -> Result<Document> {
         let document = self.document.0.clone();
-        self.inner_build(document)
+        self.inner_build(document, now)
     }
 }
 
 impl<D> DocumentBuilder<D> {
-    fn inner_build(self, mut document: Document) -> Result<Document> {
+    fn inner_build(self, mut document: Document, now: DateTime<Utc>) -> Result<Document> {
         let did = document.id.clone();
 
         // verification methods
@@ -460,11 +493,22 @@ impl<D> DocumentBuilder<D> {
             }
         }
 
-        document.assertion_method = to_vm(&did, self.assertion_method)?;
-        document.authentication = to_vm(&did, self.authentication)?;
-        document.key_agreement = to_vm(&did, self.key_agreement)?;
-        document.capability_invocation = to_vm(&did, self.capability_invocation)?;
-        document.capability_delegation = to_vm(&did, self.capability_delegation)?;
+        // relationships are added to any the document already has, skipping
+        // methods it already references
+        for (existing, added) in [
+            (&mut document.assertion_method, self.assertion_method),
+            (&mut document.authentication, self.authentication),
+            (&mut document.key_agreement, self.key_agreement),
+            (&mut document.capability_invocation, self.capability_invocation),
+            (&mut document.capability_delegation, self.capability_delegation),
+        ] {
+            for vm in to_vm(&did, added)?.unwrap_or_default() {
+                let existing = existing.get_or_insert(vec![]);
+                if !existing.iter().any(|e| kind_id(e) == kind_id(&vm)) {
+                    existing.push(vm);
+                }
+            }
+        }
 
         // services
         if let Some(builders) = self.service {
@@ -474,11 +518,22 @@ impl<D> DocumentBuilder<D> {
             }
         }
 
-        document.also_known_as = self.also_known_as;
-        document.controller = self.controller;
+        if self.also_known_as.is_some() {
+            document.also_known_as = self.also_known_as;
+        }
+        if self.controller.is_some() {
+            document.controller = self.controller;
+        }
 
-        let mut metadata = self.metadata.unwrap_or_default();
-        metadata.updated = Some(chrono::Utc::now());
+        // keep the creation time (a new document is created now) unless the
+        // metadata is replaced: other metadata describes a resolved version so
+        // is not carried forward
+        let existing = document.did_document_metadata.take();
+        let mut metadata = self.metadata.unwrap_or_else(|| DocumentMetadata {
+            created: existing.map_or(now, |md| md.created),
+            ..DocumentMetadata::default()
+        });
+        metadata.updated = Some(now);
         document.did_document_metadata = Some(metadata);
 
         if let Some(context) = self.context {
@@ -489,6 +544,14 @@ impl<D> DocumentBuilder<D> {
     }
 }
 
+// The ID of a verification method, whether embedded or referenced.
+fn kind_id(vm: &Kind<VerificationMethod>) -> &str {
+    match vm {
+        Kind::Object(vm) => &vm.id,
+        Kind::String(id) => id,
+    }
+}
+
 fn to_vm(
     did: &str, vms: Option<Vec<Kind<VerificationMethodBuilder>>>,
 ) -> Result<Option<Vec<Kind<VerificationMethod>>>> {
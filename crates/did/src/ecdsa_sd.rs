@@ -0,0 +1,332 @@
+//! # ECDSA Selective Disclosure Cryptosuite
+//!
+//! The [`ecdsa-sd-2023`] Data Integrity cryptosuite, providing selective
+//! disclosure using ECDSA signatures over P-256.
+//!
+//! - The issuer adds a base proof to a document using [`add_base_proof`].
+//!   Each of the document's non-mandatory canonical statements is signed
+//!   with an ephemeral key, and the issuer's P-256 signer signs the ephemeral
+//!   public key together with the mandatory statements. JSON Pointers identify the
+//!   mandatory statements the holder must always disclose.
+//! - The holder uses [`derive_proof`] to select the statements to disclose,
+//!   creating a new document secured by a derived proof carrying only the
+//!   signatures for the disclosed statements.
+//! - The verifier checks the derived proof using [`verify_derived_proof`]
+//!   and the issuer's P-256 multikey.
+//!
+//! Unlike `bbs-2023`, derived proofs share the issuer's signatures and so
+//! can be correlated.
+//!
+//! Documents are converted to RDF offline, so may only reference the
+//! contexts bundled with [`crate::rdf`].
+//!
+//! [`ecdsa-sd-2023`]: https://www.w3.org/TR/vc-di-ecdsa/#ecdsa-sd-2023
+
+mod proof_value;
+
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow, bail};
+use credibil_ecc::Signer;
+use hmac::{Hmac, Mac};
+use multibase::Base;
+use p256::ecdsa::signature::Signer as _;
+use p256::ecdsa::{Signature, SigningKey};
+use rand::RngCore;
+use rand::rngs::OsRng;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use self::proof_value::{BaseProof, DerivedProof};
+use crate::{Proof, ProofKey, rdf};
+
+/// The name of the cryptosuite.
+pub const CRYPTOSUITE: &str = "ecdsa-sd-2023";
+
+/// Proof type for Data Integrity proofs.
+const DATA_INTEGRITY_PROOF: &str = "DataIntegrityProof";
+
+/// Options used when creating a base proof.
+#[derive(Clone, Debug, Default)]
+pub struct BaseOptions {
+    /// JSON Pointers to the parts of the document the holder must always
+    /// disclose.
+    pub mandatory_pointers: Vec<String>,
+
+    /// The key used to randomize blank node labels. A random key is
+    /// generated when not set.
+    pub hmac_key: Option<[u8; 32]>,
+}
+
+/// Add an `ecdsa-sd-2023` base proof to a JSON-LD document, signed by the
+/// issuer's P-256 signer.
+///
+/// The proof configuration provides the proof's properties (type,
+/// cryptosuite, verification method, purpose, etc.) and is returned with
+/// the base proof value on the secured document.
+///
+/// # Errors
+///
+/// Returns an error if the document already has a proof, the configuration
+/// is not for an `ecdsa-sd-2023` Data Integrity proof, the signer does not
+/// use a P-256 key, a mandatory pointer does not match the document, or the
+/// document cannot be canonicalized.
+pub async fn add_base_proof(
+    document: &Value, config: &Proof, signer: &impl Signer, options: &BaseOptions,
+) -> Result<Value> {
+    if !document.is_object() {
+        bail!("document must be a JSON object");
+    }
+    if document.get("proof").is_some() {
+        bail!("document is already secured");
+    }
+    check_config(config)?;
+    if ProofKey::from_algorithm(&signer.algorithm().await?)? != ProofKey::P256 {
+        bail!("`{CRYPTOSUITE}` proofs must be signed using a P-256 key");
+    }
+
+    let hmac_key = options.hmac_key.unwrap_or_else(|| {
+        let mut key = [0; 32];
+        OsRng.fill_bytes(&mut key);
+        key
+    });
+
+    // group the canonical statements into mandatory and non-mandatory
+    let grouped = rdf::canonicalize_and_group(
+        document,
+        hmac_labels(&hmac_key),
+        &[&options.mandatory_pointers],
+    )?;
+    let mandatory = &grouped.groups[0];
+
+    // sign each non-mandatory statement with an ephemeral key
+    let ephemeral_key = SigningKey::random(&mut OsRng);
+    let signatures = mandatory
+        .non_matching
+        .values()
+        .map(|nquad| {
+            let signature: Signature = ephemeral_key.sign(nquad.as_bytes());
+            signature.to_vec()
+        })
+        .collect();
+    let public_key = multikey_bytes(ephemeral_key.verifying_key())?;
+
+    let to_sign = [
+        proof_hash(document, config)?,
+        public_key.clone(),
+        Sha256::digest(mandatory.matching.values().cloned().collect::<String>()).to_vec(),
+    ]
+    .concat();
+    let base_signature = Signature::from_slice(&signer.try_sign(&to_sign).await?)?;
+
+    let base_proof = BaseProof {
+        base_signature: base_signature.to_vec(),
+        public_key,
+        hmac_key: hmac_key.to_vec(),
+        signatures,
+        mandatory_pointers: options.mandatory_pointers.clone(),
+    };
+
+    let mut proof = config.clone();
+    proof.proof_value = Some(base_proof.encode()?);
+    let mut secured = document.clone();
+    secured["proof"] = serde_json::to_value(&proof)?;
+    Ok(secured)
+}
+
+/// Derive a proof from a document secured with an `ecdsa-sd-2023` base
+/// proof, disclosing the mandatory statements and those selected by the
+/// holder's JSON Pointers.
+///
+/// Returns the document reduced to the disclosed parts and secured by the
+/// derived proof.
+///
+/// # Errors
+///
+/// Returns an error if the document does not have an `ecdsa-sd-2023` base
+/// proof, a pointer does not match the document, or nothing is disclosed.
+pub fn derive_proof(document: &Value, selective_pointers: &[String]) -> Result<Value> {
+    let mut proof: Proof = match document.get("proof") {
+        Some(proof @ Value::Object(_)) => serde_json::from_value(proof.clone())?,
+        Some(_) => bail!("document must have a single proof"),
+        None => bail!("document has no proof"),
+    };
+    check_config(&proof)?;
+    let Some(proof_value) = &proof.proof_value else {
+        bail!("proof value is missing");
+    };
+    let base = BaseProof::decode(proof_value)?;
+
+    let mut unsecured = document.clone();
+    if let Value::Object(map) = &mut unsecured {
+        map.remove("proof");
+    }
+
+    let mandatory_pointers = &base.mandatory_pointers;
+    let combined_pointers = [mandatory_pointers.as_slice(), selective_pointers].concat();
+    let grouped = rdf::canonicalize_and_group(
+        &unsecured,
+        hmac_labels(&base.hmac_key),
+        &[mandatory_pointers, selective_pointers, &combined_pointers],
+    )?;
+    let (mandatory, selective, combined) =
+        (&grouped.groups[0], &grouped.groups[1], &grouped.groups[2]);
+
+    // mandatory statements are identified by their index in the disclosed
+    // statements
+    let combined_indexes = combined.matching.keys().copied().collect::<Vec<_>>();
+    let mandatory_indexes = mandatory
+        .matching
+        .keys()
+        .filter_map(|index| combined_indexes.iter().position(|i| i == index))
+        .collect::<Vec<_>>();
+
+    // keep the signatures for the selected non-mandatory statements
+    if base.signatures.len() != mandatory.non_matching.len() {
+        bail!("base proof signatures do not match the document");
+    }
+    let signatures = mandatory
+        .non_matching
+        .keys()
+        .zip(base.signatures)
+        .filter(|(index, _)| selective.matching.contains_key(index))
+        .map(|(_, signature)| signature)
+        .collect();
+
+    // map the verifier's canonical blank node labels to the signed labels
+    let label_map = rdf::canonical_labels(&combined.deskolemized)?
+        .into_iter()
+        .map(|(input, c14n)| {
+            let label = grouped
+                .label_map
+                .get(&input)
+                .ok_or_else(|| anyhow!("blank node {input} is not in the label map"))?;
+            Ok((c14n, label.clone()))
+        })
+        .collect::<Result<_>>()?;
+
+    let Some(mut reveal) = rdf::select_json_ld(&unsecured, &combined_pointers)? else {
+        bail!("derived proof must disclose at least one statement");
+    };
+
+    let derived = DerivedProof {
+        base_signature: base.base_signature,
+        public_key: base.public_key,
+        signatures,
+        label_map,
+        mandatory_indexes,
+    };
+    proof.proof_value = Some(derived.encode()?);
+    reveal["proof"] = serde_json::to_value(&proof)?;
+    Ok(reveal)
+}
+
+/// Verify an `ecdsa-sd-2023` derived proof over a document (without its
+/// proof) using the issuer's P-256 multikey.
+///
+/// # Errors
+///
+/// Returns an error if the proof is a base proof or is not valid for the
+/// document.
+pub fn verify_derived_proof(document: &Value, proof: &Proof, multikey: &str) -> Result<()> {
+    check_config(proof)?;
+    let Some(proof_value) = &proof.proof_value else {
+        bail!("proof value is missing");
+    };
+    if proof_value::is_base_proof(proof_value) {
+        bail!("base proofs cannot be verified: the holder must derive a proof");
+    }
+    let derived = DerivedProof::decode(proof_value)?;
+
+    // relabel the document's blank nodes with the signed labels
+    let quads = rdf::to_rdf(&rdf::expand(document)?)?;
+    let (_, nquads) = rdf::label_replacement_canonicalize(&quads, |canonical| {
+        canonical
+            .iter()
+            .map(|(input, c14n)| {
+                let label = derived
+                    .label_map
+                    .get(c14n)
+                    .ok_or_else(|| anyhow!("blank node {c14n} is not in the label map"))?;
+                Ok((input.clone(), label.clone()))
+            })
+            .collect()
+    })?;
+
+    let mut mandatory = String::new();
+    let mut non_mandatory = Vec::new();
+    for (index, nquad) in nquads.iter().enumerate() {
+        if derived.mandatory_indexes.contains(&index) {
+            mandatory.push_str(nquad);
+        } else {
+            non_mandatory.push(nquad);
+        }
+    }
+    if derived.signatures.len() != non_mandatory.len() {
+        bail!("derived proof signatures do not match the disclosed statements");
+    }
+
+    // the issuer signed the ephemeral key and the mandatory statements
+    let to_verify = [
+        proof_hash(document, proof)?,
+        derived.public_key.clone(),
+        Sha256::digest(mandatory).to_vec(),
+    ]
+    .concat();
+    ProofKey::P256.verify(multikey, &to_verify, &derived.base_signature)?;
+
+    // the ephemeral key signed each disclosed statement
+    let ephemeral_key = multibase::encode(Base::Base58Btc, &derived.public_key);
+    for (nquad, signature) in non_mandatory.iter().zip(&derived.signatures) {
+        ProofKey::P256.verify(&ephemeral_key, nquad.as_bytes(), signature)?;
+    }
+    Ok(())
+}
+
+// Check the proof (configuration) is an `ecdsa-sd-2023` Data Integrity
+// proof.
+fn check_config(proof: &Proof) -> Result<()> {
+    if proof.type_ != DATA_INTEGRITY_PROOF || proof.cryptosuite.as_deref() != Some(CRYPTOSUITE) {
+        bail!("proof must be an `{CRYPTOSUITE}` {DATA_INTEGRITY_PROOF}");
+    }
+    Ok(())
+}
+
+// The hash of the canonicalized proof configuration: the proof without its
+// value, sharing the document's `@context`.
+fn proof_hash(document: &Value, proof: &Proof) -> Result<Vec<u8>> {
+    let mut config = serde_json::to_value(proof)?;
+    if let Value::Object(map) = &mut config {
+        map.remove("proofValue");
+        if let Some(context) = document.get("@context") {
+            map.insert("@context".to_string(), context.clone());
+        }
+    }
+    Ok(Sha256::digest(rdf::canonicalize(&config)?).to_vec())
+}
+
+// The multikey bytes (multicodec prefix and compressed point) of a P-256
+// public key.
+fn multikey_bytes(verifying_key: &p256::ecdsa::VerifyingKey) -> Result<Vec<u8>> {
+    let point = verifying_key.to_encoded_point(true);
+    let (_, bytes) = multibase::decode(ProofKey::P256.multikey(point.as_bytes())?)?;
+    Ok(bytes)
+}
+
+// Label blank nodes using the base64url-encoded HMAC of their canonical
+// labels, so the labels do not reveal the document's structure.
+fn hmac_labels(
+    hmac_key: &[u8],
+) -> impl FnOnce(&HashMap<String, String>) -> Result<HashMap<String, String>> + '_ {
+    move |canonical| {
+        canonical
+            .iter()
+            .map(|(input, c14n)| {
+                let mut mac = Hmac::<Sha256>::new_from_slice(hmac_key)?;
+                mac.update(c14n.as_bytes());
+                let label = multibase::encode(Base::Base64Url, mac.finalize().into_bytes());
+                Ok((input.clone(), label))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,180 @@
+//! # Proof Values
+//!
+//! Serialization of `ecdsa-sd-2023` base and derived proof values: a
+//! three-byte header identifying the proof type followed by the
+//! CBOR-encoded proof components, multibase-encoded using base64url (no
+//! padding).
+
+use std::collections::BTreeMap;
+
+use anyhow::{Result, anyhow, bail};
+use ciborium::Value;
+use multibase::Base;
+
+/// Header for a base proof.
+const BASE_HEADER: [u8; 3] = [0xd9, 0x5d, 0x00];
+
+/// Header for a derived proof.
+const DERIVED_HEADER: [u8; 3] = [0xd9, 0x5d, 0x01];
+
+/// The components of a base proof, created by the issuer.
+#[derive(Clone, Debug)]
+pub struct BaseProof {
+    pub base_signature: Vec<u8>,
+
+    /// The ephemeral P-256 public key, as multikey bytes.
+    pub public_key: Vec<u8>,
+
+    pub hmac_key: Vec<u8>,
+
+    /// Signatures over each non-mandatory statement, made with the
+    /// ephemeral key.
+    pub signatures: Vec<Vec<u8>>,
+
+    pub mandatory_pointers: Vec<String>,
+}
+
+impl BaseProof {
+    pub fn encode(&self) -> Result<String> {
+        let components = vec![
+            Value::Bytes(self.base_signature.clone()),
+            Value::Bytes(self.public_key.clone()),
+            Value::Bytes(self.hmac_key.clone()),
+            Value::Array(self.signatures.iter().cloned().map(Value::Bytes).collect()),
+            Value::Array(self.mandatory_pointers.iter().cloned().map(Value::Text).collect()),
+        ];
+        encode(BASE_HEADER, components)
+    }
+
+    pub fn decode(proof_value: &str) -> Result<Self> {
+        let Ok([base_signature, public_key, hmac_key, signatures, mandatory_pointers]) =
+            <[Value; 5]>::try_from(decode(BASE_HEADER, proof_value)?)
+        else {
+            bail!("base proof must have 5 components");
+        };
+        Ok(Self {
+            base_signature: bytes(base_signature)?,
+            public_key: bytes(public_key)?,
+            hmac_key: bytes(hmac_key)?,
+            signatures: array(signatures)?.into_iter().map(bytes).collect::<Result<_>>()?,
+            mandatory_pointers: array(mandatory_pointers)?
+                .into_iter()
+                .map(|pointer| pointer.into_text().map_err(|_| anyhow!("pointer must be text")))
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+/// The components of a derived proof, created by the holder.
+#[derive(Clone, Debug)]
+pub struct DerivedProof {
+    pub base_signature: Vec<u8>,
+    pub public_key: Vec<u8>,
+
+    /// Signatures over the disclosed non-mandatory statements.
+    pub signatures: Vec<Vec<u8>>,
+
+    /// Map from canonical blank node labels (`c14n<n>`) to the HMAC-based
+    /// labels used when signing (`u<base64url>`).
+    pub label_map: BTreeMap<String, String>,
+
+    pub mandatory_indexes: Vec<usize>,
+}
+
+impl DerivedProof {
+    pub fn encode(&self) -> Result<String> {
+        // labels are compressed to their numeric suffix and HMAC digest
+        let label_map = self
+            .label_map
+            .iter()
+            .map(|(c14n, label)| {
+                let Some(index) = c14n.strip_prefix("c14n").and_then(|i| i.parse::<usize>().ok())
+                else {
+                    bail!("unexpected blank node label: {c14n}");
+                };
+                let Some(digest) = label.strip_prefix('u') else {
+                    bail!("unexpected blank node label: {label}");
+                };
+                Ok((Value::Integer(index.into()), Value::Bytes(Base::Base64Url.decode(digest)?)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let components = vec![
+            Value::Bytes(self.base_signature.clone()),
+            Value::Bytes(self.public_key.clone()),
+            Value::Array(self.signatures.iter().cloned().map(Value::Bytes).collect()),
+            Value::Map(label_map),
+            Value::Array(
+                self.mandatory_indexes.iter().map(|&i| Value::Integer(i.into())).collect(),
+            ),
+        ];
+        encode(DERIVED_HEADER, components)
+    }
+
+    pub fn decode(proof_value: &str) -> Result<Self> {
+        let Ok([base_signature, public_key, signatures, label_map, mandatory_indexes]) =
+            <[Value; 5]>::try_from(decode(DERIVED_HEADER, proof_value)?)
+        else {
+            bail!("derived proof must have 5 components");
+        };
+
+        let label_map = label_map
+            .into_map()
+            .map_err(|_| anyhow!("label map must be a map"))?
+            .into_iter()
+            .map(|(key, value)| {
+                let label = multibase::encode(Base::Base64Url, bytes(value)?);
+                Ok((format!("c14n{}", index(key)?), label))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            base_signature: bytes(base_signature)?,
+            public_key: bytes(public_key)?,
+            signatures: array(signatures)?.into_iter().map(bytes).collect::<Result<_>>()?,
+            label_map,
+            mandatory_indexes: array(mandatory_indexes)?
+                .into_iter()
+                .map(index)
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+/// Whether a proof value is a base proof.
+pub fn is_base_proof(proof_value: &str) -> bool {
+    multibase::decode(proof_value).is_ok_and(|(_, bytes)| bytes.starts_with(&BASE_HEADER))
+}
+
+// Encode the proof components with the header.
+fn encode(header: [u8; 3], components: Vec<Value>) -> Result<String> {
+    let mut bytes = header.to_vec();
+    ciborium::into_writer(&Value::Array(components), &mut bytes)?;
+    Ok(multibase::encode(Base::Base64Url, bytes))
+}
+
+// Decode a proof value's components, checking its header.
+fn decode(header: [u8; 3], proof_value: &str) -> Result<Vec<Value>> {
+    let (base, bytes) = multibase::decode(proof_value)?;
+    if base != Base::Base64Url {
+        bail!("proof value must be base64url-encoded");
+    }
+    let Some(cbor) = bytes.strip_prefix(&header) else {
+        bail!("proof value has an unexpected header");
+    };
+    let value: Value = ciborium::from_reader(cbor)?;
+    array(value)
+}
+
+fn bytes(value: Value) -> Result<Vec<u8>> {
+    value.into_bytes().map_err(|_| anyhow!("proof component must be a byte string"))
+}
+
+fn array(value: Value) -> Result<Vec<Value>> {
+    value.into_array().map_err(|_| anyhow!("proof component must be an array"))
+}
+
+fn index(value: Value) -> Result<usize> {
+    let integer = value.into_integer().map_err(|_| anyhow!("index must be an integer"))?;
+    Ok(usize::try_from(integer)?)
+}
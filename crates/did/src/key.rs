@@ -10,8 +10,9 @@
 //! - <https://w3c.github.io/did-resolution>
 
 use anyhow::{Result, bail};
+use credibil_core::Kind;
 
-use crate::{KeyFormat, Method, Resource, Url, VerificationMethod};
+use crate::{CONTEXT, Document, KeyFormat, Method, Resource, Url, VerificationMethod};
 
 /// Convert a `did:key` URL into a [`VerificationMethod`] object.
 ///
@@ -36,3 +37,37 @@ pub fn resolve(url: &Url) -> Result<Resource> {
     };
     Ok(Resource::VerificationMethod(vm))
 }
+
+/// Expand a `did:key` DID (or DID URL) into its DID document.
+///
+/// The document contains a single verification method for the key, which is
+/// authorized for every verification relationship except key agreement.
+///
+/// # Errors
+/// If the URL is not a valid `did:key` URL, an error is returned.
+pub fn document(url: &Url) -> Result<Document> {
+    if url.method != Method::Key {
+        bail!("DID is not a valid did:key: {url}");
+    }
+    let did = url.did();
+    let vm = VerificationMethod {
+        context: None,
+        id: format!("{did}#{}", url.id),
+        controller: did.clone(),
+        key: KeyFormat::Multikey {
+            public_key_multibase: url.id.clone(),
+        },
+    };
+    let relationship = || Some(vec![Kind::String(vm.id.clone())]);
+
+    Ok(Document {
+        context: CONTEXT.iter().map(|ctx| Kind::String((*ctx).to_string())).collect(),
+        id: did,
+        authentication: relationship(),
+        assertion_method: relationship(),
+        capability_invocation: relationship(),
+        capability_delegation: relationship(),
+        verification_method: Some(vec![vm]),
+        ..Document::default()
+    })
+}
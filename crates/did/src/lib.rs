@@ -8,10 +8,15 @@
 //!
 //! See [DID resolution](https://www.w3.org/TR/did-core/#did-resolution) fpr more.
 
+pub mod bbs;
+pub mod ecdsa_sd;
 pub mod key;
+pub mod rdf;
 pub mod web;
 pub mod webvh;
 
+mod clock;
+mod cryptosuite;
 mod document;
 mod proof;
 mod resolve;
@@ -24,7 +29,10 @@ use std::str::FromStr;
 
 use anyhow::anyhow;
 
+pub use self::clock::*;
+pub use self::cryptosuite::ProofKey;
 pub use self::document::*;
+pub use self::proof::{OneOrMany, Proof, order_proofs};
 pub use self::resolve::{Resource, resource};
 pub use self::service::*;
 pub use self::url::{QueryParams, Url};
@@ -2,9 +2,11 @@
 //!
 //! [W3C Data Integrity 1.0 Report](https://www.w3.org/community/reports/credentials/CG-FINAL-data-integrity-20220722)
 
+use std::collections::HashMap;
 use std::convert::Infallible;
 use std::str::FromStr;
 
+use anyhow::bail;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -86,6 +88,11 @@ pub struct Proof {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub proof_value: Option<String>,
 
+    /// A detached JWS over the proof options and document, used by the
+    /// legacy `JsonWebSignature2020` proof type in place of `proofValue`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jws: Option<String>,
+
     /// Each value identifies another data integrity proof that MUST verify
     /// before the current proof is processed.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -103,6 +110,78 @@ pub struct Proof {
     // pub extra: Option<HashMap<String, Value>>,
 }
 
+impl Proof {
+    /// The identifiers of the proofs that must verify before this proof (its
+    /// `previousProof` references).
+    #[must_use]
+    pub fn previous_proofs(&self) -> Vec<String> {
+        self.previous_proof.as_ref().map(OneOrMany::to_vec).unwrap_or_default()
+    }
+}
+
+/// Order a proof set or proof chain for verification.
+///
+/// Proofs in a set are independent and keep their order. Proofs in a chain
+/// reference earlier proofs by `id` using `previousProof` and are ordered so
+/// each proof follows the proofs it references.
+///
+/// # Errors
+///
+/// Returns an error if proof identifiers are repeated, if a `previousProof`
+/// references a proof that is not in the set, or if the references form a
+/// cycle.
+pub fn order_proofs(proofs: &[Proof]) -> anyhow::Result<Vec<&Proof>> {
+    let mut by_id = HashMap::new();
+    for (index, proof) in proofs.iter().enumerate() {
+        if let Some(id) = &proof.id {
+            if by_id.insert(id.as_str(), index).is_some() {
+                bail!("proof id {id} is repeated");
+            }
+        }
+    }
+
+    // depth-first traversal of each proof's previous proofs, tracking the
+    // proofs on the current path to detect cycles
+    let mut state = vec![Visit::Pending; proofs.len()];
+    let mut ordered = Vec::with_capacity(proofs.len());
+    for index in 0..proofs.len() {
+        visit(proofs, &by_id, index, &mut state, &mut ordered)?;
+    }
+    Ok(ordered)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Visit {
+    Pending,
+    InProgress,
+    Done,
+}
+
+fn visit<'a>(
+    proofs: &'a [Proof], by_id: &HashMap<&str, usize>, index: usize, state: &mut [Visit],
+    ordered: &mut Vec<&'a Proof>,
+) -> anyhow::Result<()> {
+    match state[index] {
+        Visit::Done => return Ok(()),
+        Visit::InProgress => {
+            let id = proofs[index].id.as_deref().unwrap_or_default();
+            bail!("proof chain contains a cycle at proof {id}");
+        }
+        Visit::Pending => {}
+    }
+
+    state[index] = Visit::InProgress;
+    for previous in proofs[index].previous_proofs() {
+        let Some(&previous_index) = by_id.get(previous.as_str()) else {
+            bail!("previous proof {previous} is not in the proof set");
+        };
+        visit(proofs, by_id, previous_index, state, ordered)?;
+    }
+    state[index] = Visit::Done;
+    ordered.push(&proofs[index]);
+    Ok(())
+}
+
 // Unused, but required by 'option_flexvec' deserializer FromStr trait
 impl FromStr for Proof {
     type Err = Infallible;
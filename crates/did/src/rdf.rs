@@ -0,0 +1,146 @@
+//! # RDF
+//!
+//! Conversion of JSON-LD documents to RDF and canonicalization of the
+//! resulting dataset using the [RDF Dataset Canonicalization] (RDFC-1.0)
+//! algorithm, as used by the `*-rdfc-*` Data Integrity cryptosuites.
+//!
+//! The [`select_json_ld`] and [`canonicalize_and_group`] functions support
+//! the selective disclosure cryptosuites, which sign (and disclose) a
+//! document's canonical statements individually.
+//!
+//! Documents are expanded offline: only the contexts bundled with this crate
+//! (see [`CONTEXTS`]) may be referenced by URL.
+//!
+//! [RDF Dataset Canonicalization]: https://www.w3.org/TR/rdf-canon
+
+mod canon;
+mod contexts;
+mod expand;
+mod select;
+mod to_rdf;
+
+use std::fmt::{Display, Formatter, Write};
+
+use anyhow::Result;
+use serde_json::Value;
+
+pub use self::canon::{canonical_labels, canonicalize_quads};
+pub use self::contexts::CONTEXTS;
+pub use self::expand::expand;
+pub use self::select::{
+    Group, Grouped, canonicalize_and_group, label_replacement_canonicalize, select_json_ld,
+};
+pub use self::to_rdf::to_rdf;
+
+/// `rdf:type`
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+
+/// `xsd:string`
+const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+
+/// Convert a JSON-LD document to RDF and canonicalize the result, returning
+/// the canonical N-Quads document.
+///
+/// # Errors
+///
+/// Returns an error if the document is not valid JSON-LD, references a
+/// context that is not bundled, or cannot be canonicalized.
+pub fn canonicalize(document: &Value) -> Result<String> {
+    let quads = to_rdf(&expand(document)?)?;
+    canonicalize_quads(&quads)
+}
+
+/// An RDF term: the subject, predicate, object or graph name of a quad.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Term {
+    /// An IRI.
+    Iri(String),
+
+    /// A blank node, identified by its label (without the `_:` prefix).
+    Blank(String),
+
+    /// A literal value.
+    Literal {
+        /// The lexical form of the literal.
+        value: String,
+
+        /// The datatype IRI.
+        datatype: String,
+
+        /// The language tag for `rdf:langString` literals.
+        language: Option<String>,
+    },
+}
+
+impl Display for Term {
+    /// Format the term in canonical N-Quads form.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Iri(iri) => write!(f, "<{iri}>"),
+            Self::Blank(label) => write!(f, "_:{label}"),
+            Self::Literal {
+                value,
+                datatype,
+                language,
+            } => {
+                write!(f, "\"{}\"", escape(value))?;
+                if let Some(language) = language {
+                    write!(f, "@{language}")
+                } else if datatype == XSD_STRING {
+                    Ok(())
+                } else {
+                    write!(f, "^^<{datatype}>")
+                }
+            }
+        }
+    }
+}
+
+/// An RDF quad (a triple in a named or the default graph).
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Quad {
+    /// The subject: an IRI or blank node.
+    pub subject: Term,
+
+    /// The predicate IRI.
+    pub predicate: Term,
+
+    /// The object.
+    pub object: Term,
+
+    /// The graph name, or `None` for the default graph.
+    pub graph: Option<Term>,
+}
+
+impl Display for Quad {
+    /// Format the quad as a canonical N-Quads statement (without a trailing
+    /// newline).
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {} ", self.subject, self.predicate, self.object)?;
+        if let Some(graph) = &self.graph {
+            write!(f, "{graph} ")?;
+        }
+        write!(f, ".")
+    }
+}
+
+// Escape a literal's lexical form for canonical N-Quads.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\u{08}' => escaped.push_str("\\b"),
+            '\u{0c}' => escaped.push_str("\\f"),
+            '\u{00}'..='\u{1f}' | '\u{7f}' => {
+                let _ = write!(escaped, "\\u{:04X}", u32::from(c));
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,335 @@
+//! # RDF Dataset Canonicalization
+//!
+//! An implementation of the [RDFC-1.0] algorithm using SHA-256 as the hash
+//! algorithm.
+//!
+//! [RDFC-1.0]: https://www.w3.org/TR/rdf-canon
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use anyhow::{Result, bail};
+use sha2::{Digest, Sha256};
+
+use super::{Quad, Term};
+
+/// The maximum number of Hash N-Degree Quads invocations allowed when
+/// canonicalizing a dataset. Limits the work done for poorly-formed (or
+/// malicious) datasets with many indistinguishable blank nodes.
+const MAX_DEEP_ITERATIONS: usize = 10_000;
+
+/// The maximum number of indistinguishable related blank nodes that will be
+/// permuted when computing a Hash N-Degree Quads path.
+const MAX_PERMUTED: usize = 8;
+
+/// Canonicalize an RDF dataset, returning the canonical N-Quads document.
+///
+/// Blank nodes are relabelled `c14n0`, `c14n1`, etc. and the statements are
+/// sorted, each terminated by a newline.
+///
+/// # Errors
+///
+/// Returns an error if the dataset is too complex to canonicalize.
+pub fn canonicalize_quads(quads: &[Quad]) -> Result<String> {
+    let labels = canonical_labels(quads)?;
+    Ok(relabel(quads, &labels).concat())
+}
+
+/// Compute the canonical blank node labels for an RDF dataset, returning a
+/// map from each blank node's label in the dataset to its canonical label
+/// (`c14n0`, `c14n1`, etc.).
+///
+/// # Errors
+///
+/// Returns an error if the dataset is too complex to canonicalize.
+pub fn canonical_labels(quads: &[Quad]) -> Result<HashMap<String, String>> {
+    // the dataset is a set of quads
+    let mut quads = quads.to_vec();
+    quads.sort();
+    quads.dedup();
+    let mut state = State::new(&quads);
+
+    // compute first degree hashes for each blank node
+    let mut hash_to_blank_nodes = BTreeMap::<String, Vec<String>>::new();
+    for blank_node in state.blank_node_to_quads.keys() {
+        let hash = state.hash_first_degree(blank_node);
+        hash_to_blank_nodes.entry(hash).or_default().push(blank_node.clone());
+    }
+
+    // issue canonical identifiers for blank nodes with unique hashes
+    let mut non_unique = Vec::new();
+    for blank_nodes in hash_to_blank_nodes.into_values() {
+        if blank_nodes.len() == 1 {
+            state.canonical.issue(&blank_nodes[0]);
+        } else {
+            non_unique.push(blank_nodes);
+        }
+    }
+
+    // distinguish the remaining blank nodes using their related blank nodes
+    for blank_nodes in non_unique {
+        let mut hash_paths = Vec::new();
+        for blank_node in &blank_nodes {
+            if state.canonical.get(blank_node).is_some() {
+                continue;
+            }
+            let mut issuer = Issuer::new("b");
+            issuer.issue(blank_node);
+            hash_paths.push(state.hash_n_degree(blank_node, issuer)?);
+        }
+        hash_paths.sort_by(|a, b| a.0.cmp(&b.0));
+        for (_, issuer) in hash_paths {
+            for existing in &issuer.order {
+                state.canonical.issue(existing);
+            }
+        }
+    }
+
+    Ok(state.canonical.issued)
+}
+
+/// Replace the blank node labels of a dataset's quads using the label map,
+/// returning the sorted N-Quads statements, each terminated by a newline.
+/// Blank nodes without an entry in the map keep their label.
+///
+/// The dataset is a set, so duplicate statements are returned once.
+pub(super) fn relabel(quads: &[Quad], labels: &HashMap<String, String>) -> Vec<String> {
+    let relabel_term = |term: &Term| match term {
+        Term::Blank(label) => {
+            Term::Blank(labels.get(label).map_or_else(|| label.clone(), Clone::clone))
+        }
+        _ => term.clone(),
+    };
+
+    let mut statements = quads
+        .iter()
+        .map(|quad| {
+            let quad = Quad {
+                subject: relabel_term(&quad.subject),
+                predicate: quad.predicate.clone(),
+                object: relabel_term(&quad.object),
+                graph: quad.graph.as_ref().map(relabel_term),
+            };
+            format!("{quad}\n")
+        })
+        .collect::<Vec<_>>();
+    statements.sort();
+    statements.dedup();
+    statements
+}
+
+// Canonicalization state.
+struct State<'a> {
+    blank_node_to_quads: HashMap<String, Vec<&'a Quad>>,
+    canonical: Issuer,
+    deep_iterations: usize,
+}
+
+impl<'a> State<'a> {
+    fn new(quads: &'a [Quad]) -> Self {
+        let mut blank_node_to_quads = HashMap::<String, Vec<&Quad>>::new();
+        for quad in quads {
+            for term in [Some(&quad.subject), Some(&quad.object), quad.graph.as_ref()] {
+                if let Some(Term::Blank(label)) = term {
+                    blank_node_to_quads.entry(label.clone()).or_default().push(quad);
+                }
+            }
+        }
+        Self {
+            blank_node_to_quads,
+            canonical: Issuer::new("c14n"),
+            deep_iterations: 0,
+        }
+    }
+
+    // Hash First Degree Quads: hash the quads referencing the blank node,
+    // replacing the reference blank node with `_:a` and others with `_:z`.
+    fn hash_first_degree(&self, reference: &str) -> String {
+        let mut nquads = self.blank_node_to_quads[reference]
+            .iter()
+            .map(|quad| {
+                let mask = |term: &Term| match term {
+                    Term::Blank(label) if label == reference => Term::Blank("a".to_string()),
+                    Term::Blank(_) => Term::Blank("z".to_string()),
+                    _ => term.clone(),
+                };
+                let quad = Quad {
+                    subject: mask(&quad.subject),
+                    predicate: quad.predicate.clone(),
+                    object: mask(&quad.object),
+                    graph: quad.graph.as_ref().map(mask),
+                };
+                format!("{quad}\n")
+            })
+            .collect::<Vec<_>>();
+        nquads.sort();
+        hex(&Sha256::digest(nquads.concat().as_bytes()))
+    }
+
+    // Hash Related Blank Node: hash a blank node related to another through
+    // the quad at the given position ("s", "o" or "g").
+    fn hash_related(&self, related: &str, quad: &Quad, issuer: &Issuer, position: &str) -> String {
+        let mut input = position.to_string();
+        if position != "g" {
+            input.push_str(&quad.predicate.to_string());
+        }
+        if let Some(id) = self.canonical.get(related).or_else(|| issuer.get(related)) {
+            input.push_str("_:");
+            input.push_str(id);
+        } else {
+            input.push_str(&self.hash_first_degree(related));
+        }
+        hex(&Sha256::digest(input.as_bytes()))
+    }
+
+    // Hash N-Degree Quads: hash the blank node along with the paths to its
+    // related blank nodes, returning the hash and the updated issuer.
+    fn hash_n_degree(&mut self, identifier: &str, mut issuer: Issuer) -> Result<(String, Issuer)> {
+        self.deep_iterations += 1;
+        if self.deep_iterations > MAX_DEEP_ITERATIONS {
+            bail!("dataset is too complex to canonicalize");
+        }
+
+        let mut hash_to_related = BTreeMap::<String, Vec<String>>::new();
+        for quad in &self.blank_node_to_quads[identifier] {
+            let positions =
+                [("s", Some(&quad.subject)), ("o", Some(&quad.object)), ("g", quad.graph.as_ref())];
+            for (position, term) in positions {
+                let Some(Term::Blank(related)) = term else {
+                    continue;
+                };
+                if related != identifier {
+                    let hash = self.hash_related(related, quad, &issuer, position);
+                    hash_to_related.entry(hash).or_default().push(related.clone());
+                }
+            }
+        }
+
+        let mut data_to_hash = String::new();
+        for (related_hash, blank_nodes) in hash_to_related {
+            data_to_hash.push_str(&related_hash);
+            let mut chosen_path = String::new();
+            let mut chosen_issuer = None;
+
+            if blank_nodes.len() > MAX_PERMUTED {
+                bail!("dataset is too complex to canonicalize");
+            }
+            'permutations: for permutation in permutations(&blank_nodes) {
+                let mut issuer_copy = issuer.clone();
+                let mut path = String::new();
+                let mut recursion_list = Vec::new();
+
+                for related in permutation {
+                    if let Some(id) = self.canonical.get(related) {
+                        path.push_str("_:");
+                        path.push_str(id);
+                    } else {
+                        if issuer_copy.get(related).is_none() {
+                            recursion_list.push(related);
+                        }
+                        path.push_str("_:");
+                        path.push_str(&issuer_copy.issue(related));
+                    }
+                    if !chosen_path.is_empty()
+                        && path.len() >= chosen_path.len()
+                        && path > chosen_path
+                    {
+                        continue 'permutations;
+                    }
+                }
+
+                for related in recursion_list {
+                    let (hash, result_issuer) = self.hash_n_degree(related, issuer_copy)?;
+                    issuer_copy = result_issuer;
+                    path.push_str("_:");
+                    path.push_str(&issuer_copy.issue(related));
+                    path.push('<');
+                    path.push_str(&hash);
+                    path.push('>');
+                    if !chosen_path.is_empty()
+                        && path.len() >= chosen_path.len()
+                        && path > chosen_path
+                    {
+                        continue 'permutations;
+                    }
+                }
+
+                if chosen_path.is_empty() || path < chosen_path {
+                    chosen_path = path;
+                    chosen_issuer = Some(issuer_copy);
+                }
+            }
+
+            data_to_hash.push_str(&chosen_path);
+            if let Some(chosen_issuer) = chosen_issuer {
+                issuer = chosen_issuer;
+            }
+        }
+
+        Ok((hex(&Sha256::digest(data_to_hash.as_bytes())), issuer))
+    }
+}
+
+// Issues sequential blank node identifiers with a prefix, remembering the
+// order in which they were issued.
+#[derive(Clone)]
+struct Issuer {
+    prefix: &'static str,
+    issued: HashMap<String, String>,
+    order: Vec<String>,
+}
+
+impl Issuer {
+    fn new(prefix: &'static str) -> Self {
+        Self {
+            prefix,
+            issued: HashMap::new(),
+            order: Vec::new(),
+        }
+    }
+
+    fn get(&self, existing: &str) -> Option<&str> {
+        self.issued.get(existing).map(String::as_str)
+    }
+
+    fn issue(&mut self, existing: &str) -> String {
+        if let Some(issued) = self.issued.get(existing) {
+            return issued.clone();
+        }
+        let issued = format!("{}{}", self.prefix, self.order.len());
+        self.issued.insert(existing.to_string(), issued.clone());
+        self.order.push(existing.to_string());
+        issued
+    }
+}
+
+// All permutations of the items (Heap's algorithm).
+fn permutations(items: &[String]) -> Vec<Vec<&String>> {
+    let mut items = items.iter().collect::<Vec<_>>();
+    let mut result = vec![items.clone()];
+    let mut c = vec![0; items.len()];
+    let mut i = 0;
+    while i < items.len() {
+        if c[i] < i {
+            if i % 2 == 0 {
+                items.swap(0, i);
+            } else {
+                items.swap(c[i], i);
+            }
+            result.push(items.clone());
+            c[i] += 1;
+            i = 0;
+        } else {
+            c[i] = 0;
+            i += 1;
+        }
+    }
+    result
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::with_capacity(bytes.len() * 2), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+    })
+}
@@ -0,0 +1,1026 @@
+//! # JSON-LD Expansion
+//!
+//! An implementation of the [JSON-LD 1.1 Expansion] algorithm (including
+//! context processing) sufficient for Data Integrity secured documents.
+//!
+//! Remote contexts are loaded from the contexts bundled with this crate. The
+//! `@nest` and `@import` keywords and property-valued indexes are not
+//! supported.
+//!
+//! [JSON-LD 1.1 Expansion]: https://www.w3.org/TR/json-ld11-api/#expansion-algorithm
+
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow, bail};
+use serde_json::{Map, Value, json};
+
+use super::contexts;
+
+/// The maximum depth of nested (remote and scoped) context processing.
+const MAX_CONTEXT_DEPTH: usize = 32;
+
+const KEYWORDS: [&str; 29] = [
+    "@base",
+    "@container",
+    "@context",
+    "@default",
+    "@direction",
+    "@embed",
+    "@explicit",
+    "@graph",
+    "@id",
+    "@import",
+    "@included",
+    "@index",
+    "@json",
+    "@language",
+    "@list",
+    "@nest",
+    "@none",
+    "@omitDefault",
+    "@prefix",
+    "@preserve",
+    "@propagate",
+    "@protected",
+    "@requireAll",
+    "@reverse",
+    "@set",
+    "@type",
+    "@value",
+    "@version",
+    "@vocab",
+];
+
+const VALUE_KEYWORDS: [&str; 5] = ["@value", "@language", "@direction", "@type", "@index"];
+
+/// Expand a JSON-LD document, returning the expanded document (always an
+/// array of node objects).
+///
+/// # Errors
+///
+/// Returns an error if the document is not valid JSON-LD or references a
+/// context that is not bundled.
+pub fn expand(document: &Value) -> Result<Value> {
+    let expanded = expand_element(&Context::default(), None, document, false)?;
+    let expanded = match expanded {
+        Value::Object(mut map) if map.len() == 1 && map.contains_key("@graph") => {
+            map.remove("@graph").unwrap_or_default()
+        }
+        other => other,
+    };
+    Ok(match expanded {
+        Value::Null => Value::Array(vec![]),
+        Value::Array(_) => expanded,
+        other => Value::Array(vec![other]),
+    })
+}
+
+// An active context.
+#[derive(Clone, Debug, Default)]
+struct Context {
+    terms: HashMap<String, TermDefinition>,
+    vocab: Option<String>,
+    base: Option<String>,
+    language: Option<String>,
+    direction: Option<String>,
+    previous: Option<Box<Self>>,
+}
+
+// A term definition.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct TermDefinition {
+    // `None` when the term is explicitly mapped to `null`
+    id: Option<String>,
+    reverse: bool,
+    type_: Option<String>,
+    container: Vec<String>,
+    context: Option<Value>,
+    // `Some(None)` when the term explicitly resets the default
+    #[allow(clippy::option_option)]
+    language: Option<Option<String>>,
+    #[allow(clippy::option_option)]
+    direction: Option<Option<String>>,
+    index: Option<String>,
+    prefix: bool,
+    protected: bool,
+}
+
+impl TermDefinition {
+    fn has_container(&self, container: &str) -> bool {
+        self.container.iter().any(|c| c == container)
+    }
+
+    // Compare definitions, ignoring whether they are protected.
+    fn same_as(&self, other: &Self) -> bool {
+        Self {
+            protected: false,
+            ..self.clone()
+        } == Self {
+            protected: false,
+            ..other.clone()
+        }
+    }
+}
+
+impl Context {
+    fn term(&self, term: &str) -> Option<&TermDefinition> {
+        self.terms.get(term)
+    }
+
+    fn container(&self, term: Option<&str>) -> Vec<String> {
+        term.and_then(|t| self.term(t)).map(|d| d.container.clone()).unwrap_or_default()
+    }
+
+    // Context Processing algorithm.
+    fn process(
+        &self, local: &Value, override_protected: bool, propagate: bool, depth: usize,
+    ) -> Result<Self> {
+        if depth > MAX_CONTEXT_DEPTH {
+            bail!("maximum context depth exceeded");
+        }
+
+        let mut result = self.clone();
+        let propagate = match local {
+            Value::Object(map) => match map.get("@propagate") {
+                Some(Value::Bool(propagate)) => *propagate,
+                Some(_) => bail!("invalid @propagate value"),
+                None => propagate,
+            },
+            _ => propagate,
+        };
+        if !propagate && result.previous.is_none() {
+            result.previous = Some(Box::new(self.clone()));
+        }
+
+        for context in as_slice(local) {
+            match context {
+                Value::Null => {
+                    if !override_protected && result.terms.values().any(|d| d.protected) {
+                        bail!("invalid context nullification");
+                    }
+                    let previous = if propagate { None } else { Some(Box::new(result.clone())) };
+                    result = Self {
+                        base: self.base.clone(),
+                        previous,
+                        ..Self::default()
+                    };
+                }
+                Value::String(url) => {
+                    let document = contexts::load(url)?;
+                    let Some(context) = document.get("@context") else {
+                        bail!("invalid remote context: {url}");
+                    };
+                    result = result.process(context, false, true, depth + 1)?;
+                }
+                Value::Object(map) => {
+                    result.process_definition(map, override_protected)?;
+                }
+                _ => bail!("invalid local context"),
+            }
+        }
+        Ok(result)
+    }
+
+    // Process a context definition (a map) into the active context.
+    fn process_definition(
+        &mut self, map: &Map<String, Value>, override_protected: bool,
+    ) -> Result<()> {
+        if let Some(version) = map.get("@version") {
+            if version.as_f64() != Some(1.1) {
+                bail!("invalid @version value");
+            }
+        }
+        if map.contains_key("@import") {
+            bail!("@import is not supported");
+        }
+        if let Some(base) = map.get("@base") {
+            self.base = match base {
+                Value::Null => None,
+                Value::String(base) => Some(base.clone()),
+                _ => bail!("invalid base IRI"),
+            };
+        }
+        if let Some(vocab) = map.get("@vocab") {
+            self.vocab = match vocab {
+                Value::Null => None,
+                Value::String(vocab) => Some(
+                    self.expand_iri(vocab, true, true)
+                        .ok_or_else(|| anyhow!("invalid vocab mapping"))?,
+                ),
+                _ => bail!("invalid vocab mapping"),
+            };
+        }
+        if let Some(language) = map.get("@language") {
+            self.language = match language {
+                Value::Null => None,
+                Value::String(language) => Some(language.to_lowercase()),
+                _ => bail!("invalid default language"),
+            };
+        }
+        if let Some(direction) = map.get("@direction") {
+            self.direction = match direction {
+                Value::Null => None,
+                Value::String(direction) if direction == "ltr" || direction == "rtl" => {
+                    Some(direction.clone())
+                }
+                _ => bail!("invalid base direction"),
+            };
+        }
+
+        let protected = match map.get("@protected") {
+            Some(Value::Bool(protected)) => *protected,
+            Some(_) => bail!("invalid @protected value"),
+            None => false,
+        };
+        let mut defined = HashMap::new();
+        for term in map.keys() {
+            if matches!(
+                term.as_str(),
+                "@base"
+                    | "@direction"
+                    | "@import"
+                    | "@language"
+                    | "@propagate"
+                    | "@protected"
+                    | "@version"
+                    | "@vocab"
+            ) {
+                continue;
+            }
+            self.define_term(map, term, &mut defined, protected, override_protected)?;
+        }
+        Ok(())
+    }
+
+    // Create Term Definition algorithm.
+    #[allow(clippy::too_many_lines)]
+    fn define_term(
+        &mut self, local: &Map<String, Value>, term: &str, defined: &mut HashMap<String, bool>,
+        protected: bool, override_protected: bool,
+    ) -> Result<()> {
+        match defined.get(term) {
+            Some(true) => return Ok(()),
+            Some(false) => bail!("cyclic IRI mapping for {term}"),
+            None => {}
+        }
+        defined.insert(term.to_string(), false);
+
+        let value = &local[term];
+        if term == "@type" {
+            // only `@container: @set` and `@protected` may be set for `@type`
+            defined.insert(term.to_string(), true);
+            return Ok(());
+        }
+        if is_keyword(term) {
+            bail!("keyword redefinition: {term}");
+        }
+        if looks_like_keyword(term) {
+            defined.insert(term.to_string(), true);
+            return Ok(());
+        }
+
+        let previous = self.terms.remove(term);
+        let value = match value {
+            Value::Null => json!({"@id": null}),
+            Value::String(id) => json!({"@id": id}),
+            Value::Object(_) => value.clone(),
+            _ => bail!("invalid term definition for {term}"),
+        };
+        let Value::Object(value) = value else {
+            unreachable!("term definition is a map");
+        };
+
+        let mut definition = TermDefinition {
+            protected: match value.get("@protected") {
+                Some(Value::Bool(protected)) => *protected,
+                Some(_) => bail!("invalid @protected value"),
+                None => protected,
+            },
+            ..TermDefinition::default()
+        };
+
+        if let Some(type_) = value.get("@type") {
+            let Value::String(type_) = type_ else {
+                bail!("invalid type mapping for {term}");
+            };
+            self.define_dependencies(local, type_, defined, protected, override_protected)?;
+            let type_ = self
+                .expand_iri(type_, false, true)
+                .ok_or_else(|| anyhow!("invalid type mapping for {term}"))?;
+            if !matches!(type_.as_str(), "@id" | "@json" | "@none" | "@vocab")
+                && !is_absolute_iri(&type_)
+            {
+                bail!("invalid type mapping for {term}");
+            }
+            definition.type_ = Some(type_);
+        }
+
+        if let Some(reverse) = value.get("@reverse") {
+            if value.contains_key("@id") || value.contains_key("@nest") {
+                bail!("invalid reverse property for {term}");
+            }
+            let Value::String(reverse) = reverse else {
+                bail!("invalid IRI mapping for {term}");
+            };
+            self.define_dependencies(local, reverse, defined, protected, override_protected)?;
+            let id = self
+                .expand_iri(reverse, false, true)
+                .filter(|id| id.contains(':'))
+                .ok_or_else(|| anyhow!("invalid IRI mapping for {term}"))?;
+            definition.id = Some(id);
+            definition.reverse = true;
+            if let Some(container) = value.get("@container") {
+                definition.container = containers(container)?;
+            }
+            self.terms.insert(term.to_string(), definition);
+            defined.insert(term.to_string(), true);
+            return Ok(());
+        }
+
+        match value.get("@id") {
+            Some(Value::Null) => definition.id = None,
+            Some(Value::String(id)) if id != term => {
+                if !is_keyword(id) && looks_like_keyword(id) {
+                    defined.insert(term.to_string(), true);
+                    return Ok(());
+                }
+                self.define_dependencies(local, id, defined, protected, override_protected)?;
+                let id = self
+                    .expand_iri(id, false, true)
+                    .ok_or_else(|| anyhow!("invalid IRI mapping for {term}"))?;
+                if !is_keyword(&id) && !id.contains(':') {
+                    bail!("invalid IRI mapping for {term}");
+                }
+                if id == "@context" {
+                    bail!("invalid keyword alias for {term}");
+                }
+                if term.find(':').is_some_and(|i| i > 0 && i < term.len() - 1) || term.contains('/')
+                {
+                    defined.insert(term.to_string(), true);
+                    if self.expand_iri(term, false, true).as_deref() != Some(id.as_str()) {
+                        bail!("invalid IRI mapping for {term}");
+                    }
+                }
+                definition.prefix = !term.contains(':')
+                    && !term.contains('/')
+                    && id.ends_with([':', '/', '?', '#', '[', ']', '@']);
+                definition.id = Some(id);
+            }
+            Some(Value::String(_)) | None => {
+                definition.id =
+                    Some(self.term_iri(local, term, defined, protected, override_protected)?);
+            }
+            Some(_) => bail!("invalid IRI mapping for {term}"),
+        }
+
+        if let Some(container) = value.get("@container") {
+            definition.container = containers(container)?;
+            if definition.has_container("@type") {
+                match definition.type_.as_deref() {
+                    None => definition.type_ = Some("@id".to_string()),
+                    Some("@id" | "@vocab") => {}
+                    Some(_) => bail!("invalid type mapping for {term}"),
+                }
+            }
+        }
+        if let Some(index) = value.get("@index") {
+            let Value::String(index) = index else {
+                bail!("invalid term definition for {term}");
+            };
+            definition.index = Some(index.clone());
+        }
+        if let Some(context) = value.get("@context") {
+            // validate the scoped context
+            self.process(context, true, true, 1)?;
+            definition.context = Some(context.clone());
+        }
+        if let Some(language) = value.get("@language") {
+            definition.language = Some(match language {
+                Value::Null => None,
+                Value::String(language) => Some(language.to_lowercase()),
+                _ => bail!("invalid language mapping for {term}"),
+            });
+        }
+        if let Some(direction) = value.get("@direction") {
+            definition.direction = Some(match direction {
+                Value::Null => None,
+                Value::String(direction) if direction == "ltr" || direction == "rtl" => {
+                    Some(direction.clone())
+                }
+                _ => bail!("invalid base direction for {term}"),
+            });
+        }
+        if value.contains_key("@nest") {
+            bail!("@nest is not supported");
+        }
+        if let Some(prefix) = value.get("@prefix") {
+            let Value::Bool(prefix) = prefix else {
+                bail!("invalid @prefix value for {term}");
+            };
+            definition.prefix = *prefix;
+        }
+
+        if let Some(previous) = previous {
+            if previous.protected && !override_protected {
+                if !definition.same_as(&previous) {
+                    bail!("protected term redefinition: {term}");
+                }
+                definition = previous;
+            }
+        }
+        self.terms.insert(term.to_string(), definition);
+        defined.insert(term.to_string(), true);
+        Ok(())
+    }
+
+    // The IRI for a term without an explicit `@id`.
+    fn term_iri(
+        &mut self, local: &Map<String, Value>, term: &str, defined: &mut HashMap<String, bool>,
+        protected: bool, override_protected: bool,
+    ) -> Result<String> {
+        if let Some((prefix, suffix)) =
+            term.split_once(':').filter(|(prefix, _)| !prefix.is_empty())
+        {
+            if local.contains_key(prefix) {
+                self.define_term(local, prefix, defined, protected, override_protected)?;
+            }
+            if let Some(id) = self.term(prefix).and_then(|d| d.id.as_ref()) {
+                return Ok(format!("{id}{suffix}"));
+            }
+            return Ok(term.to_string());
+        }
+        if term.contains('/') {
+            return self
+                .expand_iri(term, false, true)
+                .filter(|id| is_absolute_iri(id))
+                .ok_or_else(|| anyhow!("invalid IRI mapping for {term}"));
+        }
+        self.vocab
+            .as_ref()
+            .map(|vocab| format!("{vocab}{term}"))
+            .ok_or_else(|| anyhow!("invalid IRI mapping for {term}"))
+    }
+
+    // Ensure any term (or compact IRI prefix) in the local context used by
+    // `value` is defined before `value` is expanded.
+    fn define_dependencies(
+        &mut self, local: &Map<String, Value>, value: &str, defined: &mut HashMap<String, bool>,
+        protected: bool, override_protected: bool,
+    ) -> Result<()> {
+        if local.contains_key(value) && defined.get(value) != Some(&true) {
+            self.define_term(local, value, defined, protected, override_protected)?;
+        }
+        if let Some((prefix, suffix)) = value.split_once(':') {
+            if prefix != "_" && !suffix.starts_with("//") && local.contains_key(prefix) {
+                self.define_term(local, prefix, defined, protected, override_protected)?;
+            }
+        }
+        Ok(())
+    }
+
+    // IRI Expansion algorithm. Returns `None` if the value expands to `null`
+    // (or looks like an unknown keyword).
+    fn expand_iri(&self, value: &str, document_relative: bool, vocab: bool) -> Option<String> {
+        if is_keyword(value) {
+            return Some(value.to_string());
+        }
+        if looks_like_keyword(value) {
+            return None;
+        }
+        if vocab {
+            if let Some(definition) = self.term(value) {
+                return definition.id.clone();
+            }
+        }
+        if let Some((prefix, suffix)) = value.split_once(':') {
+            if prefix == "_" || suffix.starts_with("//") {
+                return Some(value.to_string());
+            }
+            if let Some(definition) = self.term(prefix) {
+                if let (Some(id), true) = (&definition.id, definition.prefix) {
+                    return Some(format!("{id}{suffix}"));
+                }
+            }
+            if is_absolute_iri(value) {
+                return Some(value.to_string());
+            }
+        }
+        if vocab {
+            if let Some(vocab) = &self.vocab {
+                return Some(format!("{vocab}{value}"));
+            }
+        }
+        if document_relative {
+            if let Some(base) = &self.base {
+                if let Ok(iri) = url::Url::parse(base).and_then(|base| base.join(value)) {
+                    return Some(iri.to_string());
+                }
+            }
+        }
+        Some(value.to_string())
+    }
+
+    // Value Expansion algorithm.
+    fn expand_value(&self, property: &str, value: &Value) -> Value {
+        let definition = self.term(property);
+        let type_ = definition.and_then(|d| d.type_.as_deref());
+
+        if let (Some("@id" | "@vocab"), Value::String(id)) = (type_, value) {
+            let vocab = type_ == Some("@vocab");
+            return json!({"@id": self.expand_iri(id, true, vocab)});
+        }
+
+        let mut result = Map::new();
+        result.insert("@value".to_string(), value.clone());
+        match type_ {
+            Some(type_) if !matches!(type_, "@id" | "@vocab" | "@none") => {
+                result.insert("@type".to_string(), Value::String(type_.to_string()));
+            }
+            _ if value.is_string() => {
+                let language = definition
+                    .and_then(|d| d.language.clone())
+                    .unwrap_or_else(|| self.language.clone());
+                if let Some(language) = language {
+                    result.insert("@language".to_string(), Value::String(language));
+                }
+                let direction = definition
+                    .and_then(|d| d.direction.clone())
+                    .unwrap_or_else(|| self.direction.clone());
+                if let Some(direction) = direction {
+                    result.insert("@direction".to_string(), Value::String(direction));
+                }
+            }
+            _ => {}
+        }
+        Value::Object(result)
+    }
+}
+
+// Expansion algorithm.
+fn expand_element(
+    active: &Context, property: Option<&str>, element: &Value, from_map: bool,
+) -> Result<Value> {
+    match element {
+        Value::Null => Ok(Value::Null),
+        Value::Array(items) => {
+            let is_list = active.container(property).iter().any(|c| c == "@list");
+            let mut result = Vec::new();
+            for item in items {
+                let expanded = expand_element(active, property, item, from_map)?;
+                match expanded {
+                    Value::Array(expanded) if is_list => result.push(json!({"@list": expanded})),
+                    Value::Array(expanded) => result.extend(expanded),
+                    Value::Null => {}
+                    expanded => result.push(expanded),
+                }
+            }
+            Ok(Value::Array(result))
+        }
+        Value::Object(map) => expand_map(active, property, map, from_map),
+        _ => {
+            let Some(property) = property.filter(|p| *p != "@graph") else {
+                return Ok(Value::Null);
+            };
+            let active = match active.term(property).and_then(|d| d.context.as_ref()) {
+                Some(scoped) => active.process(scoped, true, true, 1)?,
+                None => active.clone(),
+            };
+            Ok(active.expand_value(property, element))
+        }
+    }
+}
+
+// Expand a map (node, value, list or set object).
+#[allow(clippy::too_many_lines)]
+fn expand_map(
+    active: &Context, property: Option<&str>, element: &Map<String, Value>, from_map: bool,
+) -> Result<Value> {
+    let scoped = property.and_then(|p| active.term(p)).and_then(|d| d.context.clone());
+    let mut active = active.clone();
+
+    // revert type-scoped (non-propagated) contexts
+    if let Some(previous) = &active.previous {
+        if !from_map {
+            let expanded =
+                element.keys().map(|key| active.expand_iri(key, false, true)).collect::<Vec<_>>();
+            let is_value = expanded.iter().any(|key| key.as_deref() == Some("@value"));
+            let is_reference = expanded.len() == 1 && expanded[0].as_deref() == Some("@id");
+            if !is_value && !is_reference {
+                active = (**previous).clone();
+            }
+        }
+    }
+    if let Some(scoped) = &scoped {
+        active = active.process(scoped, true, true, 1)?;
+    }
+    if let Some(context) = element.get("@context") {
+        active = active.process(context, false, true, 0)?;
+    }
+
+    // apply type-scoped contexts
+    let type_scoped = active.clone();
+    let mut keys = element.keys().collect::<Vec<_>>();
+    keys.sort();
+    let mut input_type = None;
+    for key in &keys {
+        if active.expand_iri(key, false, true).as_deref() != Some("@type") {
+            continue;
+        }
+        let mut types =
+            as_slice(&element[key.as_str()]).iter().filter_map(Value::as_str).collect::<Vec<_>>();
+        types.sort_unstable();
+        for type_ in types {
+            if let Some(context) = type_scoped.term(type_).and_then(|d| d.context.as_ref()) {
+                active = active.process(context, false, false, 1)?;
+            }
+            input_type = type_scoped.expand_iri(type_, true, true);
+        }
+    }
+
+    let mut result = Map::new();
+    for key in keys {
+        if key == "@context" {
+            continue;
+        }
+        let value = &element[key.as_str()];
+        let Some(expanded_property) = active.expand_iri(key, false, true) else {
+            continue;
+        };
+        if !expanded_property.contains(':') && !is_keyword(&expanded_property) {
+            continue;
+        }
+
+        if is_keyword(&expanded_property) {
+            if property == Some("@reverse") {
+                bail!("invalid reverse property map");
+            }
+            if result.contains_key(&expanded_property)
+                && !matches!(expanded_property.as_str(), "@included" | "@type")
+            {
+                bail!("colliding keywords: {expanded_property}");
+            }
+
+            let expanded_value = match expanded_property.as_str() {
+                "@id" => {
+                    let Value::String(id) = value else {
+                        bail!("invalid @id value");
+                    };
+                    json!(active.expand_iri(id, true, false))
+                }
+                "@type" => {
+                    let mut types = match result.remove("@type") {
+                        Some(Value::Array(types)) => types,
+                        Some(type_) => vec![type_],
+                        None => vec![],
+                    };
+                    for item in as_slice(value) {
+                        let Value::String(item) = item else {
+                            bail!("invalid type value");
+                        };
+                        if let Some(type_) = type_scoped.expand_iri(item, true, true) {
+                            types.push(Value::String(type_));
+                        }
+                    }
+                    if types.len() == 1 && !value.is_array() {
+                        types.remove(0)
+                    } else {
+                        Value::Array(types)
+                    }
+                }
+                "@graph" => as_array(expand_element(&active, Some("@graph"), value, false)?),
+                "@included" => as_array(expand_element(&active, None, value, false)?),
+                "@value" => {
+                    if input_type.as_deref() != Some("@json")
+                        && (value.is_object() || value.is_array())
+                    {
+                        bail!("invalid value object value");
+                    }
+                    result.insert("@value".to_string(), value.clone());
+                    continue;
+                }
+                "@language" => {
+                    let Value::String(language) = value else {
+                        bail!("invalid language-tagged string");
+                    };
+                    Value::String(language.to_lowercase())
+                }
+                "@direction" => {
+                    let Value::String(direction) = value else {
+                        bail!("invalid base direction");
+                    };
+                    if direction != "ltr" && direction != "rtl" {
+                        bail!("invalid base direction");
+                    }
+                    value.clone()
+                }
+                "@index" => {
+                    if !value.is_string() {
+                        bail!("invalid @index value");
+                    }
+                    value.clone()
+                }
+                "@list" => {
+                    if property.is_none() || property == Some("@graph") {
+                        continue;
+                    }
+                    as_array(expand_element(&active, property, value, false)?)
+                }
+                "@set" => expand_element(&active, property, value, false)?,
+                "@reverse" => {
+                    if !value.is_object() {
+                        bail!("invalid @reverse value");
+                    }
+                    let Value::Object(expanded) =
+                        expand_element(&active, Some("@reverse"), value, false)?
+                    else {
+                        continue;
+                    };
+                    for (key, items) in expanded {
+                        if key == "@reverse" {
+                            let Value::Object(forward) = items else {
+                                continue;
+                            };
+                            for (property, items) in forward {
+                                append(&mut result, &property, items);
+                            }
+                        } else {
+                            let reverse = result
+                                .entry("@reverse")
+                                .or_insert_with(|| Value::Object(Map::new()));
+                            if let Value::Object(reverse) = reverse {
+                                append(reverse, &key, items);
+                            }
+                        }
+                    }
+                    continue;
+                }
+                "@nest" => bail!("@nest is not supported"),
+                _ => continue,
+            };
+            if !expanded_value.is_null() {
+                result.insert(expanded_property, expanded_value);
+            }
+            continue;
+        }
+
+        let definition = active.term(key).cloned().unwrap_or_default();
+        let mut expanded_value = if definition.type_.as_deref() == Some("@json") {
+            json!({"@value": value, "@type": "@json"})
+        } else if let (true, Value::Object(languages)) =
+            (definition.has_container("@language"), value)
+        {
+            expand_language_map(&active, &definition, languages)?
+        } else if let (true, Value::Object(map)) = (
+            definition.has_container("@index")
+                || definition.has_container("@type")
+                || definition.has_container("@id"),
+            value,
+        ) {
+            expand_index_map(&active, key, &definition, map)?
+        } else {
+            expand_element(&active, Some(key), value, false)?
+        };
+        if expanded_value.is_null() {
+            continue;
+        }
+
+        if definition.has_container("@list") && !is_list(&expanded_value) {
+            expanded_value = json!({"@list": as_array(expanded_value)});
+        }
+        if definition.has_container("@graph")
+            && !definition.has_container("@id")
+            && !definition.has_container("@index")
+        {
+            let graphs = as_array(expanded_value)
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|item| json!({"@graph": as_array(item.clone())}))
+                .collect();
+            expanded_value = Value::Array(graphs);
+        }
+
+        if definition.reverse {
+            let reverse = result.entry("@reverse").or_insert_with(|| Value::Object(Map::new()));
+            if let Value::Object(reverse) = reverse {
+                for item in as_slice(&expanded_value) {
+                    if item.get("@value").is_some() || item.get("@list").is_some() {
+                        bail!("invalid reverse property value");
+                    }
+                }
+                append(reverse, &expanded_property, expanded_value);
+            }
+        } else {
+            append(&mut result, &expanded_property, expanded_value);
+        }
+    }
+
+    finalize(result, property)
+}
+
+// Expand a language map.
+fn expand_language_map(
+    active: &Context, definition: &TermDefinition, languages: &Map<String, Value>,
+) -> Result<Value> {
+    let direction = definition.direction.clone().unwrap_or_else(|| active.direction.clone());
+    let mut keys = languages.keys().collect::<Vec<_>>();
+    keys.sort();
+
+    let mut result = Vec::new();
+    for language in keys {
+        for item in as_slice(&languages[language.as_str()]) {
+            match item {
+                Value::Null => {}
+                Value::String(_) => {
+                    let mut value = Map::new();
+                    value.insert("@value".to_string(), item.clone());
+                    if active.expand_iri(language, false, true).as_deref() != Some("@none") {
+                        value.insert("@language".to_string(), json!(language.to_lowercase()));
+                    }
+                    if let Some(direction) = &direction {
+                        value.insert("@direction".to_string(), json!(direction));
+                    }
+                    result.push(Value::Object(value));
+                }
+                _ => bail!("invalid language map value"),
+            }
+        }
+    }
+    Ok(Value::Array(result))
+}
+
+// Expand an index, id or type map.
+fn expand_index_map(
+    active: &Context, key: &str, definition: &TermDefinition, map: &Map<String, Value>,
+) -> Result<Value> {
+    if definition.index.as_deref().is_some_and(|index| index != "@index") {
+        bail!("property-valued indexes are not supported");
+    }
+    let is_id = definition.has_container("@id");
+    let is_type = definition.has_container("@type");
+    let is_graph = definition.has_container("@graph");
+
+    let mut indexes = map.keys().collect::<Vec<_>>();
+    indexes.sort();
+
+    let mut result = Vec::new();
+    for index in indexes {
+        let mut map_context = if is_id || is_type {
+            active.previous.as_deref().cloned().unwrap_or_else(|| active.clone())
+        } else {
+            active.clone()
+        };
+        if is_type {
+            if let Some(context) = active.term(index).and_then(|d| d.context.as_ref()) {
+                map_context = map_context.process(context, false, true, 1)?;
+            }
+        }
+
+        let expanded_index = active.expand_iri(index, false, true);
+        let is_none = expanded_index.as_deref() == Some("@none");
+        let values = as_array(expand_element(&map_context, Some(key), &map[index.as_str()], true)?);
+
+        for item in values.as_array().into_iter().flatten() {
+            let mut item = item.clone();
+            if is_graph && item.get("@graph").is_none() {
+                item = json!({"@graph": [item]});
+            }
+            let Value::Object(object) = &mut item else {
+                continue;
+            };
+            if is_id {
+                if !is_none && !object.contains_key("@id") {
+                    object.insert("@id".to_string(), json!(active.expand_iri(index, true, false)));
+                }
+            } else if is_type {
+                if !is_none {
+                    let mut types = vec![json!(expanded_index)];
+                    if let Some(existing) = object.remove("@type") {
+                        types.extend(as_array(existing).as_array().cloned().unwrap_or_default());
+                    }
+                    object.insert("@type".to_string(), Value::Array(types));
+                }
+            } else if !is_none && !object.contains_key("@index") {
+                object.insert("@index".to_string(), json!(index));
+            }
+            result.push(item);
+        }
+    }
+    Ok(Value::Array(result))
+}
+
+// Validate and simplify an expanded map.
+fn finalize(mut result: Map<String, Value>, property: Option<&str>) -> Result<Value> {
+    if let Some(value) = result.get("@value") {
+        if result.keys().any(|key| !VALUE_KEYWORDS.contains(&key.as_str())) {
+            bail!("invalid value object");
+        }
+        let type_ = result.get("@type");
+        if type_.is_some() && result.contains_key("@language") {
+            bail!("invalid value object");
+        }
+        if type_.and_then(Value::as_str) == Some("@json") {
+            return Ok(Value::Object(result));
+        }
+        if value.is_null() {
+            return Ok(Value::Null);
+        }
+        if result.contains_key("@language") && !value.is_string() {
+            bail!("invalid language-tagged value");
+        }
+        if let Some(type_) = type_ {
+            if !type_.as_str().is_some_and(is_absolute_iri) {
+                bail!("invalid typed value");
+            }
+        }
+    } else if let Some(type_) = result.get_mut("@type") {
+        if !type_.is_array() {
+            *type_ = Value::Array(vec![type_.clone()]);
+        }
+    } else if result.contains_key("@set") || result.contains_key("@list") {
+        if result.len() > 2 || (result.len() == 2 && !result.contains_key("@index")) {
+            bail!("invalid set or list object");
+        }
+        if let Some(set) = result.remove("@set") {
+            return Ok(set);
+        }
+    }
+
+    if result.len() == 1 && result.contains_key("@language") {
+        return Ok(Value::Null);
+    }
+    if property.is_none() || property == Some("@graph") {
+        if result.is_empty() || result.contains_key("@value") || result.contains_key("@list") {
+            return Ok(Value::Null);
+        }
+        if result.len() == 1 && result.contains_key("@id") {
+            return Ok(Value::Null);
+        }
+    }
+    Ok(Value::Object(result))
+}
+
+// Append values to a map entry, always as an array.
+fn append(map: &mut Map<String, Value>, key: &str, value: Value) {
+    let entry = map.entry(key).or_insert_with(|| Value::Array(vec![]));
+    if let Value::Array(entry) = entry {
+        match value {
+            Value::Array(values) => entry.extend(values),
+            value => entry.push(value),
+        }
+    }
+}
+
+// Parse a container mapping.
+fn containers(container: &Value) -> Result<Vec<String>> {
+    let mut containers = Vec::new();
+    for c in as_slice(container) {
+        match c.as_str() {
+            Some(c @ ("@graph" | "@id" | "@index" | "@language" | "@list" | "@set" | "@type")) => {
+                containers.push(c.to_string());
+            }
+            _ => bail!("invalid container mapping"),
+        }
+    }
+    containers.sort();
+    Ok(containers)
+}
+
+fn is_list(value: &Value) -> bool {
+    value.get("@list").is_some()
+}
+
+fn as_slice(value: &Value) -> &[Value] {
+    match value {
+        Value::Array(values) => values,
+        _ => std::slice::from_ref(value),
+    }
+}
+
+fn as_array(value: Value) -> Value {
+    match value {
+        Value::Array(_) => value,
+        Value::Null => Value::Array(vec![]),
+        value => Value::Array(vec![value]),
+    }
+}
+
+fn is_keyword(value: &str) -> bool {
+    KEYWORDS.contains(&value)
+}
+
+// Values of the form `@` followed by one or more letters are reserved.
+fn looks_like_keyword(value: &str) -> bool {
+    value
+        .strip_prefix('@')
+        .is_some_and(|rest| !rest.is_empty() && rest.chars().all(|c| c.is_ascii_alphabetic()))
+}
+
+pub(super) fn is_absolute_iri(value: &str) -> bool {
+    let Some((scheme, _)) = value.split_once(':') else {
+        return false;
+    };
+    let mut chars = scheme.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
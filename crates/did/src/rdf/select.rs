@@ -0,0 +1,311 @@
+//! # Selective Disclosure
+//!
+//! Functions shared by the selective disclosure cryptosuites: selecting the
+//! parts of a document identified by [JSON Pointers], grouping a document's
+//! canonical statements by selection, and canonicalizing with blank node
+//! labels chosen by the cryptosuite.
+//!
+//! To match the statements of a selection with those of the full document,
+//! every node in the document is first given an identifier (skolemized)
+//! using the `urn:bnid:` scheme. Skolem identifiers are converted back to
+//! blank nodes when the document (or a selection) is converted to RDF.
+//!
+//! [JSON Pointers]: https://www.rfc-editor.org/rfc/rfc6901
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::{Result, anyhow, bail};
+use serde_json::{Map, Value};
+
+use super::canon::{canonical_labels, relabel};
+use super::{Quad, Term, expand, to_rdf};
+
+/// The URN prefix for skolemized blank node identifiers.
+const SKOLEM_PREFIX: &str = "urn:bnid:";
+
+/// The statements of a canonicalized document grouped by whether they match
+/// a selection. Statements are keyed by their index in the document's
+/// canonical N-Quads.
+#[derive(Clone, Debug, Default)]
+pub struct Group {
+    /// The statements matching the selection.
+    pub matching: BTreeMap<usize, String>,
+
+    /// The statements not matching the selection.
+    pub non_matching: BTreeMap<usize, String>,
+
+    /// The selection as an RDF dataset, labelled with the document's
+    /// (skolem) blank node labels.
+    pub deskolemized: Vec<Quad>,
+}
+
+/// A document canonicalized using custom blank node labels, with its
+/// statements grouped by selection.
+#[derive(Clone, Debug, Default)]
+pub struct Grouped {
+    /// The document's canonical N-Quads statements, relabelled and sorted,
+    /// each terminated by a newline.
+    pub nquads: Vec<String>,
+
+    /// The map from the document's (skolem) blank node labels to the labels
+    /// used in the statements.
+    pub label_map: HashMap<String, String>,
+
+    /// A group for each set of JSON Pointers, in the order requested.
+    pub groups: Vec<Group>,
+}
+
+/// Select the parts of a JSON-LD document identified by JSON Pointers.
+///
+/// The selection keeps the document's `@context` and, for each object on
+/// the path to a selected value, the object's `id` (unless it is a blank
+/// node identifier) and `type`. Returns `None` if there are no pointers.
+///
+/// # Errors
+///
+/// Returns an error if a pointer is not valid or does not match the document.
+pub fn select_json_ld(document: &Value, pointers: &[String]) -> Result<Option<Value>> {
+    if pointers.is_empty() {
+        return Ok(None);
+    }
+    let paths =
+        pointers.iter().map(|pointer| parse_pointer(pointer)).collect::<Result<Vec<_>>>()?;
+    let paths = paths.iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+    let mut selection = select(document, &paths)?;
+    if let (Value::Object(map), Some(context)) = (&mut selection, document.get("@context")) {
+        map.insert("@context".to_string(), context.clone());
+    }
+    Ok(Some(selection))
+}
+
+/// Canonicalize an RDF dataset, replacing the canonical blank node labels
+/// with labels chosen by `label_map`.
+///
+/// The `label_map` function is given the map from the dataset's blank node
+/// labels to their canonical labels and returns the map from the dataset's
+/// blank node labels to their replacement labels. Returns that map along
+/// with the relabelled, sorted N-Quads statements.
+///
+/// # Errors
+///
+/// Returns an error if the dataset cannot be canonicalized or `label_map`
+/// fails.
+pub fn label_replacement_canonicalize(
+    quads: &[Quad],
+    label_map: impl FnOnce(&HashMap<String, String>) -> Result<HashMap<String, String>>,
+) -> Result<(HashMap<String, String>, Vec<String>)> {
+    let label_map = label_map(&canonical_labels(quads)?)?;
+    let nquads = relabel(quads, &label_map);
+    Ok((label_map, nquads))
+}
+
+/// Canonicalize a JSON-LD document and group its statements by selection.
+///
+/// Blank nodes are labelled using `label_map` (see
+/// [`label_replacement_canonicalize`]) and a group is returned for each set
+/// of JSON Pointers, dividing the statements by whether they are selected.
+///
+/// # Errors
+///
+/// Returns an error if the document cannot be converted to RDF or
+/// canonicalized, or if a pointer does not match the document.
+pub fn canonicalize_and_group(
+    document: &Value,
+    label_map: impl FnOnce(&HashMap<String, String>) -> Result<HashMap<String, String>>,
+    groups: &[&[String]],
+) -> Result<Grouped> {
+    let skolemized = skolemize(document);
+    let (label_map, nquads) =
+        label_replacement_canonicalize(&deskolemized_quads(&skolemized)?, label_map)?;
+
+    let groups = groups
+        .iter()
+        .map(|pointers| {
+            let deskolemized = match select_json_ld(&skolemized, pointers)? {
+                Some(selection) => deskolemized_quads(&selection)?,
+                None => Vec::new(),
+            };
+            let selected = relabel(&deskolemized, &label_map).into_iter().collect::<HashSet<_>>();
+
+            let mut group = Group {
+                deskolemized,
+                ..Group::default()
+            };
+            for (index, nquad) in nquads.iter().enumerate() {
+                if selected.contains(nquad) {
+                    group.matching.insert(index, nquad.clone());
+                } else {
+                    group.non_matching.insert(index, nquad.clone());
+                }
+            }
+            Ok(group)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Grouped {
+        nquads,
+        label_map,
+        groups,
+    })
+}
+
+// Parse a JSON Pointer into its (unescaped) reference tokens.
+fn parse_pointer(pointer: &str) -> Result<Vec<String>> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(tokens) = pointer.strip_prefix('/') else {
+        bail!("invalid JSON pointer: {pointer}");
+    };
+    Ok(tokens.split('/').map(|token| token.replace("~1", "/").replace("~0", "~")).collect())
+}
+
+// Select the values at the paths (relative to `value`).
+fn select(value: &Value, paths: &[&[String]]) -> Result<Value> {
+    // a path ending here selects the whole value
+    if paths.iter().any(|path| path.is_empty()) {
+        return Ok(value.clone());
+    }
+
+    match value {
+        Value::Object(map) => {
+            let mut selection = Map::new();
+            if let Some((key, Value::String(id))) =
+                ["id", "@id"].into_iter().find_map(|key| map.get(key).map(|id| (key, id)))
+            {
+                if !id.starts_with("_:") {
+                    selection.insert(key.to_string(), Value::String(id.clone()));
+                }
+            }
+            for key in ["type", "@type"] {
+                if let Some(type_) = map.get(key) {
+                    selection.insert(key.to_string(), type_.clone());
+                }
+            }
+
+            for (key, paths) in group_paths(paths) {
+                let value = map.get(key).ok_or_else(|| anyhow!("JSON pointer does not match"))?;
+                selection.insert(key.to_string(), select(value, &paths)?);
+            }
+            Ok(Value::Object(selection))
+        }
+        Value::Array(items) => {
+            let mut selected = BTreeMap::new();
+            for (index, paths) in group_paths(paths) {
+                let index = index.parse::<usize>()?;
+                let item =
+                    items.get(index).ok_or_else(|| anyhow!("JSON pointer does not match"))?;
+                selected.insert(index, select(item, &paths)?);
+            }
+            Ok(Value::Array(selected.into_values().collect()))
+        }
+        _ => bail!("JSON pointer does not match"),
+    }
+}
+
+// Group (non-empty) paths by their first token.
+fn group_paths<'a>(paths: &[&'a [String]]) -> Vec<(&'a str, Vec<&'a [String]>)> {
+    let mut grouped = Vec::<(&str, Vec<&[String]>)>::new();
+    for path in paths {
+        let Some((first, rest)) = path.split_first() else {
+            continue;
+        };
+        match grouped.iter_mut().find(|(token, _)| token == first) {
+            Some((_, rests)) => rests.push(rest),
+            None => grouped.push((first, vec![rest])),
+        }
+    }
+    grouped
+}
+
+// Give every node object in a (compact) JSON-LD document an identifier,
+// replacing blank node identifiers with skolem IRIs.
+fn skolemize(document: &Value) -> Value {
+    let mut skolemized = document.clone();
+    let mut skolem = Skolem {
+        prefix: format!("{SKOLEM_PREFIX}_{}_", uuid::Uuid::new_v4().simple()),
+        ..Skolem::default()
+    };
+    skolem.value(&mut skolemized);
+    skolemized
+}
+
+#[derive(Default)]
+struct Skolem {
+    prefix: String,
+    labels: HashMap<String, String>,
+    count: usize,
+}
+
+impl Skolem {
+    fn value(&mut self, value: &mut Value) {
+        match value {
+            Value::Array(items) => items.iter_mut().for_each(|item| self.value(item)),
+            Value::Object(map) => self.object(map),
+            _ => {}
+        }
+    }
+
+    fn object(&mut self, map: &mut Map<String, Value>) {
+        if map.contains_key("@value") {
+            return;
+        }
+
+        // list and set objects, and graphs without an identifier, are not
+        // nodes in their own right
+        let is_node = !["@list", "@set", "@graph"].iter().any(|key| map.contains_key(*key));
+        if is_node {
+            let key = if map.contains_key("id") { "id" } else { "@id" };
+            let iri = match map.get(key) {
+                Some(Value::String(id)) => id.strip_prefix("_:").map(|label| self.iri(Some(label))),
+                Some(_) => None,
+                None => Some(self.iri(None)),
+            };
+            if let Some(iri) = iri {
+                map.insert(key.to_string(), Value::String(iri));
+            }
+        }
+
+        for (key, value) in map.iter_mut() {
+            if key != "@context" {
+                self.value(value);
+            }
+        }
+    }
+
+    // The skolem IRI for a blank node label, or for a new blank node.
+    fn iri(&mut self, label: Option<&str>) -> String {
+        if let Some(iri) = label.and_then(|label| self.labels.get(label)) {
+            return iri.clone();
+        }
+        let iri = format!("{}{}", self.prefix, self.count);
+        self.count += 1;
+        if let Some(label) = label {
+            self.labels.insert(label.to_string(), iri.clone());
+        }
+        iri
+    }
+}
+
+// Convert a skolemized document to RDF, converting skolem IRIs back to blank
+// nodes.
+fn deskolemized_quads(skolemized: &Value) -> Result<Vec<Quad>> {
+    let deskolemize = |term: Term| match term {
+        Term::Iri(iri) if iri.starts_with(SKOLEM_PREFIX) => {
+            Term::Blank(iri[SKOLEM_PREFIX.len()..].to_string())
+        }
+        _ => term,
+    };
+
+    let quads = to_rdf(&expand(skolemized)?)?
+        .into_iter()
+        .map(|quad| Quad {
+            subject: deskolemize(quad.subject),
+            predicate: quad.predicate,
+            object: deskolemize(quad.object),
+            graph: quad.graph.map(deskolemize),
+        })
+        .collect();
+    Ok(quads)
+}
@@ -0,0 +1,276 @@
+//! # JSON-LD to RDF
+//!
+//! Conversion of an expanded JSON-LD document to an RDF dataset following
+//! the [Deserialize JSON-LD to RDF] algorithm.
+//!
+//! Statements with relative IRIs (or blank node predicates) are dropped and
+//! `@direction` is ignored, as for the algorithm's default options.
+//!
+//! [Deserialize JSON-LD to RDF]: https://www.w3.org/TR/json-ld11-api/#deserialize-json-ld-to-rdf-algorithm
+
+use std::collections::HashMap;
+
+use anyhow::{Result, bail};
+use serde_json::{Map, Number, Value};
+
+use super::expand::is_absolute_iri;
+use super::{Quad, RDF_TYPE, Term, XSD_STRING};
+
+const RDF_FIRST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#first";
+const RDF_REST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest";
+const RDF_NIL: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#nil";
+const RDF_JSON: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON";
+const XSD_BOOLEAN: &str = "http://www.w3.org/2001/XMLSchema#boolean";
+const XSD_DOUBLE: &str = "http://www.w3.org/2001/XMLSchema#double";
+const XSD_INTEGER: &str = "http://www.w3.org/2001/XMLSchema#integer";
+
+/// Convert an expanded JSON-LD document to an RDF dataset.
+///
+/// # Errors
+///
+/// Returns an error if the document is not in expanded form.
+pub fn to_rdf(expanded: &Value) -> Result<Vec<Quad>> {
+    let Value::Array(nodes) = expanded else {
+        bail!("document is not in expanded form");
+    };
+
+    let mut dataset = Dataset::default();
+    for node in nodes {
+        let Value::Object(node) = node else {
+            bail!("document is not in expanded form");
+        };
+        dataset.node(node, None)?;
+    }
+
+    let mut quads = dataset.quads;
+    quads.sort();
+    quads.dedup();
+    Ok(quads)
+}
+
+#[derive(Default)]
+struct Dataset {
+    quads: Vec<Quad>,
+    labels: HashMap<String, String>,
+    counter: usize,
+}
+
+impl Dataset {
+    // Add the statements for a node object, returning the node's subject
+    // (`None` when its `@id` is a relative IRI).
+    fn node(&mut self, node: &Map<String, Value>, graph: Option<&Term>) -> Result<Option<Term>> {
+        let subject = match node.get("@id") {
+            Some(Value::String(id)) => self.term(id),
+            Some(_) => bail!("invalid @id value"),
+            None => Some(self.blank_node()),
+        };
+
+        if let Some(items) = node.get("@graph") {
+            let len = self.quads.len();
+            for item in as_slice(items) {
+                if let Value::Object(item) = item {
+                    self.node(item, subject.as_ref())?;
+                }
+            }
+            // statements in a graph with a relative IRI name are dropped
+            if subject.is_none() {
+                self.quads.truncate(len);
+            }
+        }
+
+        let mut properties = node.keys().collect::<Vec<_>>();
+        properties.sort();
+        for property in properties {
+            let values = &node[property.as_str()];
+            match property.as_str() {
+                "@type" => {
+                    for type_ in as_slice(values) {
+                        let (Some(subject), Some(type_)) =
+                            (&subject, type_.as_str().and_then(|t| self.term(t)))
+                        else {
+                            continue;
+                        };
+                        self.push(subject, &Term::Iri(RDF_TYPE.to_string()), type_, graph);
+                    }
+                }
+                "@reverse" => {
+                    let Value::Object(reverse) = values else {
+                        bail!("invalid @reverse value");
+                    };
+                    for (property, items) in reverse {
+                        let predicate = self.predicate(property);
+                        for item in as_slice(items) {
+                            let Value::Object(item) = item else {
+                                continue;
+                            };
+                            let object = self.node(item, graph)?;
+                            if let (Some(object), Some(subject), Some(predicate)) =
+                                (object, &subject, &predicate)
+                            {
+                                self.push(&object, predicate, subject.clone(), graph);
+                            }
+                        }
+                    }
+                }
+                property if property.starts_with('@') => {}
+                property => {
+                    let predicate = self.predicate(property);
+                    for item in as_slice(values) {
+                        let object = self.object(item, graph)?;
+                        if let (Some(object), Some(subject), Some(predicate)) =
+                            (object, &subject, &predicate)
+                        {
+                            self.push(subject, predicate, object, graph);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(subject)
+    }
+
+    // Convert a value, list or node object to an RDF term, adding any
+    // statements for nested nodes and lists.
+    fn object(&mut self, item: &Value, graph: Option<&Term>) -> Result<Option<Term>> {
+        let Value::Object(item) = item else {
+            bail!("document is not in expanded form");
+        };
+        if item.contains_key("@value") {
+            return literal(item);
+        }
+        if let Some(list) = item.get("@list") {
+            return self.list(as_slice(list), graph);
+        }
+        self.node(item, graph)
+    }
+
+    // Add the statements for a list, returning the list's head.
+    fn list(&mut self, items: &[Value], graph: Option<&Term>) -> Result<Option<Term>> {
+        if items.is_empty() {
+            return Ok(Some(Term::Iri(RDF_NIL.to_string())));
+        }
+
+        let nodes = (0..items.len()).map(|_| self.blank_node()).collect::<Vec<_>>();
+        let first = Term::Iri(RDF_FIRST.to_string());
+        let rest = Term::Iri(RDF_REST.to_string());
+        for (i, item) in items.iter().enumerate() {
+            if let Some(object) = self.object(item, graph)? {
+                self.push(&nodes[i], &first, object, graph);
+            }
+            let next = nodes.get(i + 1).cloned().unwrap_or_else(|| Term::Iri(RDF_NIL.to_string()));
+            self.push(&nodes[i], &rest, next, graph);
+        }
+        Ok(nodes.into_iter().next())
+    }
+
+    fn push(&mut self, subject: &Term, predicate: &Term, object: Term, graph: Option<&Term>) {
+        self.quads.push(Quad {
+            subject: subject.clone(),
+            predicate: predicate.clone(),
+            object,
+            graph: graph.cloned(),
+        });
+    }
+
+    // The term for an IRI or blank node identifier. Blank node identifiers
+    // are relabelled so they cannot clash with generated identifiers.
+    fn term(&mut self, id: &str) -> Option<Term> {
+        if let Some(label) = id.strip_prefix("_:") {
+            if let Some(relabelled) = self.labels.get(label) {
+                return Some(Term::Blank(relabelled.clone()));
+            }
+            let Term::Blank(relabelled) = self.blank_node() else {
+                unreachable!("blank node");
+            };
+            self.labels.insert(label.to_string(), relabelled.clone());
+            return Some(Term::Blank(relabelled));
+        }
+        is_absolute_iri(id).then(|| Term::Iri(id.to_string()))
+    }
+
+    // The predicate for a property. Blank node predicates are not allowed.
+    fn predicate(&mut self, property: &str) -> Option<Term> {
+        self.term(property).filter(|term| matches!(term, Term::Iri(_)))
+    }
+
+    fn blank_node(&mut self) -> Term {
+        let label = format!("b{}", self.counter);
+        self.counter += 1;
+        Term::Blank(label)
+    }
+}
+
+// Convert a value object to a literal.
+fn literal(item: &Map<String, Value>) -> Result<Option<Term>> {
+    let value = &item["@value"];
+    let type_ = item.get("@type").and_then(Value::as_str);
+    if type_.is_some_and(|t| t != "@json" && !is_absolute_iri(t)) {
+        return Ok(None);
+    }
+
+    let (value, datatype) = if type_ == Some("@json") {
+        (serde_json_canonicalizer::to_string(value)?, RDF_JSON)
+    } else {
+        match value {
+            Value::Bool(b) => (b.to_string(), type_.unwrap_or(XSD_BOOLEAN)),
+            Value::Number(n) if is_double(n, type_) => {
+                (canonical_double(n.as_f64().unwrap_or_default()), type_.unwrap_or(XSD_DOUBLE))
+            }
+            Value::Number(n) => (integer(n), type_.unwrap_or(XSD_INTEGER)),
+            Value::String(s) => {
+                if let Some(language) = item.get("@language").and_then(Value::as_str) {
+                    return Ok(Some(Term::Literal {
+                        value: s.clone(),
+                        datatype: "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString"
+                            .to_string(),
+                        language: Some(language.to_string()),
+                    }));
+                }
+                (s.clone(), type_.unwrap_or(XSD_STRING))
+            }
+            _ => bail!("invalid value object value"),
+        }
+    };
+
+    Ok(Some(Term::Literal {
+        value,
+        datatype: datatype.to_string(),
+        language: None,
+    }))
+}
+
+// Numbers with a fractional part, too large to be an integer, or typed as
+// `xsd:double` are converted to doubles.
+fn is_double(n: &Number, type_: Option<&str>) -> bool {
+    if n.is_i64() || n.is_u64() {
+        return type_ == Some(XSD_DOUBLE);
+    }
+    let f = n.as_f64().unwrap_or_default();
+    f.fract() != 0.0 || f.abs() >= 1e21 || type_ == Some(XSD_DOUBLE)
+}
+
+// The canonical lexical form of an integer.
+fn integer(n: &Number) -> String {
+    if n.is_i64() || n.is_u64() {
+        return n.to_string();
+    }
+    format!("{:.0}", n.as_f64().unwrap_or_default())
+}
+
+// The canonical lexical form of an `xsd:double`: a mantissa with at least one
+// fractional digit (and no trailing zeros) and an exponent, e.g. `1.1E0`.
+fn canonical_double(f: f64) -> String {
+    let formatted = format!("{f:.15E}");
+    let (mantissa, exponent) = formatted.split_once('E').unwrap_or((&formatted, "0"));
+    let mantissa = mantissa.trim_end_matches('0');
+    let mantissa =
+        if mantissa.ends_with('.') { format!("{mantissa}0") } else { mantissa.to_string() };
+    format!("{mantissa}E{exponent}")
+}
+
+fn as_slice(value: &Value) -> &[Value] {
+    match value {
+        Value::Array(values) => values,
+        _ => std::slice::from_ref(value),
+    }
+}
@@ -11,6 +11,8 @@ use credibil_jose::PublicKeyJwk;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::bbs::PublicKey;
+
 /// A DID document can express verification methods, such as cryptographic
 /// public keys, which can be used to authenticate or authorize interactions
 /// with the DID subject or associated parties.
@@ -120,6 +122,14 @@ impl From<PublicKeyJwk> for KeyFormat {
     }
 }
 
+impl From<PublicKey> for KeyFormat {
+    fn from(public_key: PublicKey) -> Self {
+        Self::Multikey {
+            public_key_multibase: public_key.to_multikey(),
+        }
+    }
+}
+
 impl From<String> for KeyFormat {
     fn from(multibase: String) -> Self {
         Self::Multikey {
@@ -9,9 +9,11 @@
 
 mod create;
 mod did;
+mod update;
 
 pub use self::create::*;
 pub use self::did::*;
+pub use self::update::UpdateBuilder;
 use crate::Url;
 
 impl Url {
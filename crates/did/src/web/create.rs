@@ -1,13 +1,16 @@
+use std::sync::Arc;
+
 use anyhow::Result;
 
 use crate::web::create_did;
-use crate::{Document, DocumentBuilder, FromScratch};
+use crate::{Clock, Document, DocumentBuilder, FromScratch, SystemClock};
 
 /// Builder to create a new `did:webvh` document and associated DID url and log.
 ///
 /// Use this to construct a `CreateResult`.
 pub struct CreateBuilder<D> {
     url: String,
+    clock: Arc<dyn Clock>,
     document: D,
 }
 
@@ -22,6 +25,7 @@ impl CreateBuilder<NoDocument> {
     pub fn new(url: impl Into<String>) -> Self {
         Self {
             url: url.into(),
+            clock: Arc::new(SystemClock),
             document: NoDocument,
         }
     }
@@ -31,11 +35,22 @@ impl CreateBuilder<NoDocument> {
     pub fn document(self, builder: DocumentBuilder<FromScratch>) -> CreateBuilder<WithDocument> {
         CreateBuilder {
             url: self.url,
+            clock: self.clock,
             document: WithDocument(builder),
         }
     }
 }
 
+impl<D> CreateBuilder<D> {
+    /// Set the clock used to timestamp the document. Defaults to the system
+    /// clock if not set here.
+    #[must_use]
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+}
+
 impl CreateBuilder<WithDocument> {
     /// Build the `CreateResult` with the provided parameters.
     ///
@@ -44,6 +59,6 @@ impl CreateBuilder<WithDocument> {
     /// Returns an error if the DID URL is invalid or if the document cannot
     /// be built.
     pub fn build(self) -> Result<Document> {
-        self.document.0.build(create_did(&self.url)?)
+        self.document.0.build(create_did(&self.url)?, self.clock.now())
     }
 }
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+
+use crate::{Clock, Document, DocumentBuilder, FromDocument, SystemClock};
+
+/// Builder to update an existing `did:web` document.
+///
+/// Changes made to the [`DocumentBuilder`] are applied to the document it was
+/// created from.
+pub struct UpdateBuilder {
+    clock: Arc<dyn Clock>,
+    document: DocumentBuilder<FromDocument>,
+}
+
+impl UpdateBuilder {
+    /// Create a new `UpdateBuilder` from a [`DocumentBuilder`] populated with
+    /// the changes to make.
+    #[must_use]
+    pub fn new(builder: DocumentBuilder<FromDocument>) -> Self {
+        Self {
+            clock: Arc::new(SystemClock),
+            document: builder,
+        }
+    }
+
+    /// Set the clock used to timestamp the document. Defaults to the system
+    /// clock if not set here.
+    #[must_use]
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Build the updated document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document is not a `did:web` document or if the
+    /// updated document cannot be built.
+    pub fn build(self) -> Result<Document> {
+        let document = self.document.build(self.clock.now())?;
+        if !document.id.starts_with("did:web:") {
+            bail!("document is not a `did:web` document");
+        }
+        Ok(document)
+    }
+}
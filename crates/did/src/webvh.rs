@@ -9,29 +9,34 @@
 mod create;
 mod deactivate;
 mod did;
+mod equivocation;
+mod prepare;
 mod resolve;
+mod rotation;
 mod update;
 mod verify;
+mod watcher;
 
 use chrono::{DateTime, Utc};
 pub use create::{CreateBuilder, CreateResult};
-use credibil_ecc::Algorithm;
 // use crate::provider::{Signer, VerifyBy};
 use credibil_ecc::Signer;
-use credibil_jose::PublicKeyJwk;
 pub use deactivate::{DeactivateBuilder, DeactivateResult};
 pub use did::*;
+pub use equivocation::*;
 use multibase::Base;
+pub use prepare::*;
 pub use resolve::*;
+pub use rotation::KeyRotation;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sha2::Digest;
 pub use update::{UpdateBuilder, UpdateResult};
-use uuid::Uuid;
 pub use verify::*;
+pub use watcher::*;
 
-use crate::Document;
-use crate::proof::Proof;
+use crate::proof::{OneOrMany, Proof};
+use crate::{Clock, Document, IdSource, ProofKey, RandomIds, SystemClock};
 
 /// Placeholder for the self-certifying identifier (SCID) in a DID URL.
 ///
@@ -41,6 +46,14 @@ pub const SCID: &str = "{SCID}";
 
 pub(crate) const VERSION: &str = "0.5";
 
+/// Profile flag added to a log entry's parameters when more than one update
+/// key signature is required to authorize a change.
+///
+/// The `updateThreshold` parameter is not part of the base `did:webvh`
+/// specification so verifiers that do not recognise this profile will not
+/// enforce the threshold.
+pub const MULTISIG_PROFILE: &str = "https://credibil.io/didwebvh/profiles/multisig";
+
 /// A `DidLog` is a set of log entries for a DID document.
 pub type DidLog = Vec<LogEntry>;
 
@@ -111,10 +124,27 @@ impl LogEntry {
     ///
     /// # Errors
     ///
-    /// Will return an error if the signer algorithm is not `EdDSA` or if the
-    /// proof structure cannot be serialized.
+    /// Will return an error if the signer algorithm is not `EdDSA`, `ES256` or
+    /// `ES384` or if the proof structure cannot be serialized.
     pub async fn sign(&mut self, signer: &impl Signer) -> anyhow::Result<()> {
-        let proof = self.proof(signer).await?;
+        self.sign_with(signer, &SystemClock, &RandomIds).await
+    }
+
+    /// Construct a controller's data integrity proof for the log entry using
+    /// the provided clock and identifier source for the proof's `created`
+    /// timestamp and `id`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the signer algorithm is not `EdDSA`, `ES256` or
+    /// `ES384` or if the proof structure cannot be serialized.
+    pub async fn sign_with(
+        &mut self, signer: &impl Signer, clock: &dyn Clock, ids: &dyn IdSource,
+    ) -> anyhow::Result<()> {
+        // controller proofs are independent of any other controller proofs
+        let mut unsigned = self.clone();
+        unsigned.proof = Vec::new();
+        let proof = unsigned.proof_with(signer, clock, ids).await?;
         self.proof.push(proof);
         Ok(())
     }
@@ -128,39 +158,139 @@ impl LogEntry {
     ///
     /// # Errors
     ///
-    /// Will return an error if the signer algorithm is not `EdDSA` or if the
-    /// proof structure cannot be serialized.
+    /// Will return an error if the signer algorithm is not `EdDSA`, `ES256` or
+    /// `ES384` or if the proof structure cannot be serialized.
     pub async fn proof(&self, signer: &impl Signer) -> anyhow::Result<Proof> {
-        let alg = signer.algorithm().await?;
-        if alg != Algorithm::EdDSA {
-            return Err(anyhow::anyhow!("signing algorithm must be Ed25519 (pure EdDSA)"));
-        }
+        self.proof_with(signer, &SystemClock, &RandomIds).await
+    }
+
+    /// Construct a proof that chains to proofs already on the log entry. The
+    /// proof's `previousProof` references the proofs by `id` and it covers
+    /// the log entry together with those proofs.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if a previous proof is not on the log entry, if
+    /// the signer algorithm is not `EdDSA`, `ES256` or `ES384` or if the
+    /// proof structure cannot be serialized.
+    pub async fn chained_proof(
+        &self, signer: &impl Signer, previous: &[String],
+    ) -> anyhow::Result<Proof> {
+        self.chained_proof_with(signer, previous, &SystemClock, &RandomIds).await
+    }
+
+    /// Construct a proof that chains to proofs already on the log entry using
+    /// the provided clock and identifier source for the proof's `created`
+    /// timestamp and `id`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if a previous proof is not on the log entry, if
+    /// the signer algorithm is not `EdDSA`, `ES256` or `ES384` or if the
+    /// proof structure cannot be serialized.
+    pub async fn chained_proof_with(
+        &self, signer: &impl Signer, previous: &[String], clock: &dyn Clock, ids: &dyn IdSource,
+    ) -> anyhow::Result<Proof> {
+        let proof_key = ProofKey::from_algorithm(&signer.algorithm().await?)?;
         let vk = signer.verifying_key().await?;
-        let multi = PublicKeyJwk::from_bytes(&vk.to_bytes())?.to_multibase()?;
+        let multi = proof_key.multikey(&vk.to_bytes())?;
 
-        let config = Proof {
-            id: Some(format!("urn:uuid:{}", Uuid::new_v4())),
-            type_: "DataIntegrityProof".to_string(),
-            cryptosuite: Some("eddsa-jcs-2022".to_string()),
-            verification_method: format!("did:key:{multi}#{multi}"),
-            created: Some(Utc::now()),
-            proof_purpose: "assertionMethod".to_string(),
-            ..Proof::default()
+        let mut config = Self::proof_config(&multi, clock, ids);
+        config.previous_proof = match previous {
+            [] => None,
+            [id] => Some(OneOrMany::One(id.clone())),
+            ids => Some(OneOrMany::Many(ids.to_vec())),
         };
-        let config_data = serde_json_canonicalizer::to_string(&config)?;
-        let config_hash = sha2::Sha256::digest(config_data.as_bytes());
+        let payload = self.signing_payload(&config)?;
+        let signature = signer.sign(&payload).await;
 
-        let data = serde_json_canonicalizer::to_string(self)?;
-        let data_hash = sha2::Sha256::digest(data.as_bytes());
+        let mut proof = config;
+        proof.proof_value = Some(multibase::encode(Base::Base58Btc, signature));
+        Ok(proof)
+    }
+
+    /// Construct a proof from a DID log entry using the provided clock and
+    /// identifier source for the proof's `created` timestamp and `id`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the signer algorithm is not `EdDSA`, `ES256` or
+    /// `ES384` or if the proof structure cannot be serialized.
+    pub async fn proof_with(
+        &self, signer: &impl Signer, clock: &dyn Clock, ids: &dyn IdSource,
+    ) -> anyhow::Result<Proof> {
+        let proof_key = ProofKey::from_algorithm(&signer.algorithm().await?)?;
+        let vk = signer.verifying_key().await?;
+        let multi = proof_key.multikey(&vk.to_bytes())?;
 
-        let payload_bytes = [config_hash.as_slice(), data_hash.as_slice()].concat();
-        let signer = signer.sign(&payload_bytes).await;
-        let value = multibase::encode(Base::Base58Btc, signer);
+        let config = Self::proof_config(&multi, clock, ids);
+        let payload = self.signing_payload(&config)?;
+        let signature = signer.sign(&payload).await;
+        let value = multibase::encode(Base::Base58Btc, signature);
 
-        let mut proof = config.clone();
+        let mut proof = config;
         proof.proof_value = Some(value);
         Ok(proof)
     }
+
+    /// Construct the configuration (a proof without a `proof_value`) for a
+    /// proof made using the multibase-encoded verification key. The clock and
+    /// identifier source provide the proof's `created` timestamp and `id`.
+    ///
+    /// The cryptosuite is `ecdsa-jcs-2019` for P-256 and P-384 keys and
+    /// `eddsa-jcs-2022` otherwise.
+    #[must_use]
+    pub fn proof_config(verification_key: &str, clock: &dyn Clock, ids: &dyn IdSource) -> Proof {
+        // unsupported keys are rejected when the signing payload is generated
+        let proof_key = ProofKey::from_multikey(verification_key).unwrap_or(ProofKey::Ed25519);
+        Proof {
+            id: Some(format!("urn:uuid:{}", ids.new_id())),
+            type_: "DataIntegrityProof".to_string(),
+            cryptosuite: Some(proof_key.jcs_cryptosuite().to_string()),
+            verification_method: format!("did:key:{verification_key}#{verification_key}"),
+            created: Some(clock.now()),
+            proof_purpose: "assertionMethod".to_string(),
+            ..Proof::default()
+        }
+    }
+
+    /// Generate the bytes to sign in order to construct a proof for the log
+    /// entry from the proof configuration.
+    ///
+    /// The payload is the hash of the canonicalized proof configuration
+    /// followed by the hash of the canonicalized log entry. The hash function
+    /// is SHA-384 for P-384 verification keys and SHA-256 otherwise.
+    ///
+    /// When the configuration has a `previousProof`, the log entry's proofs
+    /// are restricted to the referenced proofs.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the verification method is not a supported
+    /// multikey, if a previous proof is not on the log entry or if the proof
+    /// configuration or log entry cannot be canonicalized.
+    pub fn signing_payload(&self, config: &Proof) -> anyhow::Result<Vec<u8>> {
+        let proof_key = ProofKey::from_verification_method(&config.verification_method)?;
+        let mut config = config.clone();
+        config.proof_value = None;
+
+        let previous = config.previous_proofs();
+        if previous.is_empty() {
+            return proof_key.jcs_payload(&config, self);
+        }
+        let mut chained = self.clone();
+        chained.proof = previous
+            .iter()
+            .map(|id| {
+                self.proof
+                    .iter()
+                    .find(|proof| proof.id.as_ref() == Some(id))
+                    .cloned()
+                    .ok_or_else(|| anyhow::anyhow!("previous proof {id} is not on the log entry"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        proof_key.jcs_payload(&config, &chained)
+    }
 }
 
 /// Parameters for a DID log entry.
@@ -191,12 +321,47 @@ pub struct Parameters {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub witness: Option<Witness>,
 
+    /// URLs of watchers: services that cache the DID's log and notify
+    /// resolvers of updates. An empty list removes all watchers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watchers: Option<Vec<String>>,
+
     /// Indicator of whether the DID has been deactivated.
     pub deactivated: bool,
 
     /// Maximum time in seconds the DID should be cached before a full
     /// resolution must be performed.
     pub ttl: u64,
+
+    /// The number of distinct update keys required to sign a log entry. When
+    /// not set, a single update key signature is sufficient.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_threshold: Option<u64>,
+
+    /// Non-standard profiles used by the log. Verifiers that only understand
+    /// the base specification should treat a log with a profile they do not
+    /// recognise as not fully verifiable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<Vec<String>>,
+}
+
+impl Parameters {
+    /// Set the update key threshold, flagging the multi-signature profile
+    /// when more than one signature is required.
+    pub(crate) fn set_update_threshold(&mut self, threshold: u64) {
+        self.update_threshold = Some(threshold);
+        let profile = self.profile.get_or_insert(vec![]);
+        if threshold > 1 {
+            if !profile.iter().any(|p| p == MULTISIG_PROFILE) {
+                profile.push(MULTISIG_PROFILE.to_string());
+            }
+        } else {
+            profile.retain(|p| p != MULTISIG_PROFILE);
+        }
+        if profile.is_empty() {
+            self.profile = None;
+        }
+    }
 }
 
 /// A list of IDs of witnesses and their contribution to verification of changes
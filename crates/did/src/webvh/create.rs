@@ -1,15 +1,18 @@
 //! Create operation for the `did:webvh` method.
 
+use std::sync::Arc;
+
 use anyhow::{Result, bail};
-use chrono::Utc;
-use credibil_ecc::Signer;
+use credibil_ecc::{Entry, Signer};
 use multibase::Base;
 use serde::{Deserialize, Serialize};
 use sha2::Digest;
 
-use crate::webvh::verify::validate_witness;
-use crate::webvh::{LogEntry, Parameters, SCID, VERSION, Witness, create_did};
-use crate::{Document, DocumentBuilder, FromScratch};
+use crate::webvh::verify::{validate_threshold, validate_watchers, validate_witness};
+use crate::webvh::{
+    KeyRotation, LogEntry, Parameters, Prepared, PreparedEntry, SCID, VERSION, Witness, create_did,
+};
+use crate::{Clock, Document, DocumentBuilder, FromScratch, IdSource, RandomIds, SystemClock};
 
 /// Builder to create a new `did:webvh` document and associated DID url and log.
 ///
@@ -20,6 +23,10 @@ pub struct CreateBuilder<U, S, D> {
     next_key_hashes: Option<Vec<String>>,
     witness: Option<Witness>,
     ttl: u64,
+    watchers: Option<Vec<String>>,
+    update_threshold: Option<u64>,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdSource>,
     update_keys: U,
     signer: S,
     document: D,
@@ -34,8 +41,8 @@ pub struct WithUpdateKeys(Vec<String>);
 /// Builder does not have a signer (can't build).
 pub struct NoSigner;
 
-/// Builder has a signer (can build).
-pub struct WithSigner<'a, S: Signer>(pub &'a S);
+/// Builder has one or more signers (can build).
+pub struct WithSigner<'a, S: Signer>(pub Vec<&'a S>);
 
 /// Builder does not have a document (can't build).
 pub struct NoDocument;
@@ -53,6 +60,10 @@ impl CreateBuilder<NoUpdateKeys, NoSigner, NoDocument> {
             next_key_hashes: None,
             witness: None,
             ttl: 0,
+            watchers: None,
+            update_threshold: None,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(RandomIds),
             update_keys: NoUpdateKeys,
             signer: NoSigner,
             document: NoDocument,
@@ -70,6 +81,10 @@ impl CreateBuilder<NoUpdateKeys, NoSigner, NoDocument> {
             next_key_hashes: self.next_key_hashes,
             witness: self.witness,
             ttl: self.ttl,
+            watchers: self.watchers,
+            update_threshold: self.update_threshold,
+            clock: self.clock,
+            ids: self.ids,
             update_keys: NoUpdateKeys,
             signer: NoSigner,
             document: WithDocument(builder),
@@ -95,11 +110,26 @@ impl CreateBuilder<NoUpdateKeys, NoSigner, WithDocument> {
             next_key_hashes: self.next_key_hashes,
             witness: self.witness,
             ttl: self.ttl,
+            watchers: self.watchers,
+            update_threshold: self.update_threshold,
+            clock: self.clock,
+            ids: self.ids,
             update_keys: WithUpdateKeys(update_keys),
             signer: NoSigner,
             document: self.document,
         }
     }
+
+    /// Use a [`KeyRotation`] to provide the update key, commit to the next
+    /// key (by hash) and sign the log entry.
+    #[must_use]
+    pub fn key_rotation(
+        self, rotation: &KeyRotation,
+    ) -> CreateBuilder<WithUpdateKeys, WithSigner<'_, Entry>, WithDocument> {
+        self.update_keys(vec![rotation.update_key().to_string()])
+            .next_key(rotation.next_key())
+            .signer(rotation.signer())
+    }
 }
 
 impl CreateBuilder<WithUpdateKeys, NoSigner, WithDocument> {
@@ -114,8 +144,36 @@ impl CreateBuilder<WithUpdateKeys, NoSigner, WithDocument> {
             next_key_hashes: self.next_key_hashes,
             witness: self.witness,
             ttl: self.ttl,
+            watchers: self.watchers,
+            update_threshold: self.update_threshold,
+            clock: self.clock,
+            ids: self.ids,
             update_keys: self.update_keys,
-            signer: WithSigner(signer),
+            signer: WithSigner(vec![signer]),
+            document: self.document,
+        }
+    }
+
+    /// Add several signers to the builder.
+    ///
+    /// Each signer adds a data integrity proof to the log entry. Use when an
+    /// update threshold requires more than one update key signature.
+    #[must_use]
+    pub fn signers<'a, S: Signer>(
+        self, signers: &[&'a S],
+    ) -> CreateBuilder<WithUpdateKeys, WithSigner<'a, S>, WithDocument> {
+        CreateBuilder {
+            url: self.url,
+            portable: self.portable,
+            next_key_hashes: self.next_key_hashes,
+            witness: self.witness,
+            ttl: self.ttl,
+            watchers: self.watchers,
+            update_threshold: self.update_threshold,
+            clock: self.clock,
+            ids: self.ids,
+            update_keys: self.update_keys,
+            signer: WithSigner(signers.to_vec()),
             document: self.document,
         }
     }
@@ -162,23 +220,49 @@ impl<U, S, D> CreateBuilder<U, S, D> {
         self.ttl = ttl;
         self
     }
-}
 
-impl<S: Signer> CreateBuilder<WithUpdateKeys, WithSigner<'_, S>, WithDocument> {
-    /// Build the new log entry.
-    ///
-    /// Provide a `Provable` `Signer` to construct a data integrity proof. To
-    /// add more proofs, call the `sign` method on the log entry after building.
-    ///
-    /// # Errors
+    /// Set the URLs of watchers that cache the DID's log and notify resolvers
+    /// of updates.
+    #[must_use]
+    pub fn watchers(mut self, watchers: Vec<String>) -> Self {
+        self.watchers = Some(watchers);
+        self
+    }
+
+    /// Set the number of distinct update keys required to sign each log
+    /// entry. Defaults to a single signature if not set here.
     ///
-    /// Will fail if secondary algorithms fail such as generating a hash of the
-    /// log entry to calculate the `SCID` or version ID, or failing to replace
-    /// the placeholder `SCID` with the calculated one. Will also fail if the
-    /// provided signer fails to sign the log entry.
-    pub async fn build(self) -> Result<CreateResult> {
+    /// A threshold greater than one is flagged using the
+    /// [`MULTISIG_PROFILE`](crate::webvh::MULTISIG_PROFILE) profile.
+    #[must_use]
+    pub const fn update_threshold(mut self, threshold: u64) -> Self {
+        self.update_threshold = Some(threshold);
+        self
+    }
+
+    /// Set the clock used to timestamp the document, log entry and proofs.
+    /// Defaults to the system clock if not set here.
+    #[must_use]
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Set the source of proof identifiers. Defaults to random UUIDs if not
+    /// set here.
+    #[must_use]
+    pub fn id_source(mut self, ids: impl IdSource + 'static) -> Self {
+        self.ids = Arc::new(ids);
+        self
+    }
+}
+
+impl<S> CreateBuilder<WithUpdateKeys, S, WithDocument> {
+    // Build the unsigned, self-certified initial log entry.
+    fn unsigned(self) -> Result<(LogEntry, S)> {
         let did = create_did(&self.url)?;
-        let document = self.document.0.build(&did)?;
+        let now = self.clock.now();
+        let document = self.document.0.build(&did, now)?;
 
         //  update keys cannot be empty.
         if self.update_keys.0.is_empty() {
@@ -187,24 +271,35 @@ impl<S: Signer> CreateBuilder<WithUpdateKeys, WithSigner<'_, S>, WithDocument> {
         if let Some(witness) = &self.witness {
             validate_witness(witness)?;
         }
+        if let Some(threshold) = self.update_threshold {
+            validate_threshold(threshold, &self.update_keys.0)?;
+        }
+        if let Some(watchers) = &self.watchers {
+            validate_watchers(watchers)?;
+        }
+
+        let mut parameters = Parameters {
+            method: format!("did:webvh:{VERSION}"),
+            scid: SCID.to_string(),
+            update_keys: self.update_keys.0,
+            portable: self.portable,
+            next_key_hashes: self.next_key_hashes,
+            witness: self.witness,
+            watchers: self.watchers.filter(|w| !w.is_empty()),
+            deactivated: false,
+            ttl: self.ttl,
+            update_threshold: None,
+            profile: None,
+        };
+        if let Some(threshold) = self.update_threshold {
+            parameters.set_update_threshold(threshold);
+        }
 
         // initial log entry uses a placeholder (`{SCID}`) for the SCID value
         let initial_entry = LogEntry {
             version_id: SCID.to_string(),
-            version_time: document
-                .did_document_metadata
-                .as_ref()
-                .map_or_else(Utc::now, |m| m.created),
-            parameters: Parameters {
-                method: format!("did:webvh:{VERSION}"),
-                scid: SCID.to_string(),
-                update_keys: self.update_keys.0,
-                portable: self.portable,
-                next_key_hashes: self.next_key_hashes,
-                witness: self.witness,
-                deactivated: false,
-                ttl: self.ttl,
-            },
+            version_time: document.did_document_metadata.as_ref().map_or(now, |m| m.created),
+            parameters,
             state: document,
             proof: vec![],
         };
@@ -219,10 +314,58 @@ impl<S: Signer> CreateBuilder<WithUpdateKeys, WithSigner<'_, S>, WithDocument> {
         let mut log_entry = serde_json::from_str::<LogEntry>(&self_certified)?;
         let entry_hash = log_entry.hash()?;
         log_entry.version_id = format!("1-{entry_hash}");
-        log_entry.sign(self.signer.0).await?;
+
+        Ok((log_entry, self.signer))
+    }
+}
+
+impl CreateBuilder<WithUpdateKeys, NoSigner, WithDocument> {
+    /// Prepare the new log entry for signing by offline signers.
+    ///
+    /// A signing request is created for each update key. Sign the request
+    /// payloads and call [`Prepared::finalize`] with the detached signatures to
+    /// complete the log entry.
+    ///
+    /// # Errors
+    ///
+    /// Will fail if secondary algorithms fail such as generating a hash of the
+    /// log entry to calculate the `SCID` or version ID, or failing to replace
+    /// the placeholder `SCID` with the calculated one.
+    pub fn prepare(self) -> Result<Prepared> {
+        let (clock, ids) = (Arc::clone(&self.clock), Arc::clone(&self.ids));
+        let (log_entry, _) = self.unsigned()?;
+        let update_keys = log_entry.parameters.update_keys.clone();
+        let prepared = PreparedEntry::new(log_entry, &update_keys, clock.as_ref(), ids.as_ref())?;
+
+        Ok(Prepared {
+            did: prepared.entry.state.id.clone(),
+            log: vec![],
+            entries: vec![prepared],
+        })
+    }
+}
+
+impl<S: Signer> CreateBuilder<WithUpdateKeys, WithSigner<'_, S>, WithDocument> {
+    /// Build the new log entry.
+    ///
+    /// Each `Signer` provided constructs a data integrity proof. To add more
+    /// proofs, call the `sign` method on the log entry after building.
+    ///
+    /// # Errors
+    ///
+    /// Will fail if secondary algorithms fail such as generating a hash of the
+    /// log entry to calculate the `SCID` or version ID, or failing to replace
+    /// the placeholder `SCID` with the calculated one. Will also fail if the
+    /// provided signer fails to sign the log entry.
+    pub async fn build(self) -> Result<CreateResult> {
+        let (clock, ids) = (Arc::clone(&self.clock), Arc::clone(&self.ids));
+        let (mut log_entry, signer) = self.unsigned()?;
+        for signer in signer.0 {
+            log_entry.sign_with(signer, clock.as_ref(), ids.as_ref()).await?;
+        }
 
         Ok(CreateResult {
-            did,
+            did: log_entry.state.id.clone(),
             document: log_entry.state.clone(),
             log: vec![log_entry],
         })
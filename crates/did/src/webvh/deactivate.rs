@@ -1,17 +1,17 @@
 //! Deactivate (revoke) operation for the `did:webvh` method.
 
 use std::string::ToString;
+use std::sync::Arc;
 
 use anyhow::bail;
-use chrono::Utc;
-use credibil_ecc::Signer;
+use credibil_ecc::{Entry, Signer};
 use multibase::Base;
 use serde::{Deserialize, Serialize};
 use sha2::Digest;
 
 use super::verify::validate_witness;
-use super::{LogEntry, Witness};
-use crate::Document;
+use super::{KeyRotation, LogEntry, Prepared, PreparedEntry, Witness};
+use crate::{Clock, Document, IdSource, RandomIds, SystemClock};
 
 /// Builder for deactivating a DID document and associated log entry (or 2
 /// entries if there is key rotation).
@@ -21,6 +21,8 @@ pub struct DeactivateBuilder<S> {
     witness: Option<Witness>,
     log: Vec<LogEntry>,
     doc: Document,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdSource>,
 
     signer: S,
 }
@@ -28,8 +30,8 @@ pub struct DeactivateBuilder<S> {
 /// Builder does not have a signer (can't build).
 pub struct WithoutSigner;
 
-/// Builder has a signer (can build).
-pub struct WithSigner<'a, S: Signer>(pub &'a S);
+/// Builder has one or more signers (can build).
+pub struct WithSigner<'a, S: Signer>(pub Vec<&'a S>);
 
 impl DeactivateBuilder<WithoutSigner> {
     /// Crate a new `DeactivateBuilder` populated with the current log entries.
@@ -49,6 +51,8 @@ impl DeactivateBuilder<WithoutSigner> {
             witness: last_entry.parameters.witness.clone(),
             log: log.to_vec(),
             doc: last_entry.state.clone(),
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(RandomIds),
 
             signer: WithoutSigner,
         })
@@ -140,6 +144,22 @@ impl DeactivateBuilder<WithoutSigner> {
         self
     }
 
+    /// Set the clock used to timestamp the log entries and proofs. Defaults to
+    /// the system clock if not set here.
+    #[must_use]
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Set the source of proof identifiers. Defaults to random UUIDs if not
+    /// set here.
+    #[must_use]
+    pub fn id_source(mut self, ids: impl IdSource + 'static) -> Self {
+        self.ids = Arc::new(ids);
+        self
+    }
+
     /// Provide a signer to sign the log entry.
     #[must_use]
     pub fn signer<S: Signer>(self, signer: &S) -> DeactivateBuilder<WithSigner<'_, S>> {
@@ -149,33 +169,59 @@ impl DeactivateBuilder<WithoutSigner> {
             witness: self.witness,
             log: self.log,
             doc: self.doc,
+            clock: self.clock,
+            ids: self.ids,
 
-            signer: WithSigner(signer),
+            signer: WithSigner(vec![signer]),
         }
     }
-}
 
-impl<S: Signer> DeactivateBuilder<WithSigner<'_, S>> {
-    /// Build the new log entry/entries.
+    /// Provide several signers to sign the log entries.
     ///
-    /// If the last log entry has a non-empty `next_key_hashes`, two log entries
-    /// will be created: one to nullify the `next_key_hashes` and one to
-    /// deactivate the DID.
+    /// Each signer adds a data integrity proof to each log entry. Use when an
+    /// update threshold requires more than one update key signature.
+    #[must_use]
+    pub fn signers<'a, S: Signer>(self, signers: &[&'a S]) -> DeactivateBuilder<WithSigner<'a, S>> {
+        DeactivateBuilder {
+            update_keys: self.update_keys,
+            next_key_hashes: self.next_key_hashes,
+            witness: self.witness,
+            log: self.log,
+            doc: self.doc,
+            clock: self.clock,
+            ids: self.ids,
+
+            signer: WithSigner(signers.to_vec()),
+        }
+    }
+
+    /// Use a [`KeyRotation`] to rotate the update keys and sign the log
+    /// entries.
     ///
-    /// Provide a `Provable` `Signer` to construct a data integrity proof. To
-    /// add more proofs, call the `sign` method on the log entry/entries after
-    /// building.
+    /// The rotation should already have been rotated (see
+    /// [`KeyRotation::rotate`]) so its update key is the next key committed to
+    /// in the current log entry. No next key is committed to as the DID will
+    /// not be updated again.
     ///
     /// # Errors
-    /// Will fail if secondary algorithms fail such as generating a hash of the
-    /// log entry to calculate the version ID. Will also fail if the provided
-    /// signer fails to sign the log entry.
-    pub async fn build(&self) -> anyhow::Result<DeactivateResult> {
-        let mut log = self.log.clone();
-        let Some(last_entry) = log.last() else {
+    ///
+    /// Will fail if the rotation's update key does not match the current next
+    /// key hashes.
+    pub fn key_rotation(
+        self, rotation: &KeyRotation,
+    ) -> anyhow::Result<DeactivateBuilder<WithSigner<'_, Entry>>> {
+        Ok(self.rotate_keys(&[rotation.update_key()], &[])?.signer(rotation.signer()))
+    }
+}
+
+impl<S> DeactivateBuilder<S> {
+    // Build the unsigned log entry/entries.
+    fn unsigned(&self) -> anyhow::Result<Vec<LogEntry>> {
+        let Some(last_entry) = self.log.last() else {
             bail!("log must not be empty.");
         };
         let mut last_entry = last_entry.clone();
+        let mut entries = vec![];
 
         let mut params = last_entry.parameters.clone();
         params.update_keys.clone_from(&self.update_keys);
@@ -185,7 +231,7 @@ impl<S: Signer> DeactivateBuilder<WithSigner<'_, S>> {
             params.next_key_hashes = None;
             let mut entry = LogEntry {
                 version_id: last_entry.version_id.clone(),
-                version_time: Utc::now(),
+                version_time: self.clock.now(),
                 parameters: params.clone(),
                 state: self.doc.clone(),
                 proof: vec![],
@@ -200,25 +246,25 @@ impl<S: Signer> DeactivateBuilder<WithSigner<'_, S>> {
             version_number += 1;
             entry.version_id = format!("{version_number}-{entry_hash}");
 
-            entry.sign(self.signer.0).await?;
             last_entry.clone_from(&entry);
-            log.push(entry);
+            entries.push(entry);
         }
 
         params.update_keys = Vec::new();
         params.next_key_hashes = None;
         params.deactivated = true;
+        let now = self.clock.now();
         let mut md = self.doc.did_document_metadata.clone().unwrap_or_default();
-        md.updated = Some(Utc::now());
+        md.updated = Some(now);
         md.deactivated = Some(true);
         let mut doc = self.doc.clone();
         doc.did_document_metadata = Some(md);
 
         let mut entry = LogEntry {
             version_id: last_entry.version_id.clone(),
-            version_time: Utc::now(),
-            parameters: params.clone(),
-            state: doc.clone(),
+            version_time: now,
+            parameters: params,
+            state: doc,
             proof: vec![],
         };
 
@@ -230,13 +276,73 @@ impl<S: Signer> DeactivateBuilder<WithSigner<'_, S>> {
         let mut version_number = parts[0].parse::<u64>()?;
         version_number += 1;
         entry.version_id = format!("{version_number}-{entry_hash}");
+        entries.push(entry);
 
-        entry.sign(self.signer.0).await?;
-        log.push(entry);
+        Ok(entries)
+    }
+}
+
+impl DeactivateBuilder<WithoutSigner> {
+    /// Prepare the new log entry/entries for signing by offline signers.
+    ///
+    /// A signing request is created for each of the current update keys on
+    /// each new log entry. Sign the request payloads and call
+    /// [`Prepared::finalize`] with the detached signatures to complete the
+    /// log.
+    ///
+    /// # Errors
+    /// Will fail if secondary algorithms fail such as generating a hash of the
+    /// log entry to calculate the version ID.
+    pub fn prepare(&self) -> anyhow::Result<Prepared> {
+        let mut entries = vec![];
+        for entry in self.unsigned()? {
+            entries.push(PreparedEntry::new(
+                entry,
+                &self.update_keys,
+                self.clock.as_ref(),
+                self.ids.as_ref(),
+            )?);
+        }
+
+        Ok(Prepared {
+            did: self.doc.id.clone(),
+            log: self.log.clone(),
+            entries,
+        })
+    }
+}
+
+impl<S: Signer> DeactivateBuilder<WithSigner<'_, S>> {
+    /// Build the new log entry/entries.
+    ///
+    /// If the last log entry has a non-empty `next_key_hashes`, two log entries
+    /// will be created: one to nullify the `next_key_hashes` and one to
+    /// deactivate the DID.
+    ///
+    /// Each `Signer` provided constructs a data integrity proof. To add more
+    /// proofs, call the `sign` method on the log entry/entries after building.
+    ///
+    /// # Errors
+    /// Will fail if secondary algorithms fail such as generating a hash of the
+    /// log entry to calculate the version ID. Will also fail if the provided
+    /// signer fails to sign the log entry.
+    pub async fn build(&self) -> anyhow::Result<DeactivateResult> {
+        let mut log = self.log.clone();
+        for mut entry in self.unsigned()? {
+            for signer in &self.signer.0 {
+                entry.sign_with(*signer, self.clock.as_ref(), self.ids.as_ref()).await?;
+            }
+            log.push(entry);
+        }
+
+        let Some(last_entry) = log.last() else {
+            bail!("log must not be empty.");
+        };
+        let document = last_entry.state.clone();
 
         Ok(DeactivateResult {
-            did: doc.id.clone(),
-            document: doc,
+            did: document.id.clone(),
+            document,
             log,
         })
     }
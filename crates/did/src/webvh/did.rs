@@ -1,10 +1,12 @@
-//! Helper functions for converting HTTP URLs into `did:webvh` DIDs.
+//! Helper functions for converting HTTP URLs into `did:webvh` DIDs and
+//! `did:webvh` documents into their `did:web` equivalent.
 
 use std::fmt::Write;
 
 use anyhow::bail;
 use url::Url;
 
+use crate::Document;
 use crate::webvh::SCID;
 
 /// Construct a default `did:webvh` DID from a URL.
@@ -59,6 +61,36 @@ pub fn parse_url(url: &str) -> anyhow::Result<String> {
     Ok(host)
 }
 
+/// Derive the parallel `did:web` document for a `did:webvh` document.
+///
+/// The `did:webvh:{SCID}:` prefix is replaced with `did:web:` throughout the
+/// document and the `did:webvh` DID is added to `alsoKnownAs`, allowing the
+/// result to be published as `did.json` alongside the DID log.
+///
+/// # Errors
+///
+/// Will return an error if the document is not a `did:webvh` document.
+pub fn to_web_document(document: &Document) -> anyhow::Result<Document> {
+    let Some(scid_and_location) = document.id.strip_prefix("did:webvh:") else {
+        bail!("document is not a `did:webvh` document");
+    };
+    let Some((scid, location)) = scid_and_location.split_once(':') else {
+        bail!("DID is not a valid did:webvh - no SCID");
+    };
+    let web_did = format!("did:web:{location}");
+
+    let json = serde_json::to_string(document)?;
+    let json = json.replace(&format!("did:webvh:{scid}:"), "did:web:");
+    let mut web_document: Document = serde_json::from_str(&json)?;
+
+    let also_known_as = web_document.also_known_as.get_or_insert(vec![]);
+    also_known_as.retain(|id| *id != web_did);
+    if !also_known_as.contains(&document.id) {
+        also_known_as.push(document.id.clone());
+    }
+    Ok(web_document)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,216 @@
+//! Fork and equivocation detection for the `did:webvh` method.
+//!
+//! A host could serve different (individually valid) logs for the same DID to
+//! different verifiers. Comparing logs for the same SCID obtained from
+//! different fetches, mirrors or witnesses reveals any fork: two or more
+//! signed entries with the same version number but different contents.
+//!
+//! Because every log entry is signed by an authorized update key, a fork is
+//! evidence the controller (or someone holding its update keys) signed
+//! conflicting histories. A host on its own can only withhold entries, which
+//! shows up as a lagging log rather than a fork.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use super::LogEntry;
+use super::resolve::resolve_log;
+
+/// A DID log along with where it was obtained.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourcedLog {
+    /// Where the log was obtained, for example the URL of a mirror or the ID
+    /// of a witness.
+    pub source: String,
+
+    /// The log entries.
+    pub log: Vec<LogEntry>,
+}
+
+/// Result of comparing logs for the same DID.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkReport {
+    /// The SCID shared by the logs.
+    pub scid: String,
+
+    /// The version ID of the last entry common to the logs involved in a fork
+    /// or, when there is no fork, the last entry common to all the logs.
+    /// `None` if the logs diverge at the first entry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_common_version: Option<String>,
+
+    /// Sources whose logs are a strict prefix of the longest log.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub lagging: Vec<String>,
+
+    /// Evidence of equivocation, if the logs have forked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub evidence: Option<EquivocationEvidence>,
+}
+
+/// Self-contained proof that conflicting log entries were signed for the same
+/// DID version.
+///
+/// The evidence holds the shared history up to the fork and each of the
+/// conflicting entries, so it can be published and checked independently
+/// using [`EquivocationEvidence::verify`].
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EquivocationEvidence {
+    /// The SCID of the DID.
+    pub scid: String,
+
+    /// The log entries common to every branch, preceding the fork.
+    pub shared: Vec<LogEntry>,
+
+    /// The conflicting entries, one per distinct entry at the fork.
+    pub branches: Vec<ForkBranch>,
+}
+
+/// One of the conflicting entries at a fork.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkBranch {
+    /// The sources that served this entry.
+    pub sources: Vec<String>,
+
+    /// The signed, conflicting log entry.
+    pub entry: LogEntry,
+}
+
+/// Compare two or more logs for the same DID and report any fork.
+///
+/// Each log is verified in full. Since each version ID includes a hash
+/// chained to the previous version, logs with the same version ID at a given
+/// position share all preceding entries. The first position where the logs
+/// hold different entries is the fork.
+///
+/// # Errors
+///
+/// Will fail if fewer than two logs are provided, if any log is empty or
+/// fails verification, or if the logs are not for the same SCID.
+pub async fn detect_equivocation(logs: &[SourcedLog]) -> Result<ForkReport> {
+    if logs.len() < 2 {
+        bail!("at least two logs are required");
+    }
+
+    let mut scid = None;
+    for sourced in logs {
+        let Some(first) = sourced.log.first() else {
+            bail!("log from {} is empty", sourced.source);
+        };
+        if let Err(e) = resolve_log(&sourced.log, None, None).await {
+            bail!("log from {} failed verification: {e}", sourced.source);
+        }
+        match &scid {
+            None => scid = Some(first.parameters.scid.clone()),
+            Some(scid) if *scid != first.parameters.scid => {
+                bail!("log from {} is for a different SCID", sourced.source);
+            }
+            Some(_) => {}
+        }
+    }
+    let scid = scid.unwrap_or_default();
+
+    let longest = logs.iter().map(|l| l.log.len()).max().unwrap_or_default();
+    for index in 0..longest {
+        // group the logs holding an entry at this position by version ID
+        let mut groups: BTreeMap<&str, (Vec<String>, &LogEntry)> = BTreeMap::new();
+        for sourced in logs {
+            if let Some(entry) = sourced.log.get(index) {
+                groups
+                    .entry(entry.version_id.as_str())
+                    .or_insert_with(|| (vec![], entry))
+                    .0
+                    .push(sourced.source.clone());
+            }
+        }
+        if groups.len() < 2 {
+            continue;
+        }
+
+        // every log reaching this position shares the preceding entries
+        let Some(forked) = logs.iter().find(|l| l.log.len() > index) else {
+            bail!("no log reaches the fork");
+        };
+        let shared = forked.log[..index].to_vec();
+        let last_common_version = shared.last().map(|e| e.version_id.clone());
+        let branches = groups
+            .into_values()
+            .map(|(sources, entry)| ForkBranch {
+                sources,
+                entry: entry.clone(),
+            })
+            .collect();
+
+        return Ok(ForkReport {
+            scid: scid.clone(),
+            last_common_version,
+            lagging: vec![],
+            evidence: Some(EquivocationEvidence {
+                scid,
+                shared,
+                branches,
+            }),
+        });
+    }
+
+    // no fork: the shortest log is common to all
+    let shortest = logs.iter().map(|l| l.log.len()).min().unwrap_or_default();
+    let last_common_version =
+        shortest.checked_sub(1).and_then(|i| logs[0].log.get(i)).map(|e| e.version_id.clone());
+    let lagging = logs.iter().filter(|l| l.log.len() < longest).map(|l| l.source.clone()).collect();
+
+    Ok(ForkReport {
+        scid,
+        last_common_version,
+        lagging,
+        evidence: None,
+    })
+}
+
+impl EquivocationEvidence {
+    /// Verify the evidence.
+    ///
+    /// Checks there are at least two distinct entries at the same position
+    /// following the shared history and that the shared history followed by
+    /// each conflicting entry is a valid log for the SCID.
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the evidence does not demonstrate equivocation.
+    pub async fn verify(&self) -> Result<()> {
+        if self.branches.len() < 2 {
+            bail!("equivocation requires at least two conflicting entries");
+        }
+
+        let expected = self.shared.len() + 1;
+        let mut version_ids = vec![];
+        for branch in &self.branches {
+            let entry = &branch.entry;
+            let Some((number, _)) = entry.version_id.split_once('-') else {
+                bail!("log entry version id has an unexpected format");
+            };
+            if number.parse::<usize>()? != expected {
+                bail!("entry {} is not at the fork", entry.version_id);
+            }
+            if version_ids.contains(&&entry.version_id) {
+                bail!("duplicate entry {}", entry.version_id);
+            }
+            version_ids.push(&entry.version_id);
+
+            let mut log = self.shared.clone();
+            log.push(entry.clone());
+            if log[0].parameters.scid != self.scid {
+                bail!("entry {} is for a different SCID", entry.version_id);
+            }
+            resolve_log(&log, None, None).await?;
+        }
+
+        Ok(())
+    }
+}
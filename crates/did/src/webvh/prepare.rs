@@ -0,0 +1,186 @@
+//! Two-phase (prepare/finalize) construction of `did:webvh` log entries.
+//!
+//! Used when update keys are held by offline (for example, air-gapped)
+//! signers. The `prepare` method on each builder outputs the unsigned log
+//! entries along with the exact payloads to be signed. Detached signatures
+//! are then provided to [`Prepared::finalize`] to assemble and verify the
+//! proofs.
+
+use anyhow::{Result, bail};
+use multibase::Base;
+use serde::{Deserialize, Serialize};
+
+use super::LogEntry;
+use super::resolve::resolve_log_with;
+use super::verify::verify_proofs;
+use crate::proof::Proof;
+use crate::{Clock, Document, IdSource, SystemClock};
+
+/// Log entries prepared for offline signing.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Prepared {
+    /// The `did:webvh` DID.
+    pub did: String,
+
+    /// The existing (signed) log entries.
+    pub log: Vec<LogEntry>,
+
+    /// The new, unsigned log entries in the order they are to be appended to
+    /// the log.
+    pub entries: Vec<PreparedEntry>,
+}
+
+/// An unsigned log entry and the signatures required to complete it.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreparedEntry {
+    /// The unsigned log entry.
+    pub entry: LogEntry,
+
+    /// A signing request for each update key that may sign the entry.
+    pub requests: Vec<SigningRequest>,
+}
+
+/// A request for a detached signature over a log entry.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SigningRequest {
+    /// The version ID of the log entry to be signed.
+    pub version_id: String,
+
+    /// The multibase-encoded update key expected to sign the payload.
+    pub verification_key: String,
+
+    /// The proof configuration the signature will be bound to.
+    pub proof_config: Proof,
+
+    /// The multibase-encoded (base58btc) bytes to be signed.
+    pub payload: String,
+}
+
+/// A signature created by an offline signer in response to a
+/// [`SigningRequest`].
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetachedSignature {
+    /// The version ID of the signed log entry.
+    pub version_id: String,
+
+    /// The multibase-encoded update key that created the signature.
+    pub verification_key: String,
+
+    /// The multibase-encoded (base58btc) signature.
+    pub signature: String,
+}
+
+impl PreparedEntry {
+    /// Create signing requests for the entry, one per verification key.
+    pub(crate) fn new(
+        entry: LogEntry, verification_keys: &[String], clock: &dyn Clock, ids: &dyn IdSource,
+    ) -> Result<Self> {
+        let mut requests = vec![];
+        for key in verification_keys {
+            let proof_config = LogEntry::proof_config(key, clock, ids);
+            let payload = entry.signing_payload(&proof_config)?;
+            requests.push(SigningRequest {
+                version_id: entry.version_id.clone(),
+                verification_key: key.clone(),
+                proof_config,
+                payload: multibase::encode(Base::Base58Btc, payload),
+            });
+        }
+        Ok(Self { entry, requests })
+    }
+}
+
+impl Prepared {
+    /// All signing requests, in log entry order.
+    pub fn requests(&self) -> impl Iterator<Item = &SigningRequest> {
+        self.entries.iter().flat_map(|e| e.requests.iter())
+    }
+
+    /// Assemble proofs from detached signatures and verify the resulting log.
+    ///
+    /// Not every signing request needs a signature, but each entry must be
+    /// signed by enough update keys to meet the update threshold (or one
+    /// update key when there is no threshold).
+    ///
+    /// # Errors
+    ///
+    /// Will fail if a signature does not match a signing request, if any of
+    /// the resulting proofs are invalid, or if the completed log cannot be
+    /// resolved.
+    pub async fn finalize(self, signatures: &[DetachedSignature]) -> Result<FinalizeResult> {
+        self.finalize_with(signatures, &SystemClock).await
+    }
+
+    /// Assemble proofs from detached signatures and verify the resulting log
+    /// using the provided clock as the current time.
+    ///
+    /// # Errors
+    ///
+    /// Will fail if a signature does not match a signing request, if any of
+    /// the resulting proofs are invalid, or if the completed log cannot be
+    /// resolved.
+    pub async fn finalize_with(
+        self, signatures: &[DetachedSignature], clock: &dyn Clock,
+    ) -> Result<FinalizeResult> {
+        for signature in signatures {
+            if !self.requests().any(|r| {
+                r.version_id == signature.version_id
+                    && r.verification_key == signature.verification_key
+            }) {
+                bail!(
+                    "no signing request for version {} by {}",
+                    signature.version_id,
+                    signature.verification_key
+                );
+            }
+        }
+
+        let mut log = self.log;
+        for prepared in self.entries {
+            let mut entry = prepared.entry;
+            for request in prepared.requests {
+                let Some(signature) = signatures.iter().find(|s| {
+                    s.version_id == request.version_id
+                        && s.verification_key == request.verification_key
+                }) else {
+                    continue;
+                };
+                let mut proof = request.proof_config;
+                proof.proof_value = Some(signature.signature.clone());
+                entry.proof.push(proof);
+            }
+            verify_proofs(&entry).await?;
+            log.push(entry);
+        }
+
+        // verify the completed log
+        resolve_log_with(&log, None, None, clock).await?;
+
+        let Some(last_entry) = log.last() else {
+            bail!("log must not be empty.");
+        };
+        Ok(FinalizeResult {
+            did: self.did,
+            document: last_entry.state.clone(),
+            log,
+        })
+    }
+}
+
+/// Output of a `finalize` operation.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct FinalizeResult {
+    /// The `did:webvh` DID.
+    pub did: String,
+
+    /// The `did:webvh` document.
+    pub document: Document,
+
+    /// Version history log consisting of the original log (if any) appended
+    /// with the newly signed entries.
+    pub log: Vec<LogEntry>,
+}
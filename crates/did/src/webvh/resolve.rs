@@ -4,16 +4,15 @@
 //!
 //! See: <https://identity.foundation/didwebvh/next/>
 
-use std::vec;
-
 use anyhow::{Result, bail};
 use chrono::{DateTime, Utc};
 use multibase::Base;
+use serde::{Deserialize, Serialize};
 use sha2::Digest;
 
-use super::verify::{verify_proofs, verify_witness};
+use super::verify::{verify_signers, verify_witness};
 use super::{LogEntry, SCID, WitnessEntry};
-use crate::{Document, DocumentMetadataBuilder, QueryParams, Url};
+use crate::{Clock, Document, DocumentMetadataBuilder, QueryParams, SystemClock, Url};
 
 impl Url {
     /// Convert a `did:webvh` URL to an HTTP URL pointing to the location of the
@@ -76,69 +75,257 @@ impl Url {
 /// # Errors
 ///
 /// Will fail if the log entries are invalid.
-#[allow(clippy::too_many_lines)]
 pub async fn resolve_log(
     log: &[LogEntry], proofs: Option<&[WitnessEntry]>, parameters: Option<&QueryParams>,
+) -> Result<Document> {
+    resolve_log_with(log, proofs, parameters, &SystemClock).await
+}
+
+/// Verification of the contents of the `did.jsonl` file and resolution into a
+/// DID document using the provided clock as the current time.
+///
+/// Log entries with a version time later than the clock's current time are
+/// rejected. Use with a fixed clock to resolve a log reproducibly or as of a
+/// given time (in combination with the `versionTime` query parameter).
+///
+/// # Errors
+///
+/// Will fail if the log entries are invalid.
+pub async fn resolve_log_with(
+    log: &[LogEntry], proofs: Option<&[WitnessEntry]>, parameters: Option<&QueryParams>,
+    clock: &dyn Clock,
 ) -> Result<Document> {
     if log.is_empty() {
         bail!("log entries are empty");
     }
 
-    let mut prev_index = 0;
-    // let mut prev_version = SCID.to_string();
-    let mut prev_version = log[0].parameters.scid.clone();
-    let mut prev_time = DateTime::<Utc>::MIN_UTC;
-    let mut doc = Document::default();
-    let mut prev_next_key_hashes: Option<Vec<String>> = None;
+    let now = clock.now();
+    let mut checkpoint = Checkpoint::genesis(&log[0].parameters.scid);
     for i in 0..log.len() {
+        checkpoint.apply(&log[i], proofs, now).await?;
+
+        // Check for explicit version ID or version time request. (Otherwise
+        // the latest version is returned.)
+        if let Some(params) = parameters {
+            if let Some(version_id) = &params.version_id {
+                if *version_id == log[i].version_id {
+                    break;
+                }
+            }
+            if let Some(version_time) = &params.version_time {
+                let version_time = version_time.parse::<DateTime<Utc>>()?;
+                if version_time >= log[i].version_time {
+                    if i < log.len() - 1 {
+                        if version_time <= log[i + 1].version_time {
+                            break;
+                        }
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    Ok(checkpoint.document)
+}
+
+/// The verified state of a DID log as at its latest entry.
+///
+/// A checkpoint allows a log to be verified incrementally: once a log has been
+/// verified, new entries can be checked against the checkpoint without
+/// downloading or re-verifying the entries it covers. Because each version ID
+/// is chained to the previous version, a log that contains the checkpoint's
+/// version ID starts with the same (verified) entries.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Checkpoint {
+    /// The SCID of the DID.
+    pub scid: String,
+
+    /// The version number of the latest verified entry.
+    pub version_number: u64,
+
+    /// The version ID of the latest verified entry.
+    pub version_id: String,
+
+    /// The version time of the latest verified entry.
+    pub version_time: DateTime<Utc>,
+
+    /// The next key hashes committed to by the latest verified entry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_key_hashes: Option<Vec<String>>,
+
+    /// The update keys authorized by the latest verified entry.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub update_keys: Vec<String>,
+
+    /// The update threshold set by the latest verified entry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_threshold: Option<u64>,
+
+    /// The DID document resolved from the latest verified entry.
+    pub document: Document,
+}
+
+impl Checkpoint {
+    /// Verify a complete log, returning a checkpoint at its latest entry.
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the log entries are invalid.
+    pub async fn verify(log: &[LogEntry], proofs: Option<&[WitnessEntry]>) -> Result<Self> {
+        Self::verify_with(log, proofs, &SystemClock).await
+    }
+
+    /// Verify a complete log using the provided clock as the current time,
+    /// returning a checkpoint at its latest entry.
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the log entries are invalid.
+    pub async fn verify_with(
+        log: &[LogEntry], proofs: Option<&[WitnessEntry]>, clock: &dyn Clock,
+    ) -> Result<Self> {
+        let Some(first) = log.first() else {
+            bail!("log entries are empty");
+        };
+        let now = clock.now();
+        let mut checkpoint = Self::genesis(&first.parameters.scid);
+        for entry in log {
+            checkpoint.apply(entry, proofs, now).await?;
+        }
+        Ok(checkpoint)
+    }
+
+    /// Verify entries appended to the log since the checkpoint, returning a
+    /// checkpoint at the latest entry.
+    ///
+    /// The entries must overlap the checkpoint: the first entry must be the
+    /// checkpoint's latest verified entry. Entries preceding it are ignored,
+    /// so a complete log can also be provided.
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the entries do not include the checkpoint's latest entry,
+    /// indicating the log has been truncated or rewritten, or if any new
+    /// entry is invalid.
+    pub async fn extend(
+        &self, entries: &[LogEntry], proofs: Option<&[WitnessEntry]>,
+    ) -> Result<Self> {
+        self.extend_with(entries, proofs, &SystemClock).await
+    }
+
+    /// Verify entries appended to the log since the checkpoint using the
+    /// provided clock as the current time, returning a checkpoint at the
+    /// latest entry.
+    ///
+    /// See [`Checkpoint::extend`].
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the entries do not include the checkpoint's latest entry
+    /// or if any new entry is invalid.
+    pub async fn extend_with(
+        &self, entries: &[LogEntry], proofs: Option<&[WitnessEntry]>, clock: &dyn Clock,
+    ) -> Result<Self> {
+        let Some(start) = entries.iter().position(|e| self.is_tip(e)) else {
+            if entries.iter().any(|e| version_number(e).ok() == Some(self.version_number)) {
+                bail!(
+                    "log has been rewritten: version {} replaced since it was verified",
+                    self.version_id
+                );
+            }
+            bail!("log does not contain verified version {}", self.version_id);
+        };
+
+        let now = clock.now();
+        let mut checkpoint = self.clone();
+        for entry in &entries[start + 1..] {
+            checkpoint.apply(entry, proofs, now).await?;
+        }
+        Ok(checkpoint)
+    }
+
+    // The state before the first log entry is applied.
+    fn genesis(scid: &str) -> Self {
+        Self {
+            scid: scid.to_string(),
+            version_number: 0,
+            version_id: scid.to_string(),
+            version_time: DateTime::<Utc>::MIN_UTC,
+            ..Self::default()
+        }
+    }
+
+    fn is_tip(&self, entry: &LogEntry) -> bool {
+        entry.version_id == self.version_id
+    }
+
+    // Verify the next log entry and advance the checkpoint.
+    async fn apply(
+        &mut self, entry: &LogEntry, proofs: Option<&[WitnessEntry]>, now: DateTime<Utc>,
+    ) -> Result<()> {
         // 1. Update current parameters with parameters from the entry being
         // processed.
 
-        // 2. Verify controller proofs.
-        verify_proofs(&log[i]).await?;
+        // 2. Verify controller proofs. The first entry is authorized by its
+        // own update keys. Later entries are authorized by the previous
+        // entry's update keys and threshold unless key pre-rotation is
+        // enabled, in which case the entry's own update keys sign once they
+        // have been matched against the previous next-key hashes (step 7). A
+        // deactivated entry clears its update keys, so is always authorized by
+        // the previous entry's keys.
+        let (update_keys, threshold) = if self.version_number == 0 {
+            (&entry.parameters.update_keys, entry.parameters.update_threshold)
+        } else if self.next_key_hashes.is_some() && !entry.parameters.deactivated {
+            (&entry.parameters.update_keys, self.update_threshold)
+        } else {
+            (&self.update_keys, self.update_threshold)
+        };
+        let signers = verify_signers(entry, update_keys).await?;
+        let threshold = threshold.unwrap_or(1);
+        if signers < threshold {
+            bail!("log entry signed by {signers} update keys but {threshold} are required");
+        }
 
         // 3.1. Verify the version number is incremented by one for each entry.
         // 3.2. Verify the version number and entry hash is separated by `-`.
-        let version_parts = log[i].version_id.split('-').collect::<Vec<&str>>();
-        if version_parts.len() != 2 {
-            bail!("log entry version id has an unexpected format");
-        }
-        let index = version_parts[0].parse::<u64>()?;
-        if index != prev_index + 1 {
+        let index = version_number(entry)?;
+        if index != self.version_number + 1 {
             bail!("log entries are not sequential");
         }
 
         // 3.3. Verify the entry hash.
-        log[i].verify_hash(&prev_version)?;
+        entry.verify_hash(&self.version_id)?;
 
         // 4. The version time must be in the past and monotonically increasing.
-        if log[i].version_time > Utc::now() {
+        if entry.version_time > now {
             bail!("log entry time is in the future");
         }
-        if log[i].version_time <= prev_time {
+        if entry.version_time <= self.version_time {
             bail!(
                 "log entry times are not monotonically increasing: {} -> {}",
-                log[i].version_time,
-                prev_time
+                entry.version_time,
+                self.version_time
             );
         }
 
         // 5. If the entry is the first one, verify the SCID.
-        if i == 0 {
-            let initial_string = serde_json::to_string(&log[i])?;
-            let replaced = initial_string.replace(&log[i].parameters.scid, SCID);
+        if index == 1 {
+            let initial_string = serde_json::to_string(entry)?;
+            let replaced = initial_string.replace(&entry.parameters.scid, SCID);
             let mut initial_log_entry = serde_json::from_str::<LogEntry>(&replaced)?;
             initial_log_entry.version_id = SCID.to_string();
             initial_log_entry.proof = vec![];
             let hash = initial_log_entry.hash()?;
-            if hash != log[i].parameters.scid {
+            if hash != entry.parameters.scid {
                 bail!("first log entry SCID does not match calculated hash");
             }
         }
 
         // 6. Record the state as the document to return (if everything else is
         // successful).
-        doc = log[i].state.clone();
+        let mut doc = entry.state.clone();
 
         // Add method-specific metadata to the document from the log parameters.
         let mut mdb = doc
@@ -146,19 +333,23 @@ pub async fn resolve_log(
             .as_ref()
             .map_or_else(DocumentMetadataBuilder::new, DocumentMetadataBuilder::from);
         mdb = mdb
-            .additional("versionId", log[i].version_id.clone())
-            .additional("versionTime", log[i].version_time.to_rfc3339())
-            .additional("scid", log[i].parameters.scid.clone())
-            .additional("portable", log[i].parameters.portable);
-        if log[i].parameters.witness.is_some() {
-            mdb = mdb.additional("witness", log[i].parameters.witness.clone());
+            .additional("versionId", entry.version_id.clone())
+            .additional("versionTime", entry.version_time.to_rfc3339())
+            .additional("scid", entry.parameters.scid.clone())
+            .additional("portable", entry.parameters.portable)
+            .additional("ttl", entry.parameters.ttl);
+        if entry.parameters.witness.is_some() {
+            mdb = mdb.additional("witness", entry.parameters.witness.clone());
+        }
+        if let Some(threshold) = entry.parameters.update_threshold {
+            mdb = mdb.additional("updateThreshold", threshold);
         }
         doc.did_document_metadata = Some(mdb.build());
 
         // 7. If key pre-rotation is enabled, check the update keys match the
         // previous entry's next-key hashes.
-        if let Some(next_key_hashes) = &prev_next_key_hashes {
-            for key in &log[i].parameters.update_keys {
+        if let Some(next_key_hashes) = &self.next_key_hashes {
+            for key in &entry.parameters.update_keys {
                 let key_digest = sha2::Sha256::digest(key.as_bytes());
                 let key_hash = multibase::encode(Base::Base58Btc, key_digest.as_slice());
                 if !next_key_hashes.contains(&key_hash) {
@@ -167,42 +358,32 @@ pub async fn resolve_log(
             }
         }
 
-        // 8. Increment.
-        prev_index = index;
-        prev_version.clone_from(&log[i].version_id);
-        prev_time.clone_from(&log[i].version_time);
-        prev_next_key_hashes.clone_from(&log[i].parameters.next_key_hashes);
-
-        // 9. Check witness proofs if provided.
-        if proofs.is_some() && log[i].parameters.witness.is_some() {
+        // 8. Check witness proofs if provided.
+        if entry.parameters.witness.is_some() {
             if let Some(witness_entries) = proofs {
-                verify_witness(&log[i], witness_entries).await?;
+                verify_witness(entry, witness_entries).await?;
             }
         }
 
-        // Check for explicit version ID or version time request. (Otherwise
-        // the latest version is returned.)
-        if let Some(params) = parameters {
-            if let Some(version_id) = &params.version_id {
-                if *version_id == log[i].version_id {
-                    break;
-                }
-            }
-            if let Some(version_time) = &params.version_time {
-                let version_time = version_time.parse::<DateTime<Utc>>()?;
-                if version_time >= log[i].version_time {
-                    if i < log.len() - 1 {
-                        if version_time <= log[i + 1].version_time {
-                            break;
-                        }
-                    } else {
-                        break;
-                    }
-                }
-            }
-        }
+        // 9. Increment.
+        self.version_number = index;
+        self.version_id.clone_from(&entry.version_id);
+        self.version_time = entry.version_time;
+        self.next_key_hashes.clone_from(&entry.parameters.next_key_hashes);
+        self.update_keys.clone_from(&entry.parameters.update_keys);
+        self.update_threshold = entry.parameters.update_threshold;
+        self.document = doc;
+
+        Ok(())
     }
-    Ok(doc)
+}
+
+// Parse the version number from a log entry's version ID.
+fn version_number(entry: &LogEntry) -> Result<u64> {
+    let Some((number, _)) = entry.version_id.split_once('-') else {
+        bail!("log entry version id has an unexpected format");
+    };
+    Ok(number.parse::<u64>()?)
 }
 
 #[cfg(test)]
@@ -0,0 +1,93 @@
+//! Key pre-rotation helper for the `did:webvh` method.
+//!
+//! Wraps a `credibil_ecc` keyring entry so the current key can be used as the
+//! DID's update key and the entry's next key committed (by hash) for
+//! subsequent key rotation.
+
+use anyhow::{Result, bail};
+use credibil_ecc::{Entry, Keyring, NextKey, Signer, Vault};
+use credibil_jose::PublicKeyJwk;
+use multibase::Base;
+use sha2::Digest;
+
+/// Key pre-rotation state for a `did:webvh` controller.
+///
+/// Holds a keyring entry along with the multibase-encoded current (update)
+/// key and next key. Use with the `key_rotation` methods on
+/// [`CreateBuilder`](super::CreateBuilder),
+/// [`UpdateBuilder`](super::UpdateBuilder) and
+/// [`DeactivateBuilder`](super::DeactivateBuilder).
+pub struct KeyRotation {
+    entry: Entry,
+    update_key: String,
+    next_key: String,
+}
+
+impl KeyRotation {
+    /// Create a new `KeyRotation` from a keyring entry.
+    ///
+    /// The entry's current key becomes the update key and its next key is the
+    /// key committed to for the next rotation.
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the current or next key cannot be retrieved from the
+    /// entry or cannot be encoded as a multibase string.
+    pub async fn new(entry: Entry) -> Result<Self> {
+        let verifying_key = entry.verifying_key().await?;
+        let update_key = PublicKeyJwk::from_bytes(&verifying_key.to_bytes())?.to_multibase()?;
+        let next_key = entry.next_key().await?;
+        let next_key = PublicKeyJwk::from_bytes(&next_key.to_bytes())?.to_multibase()?;
+
+        Ok(Self {
+            entry,
+            update_key,
+            next_key,
+        })
+    }
+
+    /// Rotate the keyring entry.
+    ///
+    /// The committed next key is promoted to be the update key and a fresh
+    /// next key is generated by the keyring.
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the keyring cannot rotate the entry or if the promoted key
+    /// does not match the previously committed next key.
+    pub async fn rotate(self, vault: &impl Vault) -> Result<Self> {
+        let committed = self.next_key;
+        let entry = Keyring::rotate(vault, self.entry).await?;
+        let rotated = Self::new(entry).await?;
+        if rotated.update_key != committed {
+            bail!("rotated update key does not match the committed next key");
+        }
+        Ok(rotated)
+    }
+
+    /// The multibase-encoded update key authorized to sign log entries.
+    #[must_use]
+    pub fn update_key(&self) -> &str {
+        &self.update_key
+    }
+
+    /// The multibase-encoded key committed to for the next rotation.
+    #[must_use]
+    pub fn next_key(&self) -> &str {
+        &self.next_key
+    }
+
+    /// The hash of the next key as it appears in a log entry's
+    /// `nextKeyHashes` parameter.
+    #[must_use]
+    pub fn next_key_hash(&self) -> String {
+        let digest = sha2::Sha256::digest(self.next_key.as_bytes());
+        multibase::encode(Base::Base58Btc, digest.as_slice())
+    }
+
+    /// The keyring entry used to sign log entries with the update key.
+    #[must_use]
+    pub const fn signer(&self) -> &Entry {
+        &self.entry
+    }
+}
@@ -1,16 +1,17 @@
 //! Update operation for the `did:webvh` method.
 
+use std::sync::Arc;
+
 use anyhow::{Result, bail};
-use chrono::Utc;
-use credibil_ecc::Signer;
+use credibil_ecc::{Entry, Signer};
 use multibase::Base;
 use serde::{Deserialize, Serialize};
 use sha2::Digest;
 
-use super::resolve::resolve_log;
-use super::verify::validate_witness;
-use super::{LogEntry, Witness, WitnessEntry};
-use crate::{Document, DocumentBuilder, FromDocument};
+use super::resolve::resolve_log_with;
+use super::verify::{validate_threshold, validate_watchers, validate_witness};
+use super::{KeyRotation, LogEntry, Prepared, PreparedEntry, Witness, WitnessEntry};
+use crate::{Clock, Document, DocumentBuilder, FromDocument, IdSource, RandomIds, SystemClock};
 
 /// Builder to update a DID document and associated log entry.
 ///
@@ -22,8 +23,12 @@ pub struct UpdateBuilder<D, L, S> {
     portable: Option<bool>,
     witness: Option<Witness>,
     ttl: Option<u64>,
+    watchers: Option<Vec<String>>,
+    update_threshold: Option<u64>,
     update_keys: Option<Vec<String>>,
     next_keys: Option<Vec<String>>,
+    clock: Option<Arc<dyn Clock>>,
+    ids: Option<Arc<dyn IdSource>>,
     signer: S,
 }
 
@@ -48,8 +53,8 @@ pub struct WithLog(Vec<LogEntry>);
 /// Builder does not have a signer (can't build).
 pub struct NoSigner;
 
-/// Builder has a signer (can build).
-pub struct WithSigner<'a, S: Signer>(pub &'a S);
+/// Builder has one or more signers (can build).
+pub struct WithSigner<'a, S: Signer>(pub Vec<&'a S>);
 
 impl UpdateBuilder<NoDocument, NoLog, NoSigner> {
     /// Create a new update builder.
@@ -62,8 +67,12 @@ impl UpdateBuilder<NoDocument, NoLog, NoSigner> {
             portable: None,
             witness: None,
             ttl: None,
+            watchers: None,
+            update_threshold: None,
             update_keys: None,
             next_keys: None,
+            clock: None,
+            ids: None,
             signer: NoSigner,
         }
     }
@@ -87,8 +96,12 @@ impl<L, S> UpdateBuilder<NoDocument, L, S> {
             portable: self.portable,
             witness: self.witness,
             ttl: self.ttl,
+            watchers: self.watchers,
+            update_threshold: self.update_threshold,
             update_keys: self.update_keys,
             next_keys: self.next_keys,
+            clock: self.clock,
+            ids: self.ids,
             signer: self.signer,
         }
     }
@@ -105,8 +118,12 @@ impl<D, S> UpdateBuilder<D, NoLog, S> {
             portable: self.portable,
             witness: self.witness,
             ttl: self.ttl,
+            watchers: self.watchers,
+            update_threshold: self.update_threshold,
             update_keys: self.update_keys,
             next_keys: self.next_keys,
+            clock: self.clock,
+            ids: self.ids,
             signer: self.signer,
         }
     }
@@ -133,9 +150,11 @@ impl<D, L, S> UpdateBuilder<D, L, S> {
     /// `new_next_keys` to an empty list.
     ///
     /// # Note
-    /// The new update keys must not be used to sign the new log entry. Only
-    /// the current update keys should be used to sign the new log entry. This
-    /// will be checked on the build operation.
+    /// Without pre-rotation, the new update keys must not be used to sign the
+    /// new log entry. Only the current update keys (meeting the current
+    /// update threshold) can sign it. With pre-rotation, the new update keys
+    /// committed to in the current log entry sign it. This is checked when
+    /// the log is resolved.
     ///
     /// # Errors
     /// If the hashed new update keys do not match the current next key hashes
@@ -194,6 +213,53 @@ impl<D, L, S> UpdateBuilder<D, L, S> {
         self.ttl = Some(ttl);
         self
     }
+
+    /// Set the URLs of watchers that cache the DID's log and notify resolvers
+    /// of updates. Pass an empty list to remove all watchers.
+    ///
+    /// If this function is not called, the watchers from the last log entry
+    /// will be used.
+    #[must_use]
+    pub fn watchers(mut self, watchers: Vec<String>) -> Self {
+        self.watchers = Some(watchers);
+        self
+    }
+
+    /// Set the number of distinct update keys required to sign log entries.
+    /// Will stay the same as the current log entry if not overridden here.
+    ///
+    /// Note that the threshold in effect on the current log entry must still
+    /// be met when signing this entry.
+    #[must_use]
+    pub const fn update_threshold(mut self, threshold: u64) -> Self {
+        self.update_threshold = Some(threshold);
+        self
+    }
+
+    /// Set the clock used to timestamp the document, log entry and proofs and
+    /// to verify the existing log. Defaults to the system clock if not set
+    /// here.
+    #[must_use]
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
+    /// Set the source of proof identifiers. Defaults to random UUIDs if not
+    /// set here.
+    #[must_use]
+    pub fn id_source(mut self, ids: impl IdSource + 'static) -> Self {
+        self.ids = Some(Arc::new(ids));
+        self
+    }
+
+    // The clock and identifier source, defaulting to the system clock and
+    // random identifiers.
+    fn sources(&self) -> (Arc<dyn Clock>, Arc<dyn IdSource>) {
+        let clock = self.clock.clone().unwrap_or_else(|| Arc::new(SystemClock));
+        let ids = self.ids.clone().unwrap_or_else(|| Arc::new(RandomIds));
+        (clock, ids)
+    }
 }
 
 impl<D, L> UpdateBuilder<D, L, NoSigner> {
@@ -207,30 +273,68 @@ impl<D, L> UpdateBuilder<D, L, NoSigner> {
             portable: self.portable,
             witness: self.witness,
             ttl: self.ttl,
+            watchers: self.watchers,
+            update_threshold: self.update_threshold,
             update_keys: self.update_keys,
             next_keys: self.next_keys,
-            signer: WithSigner(signer),
+            clock: self.clock,
+            ids: self.ids,
+            signer: WithSigner(vec![signer]),
         }
     }
-}
 
-impl<S: Signer> UpdateBuilder<WithDocument, WithLog, WithSigner<'_, S>> {
-    /// Build the new log entry.
-    ///
-    /// Provide a `Provable` `Signer` to construct a data integrity proof. To
-    /// add more proofs, call the `sign` method on the log entry after building.
+    /// Add several signers to the builder.
     ///
-    /// # Errors
+    /// Each signer adds a data integrity proof to the log entry. Use when an
+    /// update threshold requires more than one update key signature.
+    #[must_use]
+    pub fn signers<'a, S: Signer>(
+        self, signers: &[&'a S],
+    ) -> UpdateBuilder<D, L, WithSigner<'a, S>> {
+        UpdateBuilder {
+            document: self.document,
+            log_entries: self.log_entries,
+            witness_entries: self.witness_entries,
+            portable: self.portable,
+            witness: self.witness,
+            ttl: self.ttl,
+            watchers: self.watchers,
+            update_threshold: self.update_threshold,
+            update_keys: self.update_keys,
+            next_keys: self.next_keys,
+            clock: self.clock,
+            ids: self.ids,
+            signer: WithSigner(signers.to_vec()),
+        }
+    }
+
+    /// Use a [`KeyRotation`] to rotate the update keys and sign the log entry.
     ///
-    /// Will fail if secondary algorithms fail such as generating a hash of the
-    /// log entry to calculate the version ID. Will also fail if the provided
-    /// signer fails to sign the log entry.
-    pub async fn build(self) -> Result<UpdateResult> {
-        let document = self.document.0.build()?;
+    /// The rotation should already have been rotated (see
+    /// [`KeyRotation::rotate`]) so its update key is the next key committed to
+    /// in the current log entry. A fresh next key is committed to for the
+    /// subsequent rotation.
+    #[must_use]
+    pub fn key_rotation(
+        self, rotation: &KeyRotation,
+    ) -> UpdateBuilder<D, L, WithSigner<'_, Entry>> {
+        self.rotate_keys(&[rotation.update_key().to_string()], &[rotation.next_key().to_string()])
+            .signer(rotation.signer())
+    }
+}
+
+impl<S> UpdateBuilder<WithDocument, WithLog, S> {
+    // Validate the existing log and build the unsigned log entry.
+    async fn unsigned(self) -> Result<(Vec<LogEntry>, LogEntry, S)> {
+        let (clock, _) = self.sources();
+        let now = clock.now();
+        let document = self.document.0.build(now)?;
         let mut log_entries = self.log_entries.0;
 
         // validate the existing log entries by resolving the DID document
-        let _ = resolve_log(&log_entries, self.witness_entries.as_deref(), None).await?;
+        let _ =
+            resolve_log_with(&log_entries, self.witness_entries.as_deref(), None, clock.as_ref())
+                .await?;
 
         let Some(last_entry) = log_entries.last() else {
             bail!("log must not be empty.");
@@ -279,16 +383,24 @@ impl<S: Signer> UpdateBuilder<WithDocument, WithLog, WithSigner<'_, S>> {
         if let Some(ttl) = self.ttl {
             params.ttl = ttl;
         }
+        if let Some(watchers) = self.watchers {
+            validate_watchers(&watchers)?;
+            params.watchers = Some(watchers).filter(|w| !w.is_empty());
+        }
+        if let Some(threshold) = self.update_threshold {
+            params.set_update_threshold(threshold);
+        }
+        if let Some(threshold) = params.update_threshold {
+            validate_threshold(threshold, &params.update_keys)?;
+        }
 
-        let version_time = document
-            .did_document_metadata
-            .as_ref()
-            .map_or_else(Utc::now, |m| m.updated.unwrap_or_else(Utc::now));
+        let version_time =
+            document.did_document_metadata.as_ref().map_or(now, |m| m.updated.unwrap_or(now));
         let mut entry = LogEntry {
             version_id: last_entry.version_id.clone(),
             version_time,
             parameters: params.clone(),
-            state: document.clone(),
+            state: document,
             proof: vec![],
         };
 
@@ -301,9 +413,67 @@ impl<S: Signer> UpdateBuilder<WithDocument, WithLog, WithSigner<'_, S>> {
         version_number += 1;
         entry.version_id = format!("{version_number}-{entry_hash}");
 
-        // Sign (adds a proof to the log entry).
-        entry.sign(self.signer.0).await?;
+        Ok((log_entries, entry, self.signer))
+    }
+}
+
+impl UpdateBuilder<WithDocument, WithLog, NoSigner> {
+    /// Prepare the new log entry for signing by offline signers.
+    ///
+    /// A signing request is created for each update key authorized to sign
+    /// the new entry. Sign the request payloads and call
+    /// [`Prepared::finalize`] with the detached signatures to complete the log
+    /// entry.
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the existing log entries cannot be verified or secondary
+    /// algorithms fail such as generating a hash of the log entry to calculate
+    /// the version ID.
+    pub async fn prepare(self) -> Result<Prepared> {
+        let (clock, ids) = self.sources();
+        let (log_entries, entry, _) = self.unsigned().await?;
+
+        // without pre-rotation, the current update keys sign the new entry
+        let Some(last_entry) = log_entries.last() else {
+            bail!("log must not be empty.");
+        };
+        let update_keys = if last_entry.parameters.next_key_hashes.is_some() {
+            entry.parameters.update_keys.clone()
+        } else {
+            last_entry.parameters.update_keys.clone()
+        };
+        let prepared = PreparedEntry::new(entry, &update_keys, clock.as_ref(), ids.as_ref())?;
+
+        Ok(Prepared {
+            did: prepared.entry.state.id.clone(),
+            log: log_entries,
+            entries: vec![prepared],
+        })
+    }
+}
+
+impl<S: Signer> UpdateBuilder<WithDocument, WithLog, WithSigner<'_, S>> {
+    /// Build the new log entry.
+    ///
+    /// Each `Signer` provided constructs a data integrity proof. To add more
+    /// proofs, call the `sign` method on the log entry after building.
+    ///
+    /// # Errors
+    ///
+    /// Will fail if secondary algorithms fail such as generating a hash of the
+    /// log entry to calculate the version ID. Will also fail if the provided
+    /// signer fails to sign the log entry.
+    pub async fn build(self) -> Result<UpdateResult> {
+        let (clock, ids) = self.sources();
+        let (mut log_entries, mut entry, signer) = self.unsigned().await?;
+
+        // Sign (adds a proof to the log entry for each signer).
+        for signer in signer.0 {
+            entry.sign_with(signer, clock.as_ref(), ids.as_ref()).await?;
+        }
 
+        let document = entry.state.clone();
         log_entries.push(entry);
 
         Ok(UpdateResult {
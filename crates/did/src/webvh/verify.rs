@@ -1,29 +1,72 @@
 //! Verification and validation functions for `did:webvh` log entries and
 //! information referenced in the log parameters.
 
+use std::collections::HashSet;
+
 use anyhow::bail;
-use credibil_jose::PublicKeyJwk;
-use sha2::Digest;
 
 use super::{LogEntry, Witness, WitnessEntry};
-use crate::proof::Proof;
+use crate::ProofKey;
+use crate::proof::{Proof, order_proofs};
 
 /// Verify the controller's proofs in a log entry.
 ///
 /// Requires a DID resolver to fetch a DID document and find the verification
 /// method referenced in the proof.
 ///
+/// If the entry's parameters specify an `updateThreshold`, the number of
+/// distinct update keys that signed the entry must meet the threshold.
+///
+/// Proofs may form a proof set or a proof chain. Chained proofs are verified
+/// after the proofs they reference.
+///
 /// # Errors
-/// Will return an error if any of the proofs on the log entry are invalid.
+/// Will return an error if any of the proofs on the log entry are invalid, if
+/// a proof chain is broken or cyclic, or if there are fewer distinct signers
+/// than the update threshold.
 pub async fn verify_proofs(log_entry: &LogEntry) -> anyhow::Result<()> {
+    let count = signers(log_entry)?.len() as u64;
+    let threshold = log_entry.parameters.update_threshold.unwrap_or(1);
+    if count < threshold {
+        bail!("log entry signed by {count} update keys but {threshold} are required");
+    }
+    Ok(())
+}
+
+/// Verify the controller's proofs in a log entry against a set of authorized
+/// update keys, returning the number of distinct keys that signed the entry.
+///
+/// Unlike [`verify_proofs`], the update threshold is not enforced: the caller
+/// decides which threshold is in force. A deactivated entry clears its update
+/// keys, so should be verified against the previous entry's keys.
+///
+/// # Errors
+/// Will return an error if any of the proofs on the log entry are invalid, if
+/// a proof chain is broken or cyclic, or if a proof is signed by a key that is
+/// not in `update_keys`.
+pub async fn verify_signers(log_entry: &LogEntry, update_keys: &[String]) -> anyhow::Result<u64> {
+    let signers = signers(log_entry)?;
+    if let Some(key) = signers.iter().find(|key| !update_keys.contains(key)) {
+        bail!("update key {key} is not authorized to sign the log entry");
+    }
+    Ok(signers.len() as u64)
+}
+
+// Verify the controller's proofs, returning the distinct signing keys.
+fn signers(log_entry: &LogEntry) -> anyhow::Result<HashSet<String>> {
     if log_entry.proof.is_empty() {
         bail!("log entry has no proof");
     }
 
-    for proof in &log_entry.proof {
+    let mut signers = HashSet::new();
+    for proof in order_proofs(&log_entry.proof)? {
         verify_proof(log_entry, proof, &ProofSigner::Controller)?;
+        let Some((_, key)) = proof.verification_method.split_once('#') else {
+            bail!("verification method id has an unexpected format");
+        };
+        signers.insert(key.to_string());
     }
-    Ok(())
+    Ok(signers)
 }
 
 /// Type of signer for a proof.
@@ -47,12 +90,12 @@ pub enum ProofSigner {
 pub fn verify_proof(
     log_entry: &LogEntry, proof: &Proof, signer: &ProofSigner,
 ) -> anyhow::Result<()> {
+    // a controller's proof covers the log entry without proofs unless it is
+    // chained to earlier proofs
     let mut unsigned_entry = log_entry.clone();
-    if matches!(signer, ProofSigner::Controller) {
+    if matches!(signer, ProofSigner::Controller) && proof.previous_proof.is_none() {
         unsigned_entry.proof = Vec::new();
     }
-    let unsigned_data = serde_json_canonicalizer::to_string(&unsigned_entry)?;
-    let unsigned_hash = sha2::Sha256::digest(unsigned_data.as_bytes());
 
     let Some(proof_value) = &proof.proof_value else {
         bail!("proof value is missing");
@@ -66,22 +109,25 @@ pub fn verify_proof(
             proof.proof_purpose
         );
     }
-    if proof.cryptosuite != Some("eddsa-jcs-2022".to_string()) {
-        bail!(
-            "unsupported cryptosuite {} - must be 'eddsa-jcs-2022'",
-            proof.cryptosuite.as_deref().unwrap_or("")
-        );
-    }
 
     let parts = proof.verification_method.split('#').collect::<Vec<&str>>();
     if parts.len() != 2 {
         bail!("verification method id has an unexpected format");
     }
     let verification_key = parts[1].to_string();
+    let proof_key = ProofKey::from_multikey(&verification_key)?;
+    if proof.cryptosuite.as_deref() != Some(proof_key.jcs_cryptosuite()) {
+        bail!(
+            "unsupported cryptosuite {} - must be '{}' for the verification key",
+            proof.cryptosuite.as_deref().unwrap_or(""),
+            proof_key.jcs_cryptosuite()
+        );
+    }
 
     // If we are verifying a controller's proof, the verification method public
     // key must be authorized to update log entries unless the proof is for a
-    // deactivated log entry.
+    // deactivated log entry. A deactivated entry has no update keys of its
+    // own: see `verify_signers` to check it against the previous entry's.
     if !log_entry.parameters.deactivated {
         match signer {
             ProofSigner::Controller => {
@@ -94,17 +140,27 @@ pub fn verify_proof(
     }
 
     // Verify the signature.
-    let mut config = proof.clone();
-    config.proof_value = None;
-    let config_data = serde_json_canonicalizer::to_string(&config)?;
-    let config_hash = sha2::Sha256::digest(config_data.as_bytes());
-    let payload = [config_hash.as_slice(), unsigned_hash.as_slice()].concat();
+    let payload = unsigned_entry.signing_payload(proof)?;
     let (base, signature) = multibase::decode(proof_value)?;
     if base != multibase::Base::Base58Btc {
         bail!("unsupported multibase encoding");
     }
-    let key = PublicKeyJwk::from_multibase(&verification_key)?;
-    key.verify_bytes(&payload, &signature)?;
+    proof_key.verify(&verification_key, &payload, &signature)
+}
+
+/// Validate an update key threshold against the update keys it applies to.
+///
+/// # Errors
+///
+/// Will fail if the threshold is zero or greater than the number of update
+/// keys.
+pub fn validate_threshold(threshold: u64, update_keys: &[String]) -> anyhow::Result<()> {
+    if threshold == 0 {
+        bail!("update threshold must be greater than zero.");
+    }
+    if threshold > update_keys.len() as u64 {
+        bail!("update threshold must not exceed the number of update keys.");
+    }
     Ok(())
 }
 
@@ -142,6 +198,24 @@ pub fn validate_witness(witness: &Witness) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Validate the watcher URLs in the log parameters.
+///
+/// # Errors
+///
+/// Will fail if a watcher URL does not use `https` or is repeated.
+pub fn validate_watchers(watchers: &[String]) -> anyhow::Result<()> {
+    let mut seen = HashSet::new();
+    for watcher in watchers {
+        if !watcher.starts_with("https://") {
+            bail!("watcher URL must use 'https://'.");
+        }
+        if !seen.insert(watcher) {
+            bail!("watcher URL {watcher} is repeated.");
+        }
+    }
+    Ok(())
+}
+
 /// Verify a set of witness entries.
 ///
 /// Requires a resolver for the witness proof signatures.
@@ -150,6 +224,13 @@ pub fn validate_witness(witness: &Witness) -> anyhow::Result<()> {
 /// provided for witness that does not exist in the entry's list of witnesses.
 /// Instead it will omit that witness from the total weight calculation.
 ///
+/// A witness proof may be chained to the controller's proofs or to the
+/// witness's earlier proofs using `previousProof`. A chained proof only counts
+/// when the proofs it references have been verified. Each witness's proofs are
+/// ordered separately, so a witness with a broken or cyclic chain is omitted
+/// without affecting other witnesses. A witness's weight is counted once,
+/// however many of its proofs verify.
+///
 /// # Errors
 ///
 /// Will fail if the total weight of witness proofs does not meet the threshold.
@@ -161,18 +242,16 @@ pub async fn verify_witness(
         bail!("log entry has no witness parameters");
     };
     let mut total_weight = 0;
-    for witness in witnesses {
-        if witness.version_id != log_entry.version_id {
-            continue;
-        }
-        for proof in &witness.proof {
-            if matches!(verify_proof(log_entry, proof, &ProofSigner::Witness), Ok(())) {
-                if let Some(witness_weight) =
-                    witness_weights.witnesses.iter().find(|w| w.id == proof.verification_method)
-                {
-                    total_weight += witness_weight.weight;
-                }
-            }
+    for witness_weight in &witness_weights.witnesses {
+        let proofs = witnesses
+            .iter()
+            .filter(|witness| witness.version_id == log_entry.version_id)
+            .flat_map(|witness| &witness.proof)
+            .filter(|proof| proof.verification_method == witness_weight.id)
+            .cloned()
+            .collect::<Vec<_>>();
+        if witnessed(log_entry, proofs) {
+            total_weight += witness_weight.weight;
         }
     }
     if total_weight < witness_weights.threshold {
@@ -180,3 +259,34 @@ pub async fn verify_witness(
     }
     Ok(total_weight)
 }
+
+// Whether any of a witness's proofs verifies. Proofs are verified in chain
+// order, and a proof only verifies once the proofs it references have.
+fn witnessed(log_entry: &LogEntry, proofs: Vec<Proof>) -> bool {
+    // the controller's proofs have been verified with the log entry
+    let mut chained = log_entry.clone();
+    chained.proof.extend(proofs);
+    let Ok(ordered) = order_proofs(&chained.proof) else {
+        return false;
+    };
+    let mut verified =
+        log_entry.proof.iter().filter_map(|proof| proof.id.clone()).collect::<HashSet<_>>();
+
+    let mut witnessed = false;
+    for proof in ordered {
+        if log_entry.proof.contains(proof) {
+            continue;
+        }
+        if !proof.previous_proofs().iter().all(|id| verified.contains(id)) {
+            continue;
+        }
+        let entry = if proof.previous_proof.is_some() { &chained } else { log_entry };
+        if matches!(verify_proof(entry, proof, &ProofSigner::Witness), Ok(())) {
+            if let Some(id) = &proof.id {
+                verified.insert(id.clone());
+            }
+            witnessed = true;
+        }
+    }
+    witnessed
+}
@@ -0,0 +1,180 @@
+//! Watcher support for the `did:webvh` method.
+//!
+//! Watchers are services named by the DID controller (in the `watchers` log
+//! parameter) that cache the DID's log and notify resolvers of updates.
+//! Resolving with watchers fetches the latest log held by each watcher and
+//! cross-checks it against the log published by the origin.
+//!
+//! See: <https://identity.foundation/didwebvh/v1.0/>
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use super::equivocation::{SourcedLog, detect_equivocation};
+use super::resolve::resolve_log;
+use super::verify::verify_witness;
+use super::{LogEntry, WitnessEntry};
+use crate::{Document, QueryParams};
+
+/// A client for `did:webvh` watchers.
+///
+/// Implementers fetch the log held by a watcher, typically by making an HTTP
+/// request to the watcher URL. A local stand-in (for example, a cache shared
+/// by several resolvers) can also implement this trait.
+pub trait Watcher: Send + Sync {
+    /// Fetch the latest log the watcher at `watcher` (a URL from the
+    /// `watchers` parameter) holds for the DID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the watcher cannot be reached or does not hold a
+    /// log for the DID.
+    fn log(&self, watcher: &str, did: &str) -> impl Future<Output = Result<Vec<LogEntry>>> + Send;
+
+    /// Fetch the witness proofs (`did-witness.json`) the watcher holds for the
+    /// DID.
+    ///
+    /// The default implementation returns no proofs, for watchers that do not
+    /// cache witness proofs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the watcher cannot be reached.
+    fn witness(
+        &self, watcher: &str, did: &str,
+    ) -> impl Future<Output = Result<Vec<WitnessEntry>>> + Send {
+        let _ = (watcher, did);
+        async { Ok(vec![]) }
+    }
+}
+
+/// Output of resolving a DID log cross-checked with its watchers.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchedResult {
+    /// The resolved DID document.
+    pub document: Document,
+
+    /// The most up-to-date log consistent with the origin's log.
+    pub log: Vec<LogEntry>,
+
+    /// Watchers holding entries not yet published by the origin.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub ahead: Vec<String>,
+
+    /// Watchers that could not be reached or did not hold a log.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub unavailable: Vec<String>,
+
+    /// Watchers serving a log that fails verification.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub faulty: Vec<String>,
+
+    /// Watchers serving a log that conflicts with the origin's log. Use
+    /// [`detect_equivocation`] with the conflicting logs to obtain
+    /// publishable evidence.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub conflicting: Vec<String>,
+
+    /// Version IDs of entries held by a watcher but not published by the
+    /// origin that lack sufficient witness proofs. These entries (and any
+    /// that follow them) are excluded from `log`.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub unwitnessed: Vec<String>,
+}
+
+/// Resolve a DID log, cross-checking it with the watchers named in its
+/// parameters.
+///
+/// The origin log is verified and the `watchers` parameter of its latest
+/// entry is used to find watchers. Each watcher's log is compared with the
+/// origin's. A watcher log that extends the origin's (the origin is lagging)
+/// is used in place of the origin log.
+///
+/// When witness proofs are provided, entries not published by the origin are
+/// verified using the proofs together with the witness proofs held by the
+/// watcher. Entries without sufficient witness proofs are reported in
+/// [`WatchedResult::unwitnessed`] and excluded from the resolved log.
+///
+/// Unreachable, faulty and conflicting watchers are reported but do not cause
+/// resolution to fail: the caller decides whether to trust the result.
+///
+/// # Errors
+///
+/// Will fail if the origin log is invalid.
+pub async fn resolve_log_watched(
+    origin: &[LogEntry], watcher: &impl Watcher, proofs: Option<&[WitnessEntry]>,
+    parameters: Option<&QueryParams>,
+) -> Result<WatchedResult> {
+    resolve_log(origin, proofs, None).await?;
+    let Some(last_entry) = origin.last() else {
+        bail!("log entries are empty");
+    };
+    let did = last_entry.state.id.clone();
+
+    let mut result = WatchedResult {
+        log: origin.to_vec(),
+        ..WatchedResult::default()
+    };
+    let mut source = None;
+
+    for url in last_entry.parameters.watchers.iter().flatten() {
+        let Ok(watched) = watcher.log(url, &did).await else {
+            result.unavailable.push(url.clone());
+            continue;
+        };
+        if watched.is_empty() {
+            result.unavailable.push(url.clone());
+            continue;
+        }
+
+        let Ok(report) = detect_equivocation(&[
+            SourcedLog {
+                source: "origin".to_string(),
+                log: result.log.clone(),
+            },
+            SourcedLog {
+                source: url.clone(),
+                log: watched.clone(),
+            },
+        ])
+        .await
+        else {
+            result.faulty.push(url.clone());
+            continue;
+        };
+        if report.evidence.is_some() {
+            result.conflicting.push(url.clone());
+            continue;
+        }
+        if watched.len() > result.log.len() {
+            result.ahead.push(url.clone());
+            result.log = watched;
+            source = Some(url);
+        }
+    }
+
+    // the origin's witness proofs won't cover newer entries, so add the
+    // witness proofs held by the watcher serving them
+    if let (Some(proofs), Some(url)) = (proofs, source) {
+        let mut witness = proofs.to_vec();
+        witness.extend(watcher.witness(url, &did).await.unwrap_or_default());
+
+        let mut first_unwitnessed = None;
+        for (index, entry) in result.log.iter().enumerate().skip(origin.len()) {
+            if entry.parameters.witness.is_some() && verify_witness(entry, &witness).await.is_err()
+            {
+                first_unwitnessed = Some(index);
+                break;
+            }
+        }
+        if let Some(index) = first_unwitnessed {
+            result.unwitnessed =
+                result.log.split_off(index).into_iter().map(|e| e.version_id).collect();
+        }
+    }
+
+    // the origin's entries were verified with the witness proofs above
+    result.document = resolve_log(&result.log, None, parameters).await?;
+    Ok(result)
+}
@@ -0,0 +1,169 @@
+//! Tests for BBS signatures and the `bbs-2023` cryptosuite.
+
+mod common;
+
+use std::fmt::Write;
+
+use credibil_did::bbs::{self, BaseOptions, PublicKey, SecretKey};
+use credibil_did::{KeyFormat, Proof};
+use serde_json::json;
+
+use self::common::{credential, split};
+
+// Key generation should match the BBS `KeyGen` test vector.
+#[test]
+fn key_gen() {
+    let key_material = b"this-IS-just-an-Test-IKM-to-generate-$e(r@t#-key";
+    let key_info = b"this-IS-some-key-metadata-to-be-used-in-test-key-gen";
+    let secret_key = SecretKey::generate(key_material, key_info).expect("should generate");
+
+    assert_eq!(
+        hex(&secret_key.to_bytes()),
+        "60e55110f76883a13d030b2f6bd11883422d5abde717569fc0731f51237169fc"
+    );
+    assert_eq!(
+        hex(&secret_key.public_key().to_bytes()),
+        "a820f230f6ae38503b86c70dc50b61c58a77e45c39ab25c0652bbaa8fa136f2851bd4781c9dcde39fc9d1d52c9e60268061e7d7632171d91aa8d460acee0e96f1e7c4cfb12d3ff9ab5d5dc91c277db75c845d649ef3c4f63aebc364cd55ded0c"
+    );
+}
+
+// A single-message signature should match the BBS test vector and verify.
+#[test]
+fn signature() {
+    let secret_key = SecretKey::from_bytes(&unhex(
+        "60e55110f76883a13d030b2f6bd11883422d5abde717569fc0731f51237169fc",
+    ))
+    .expect("should decode");
+    let public_key = secret_key.public_key();
+    let header = unhex("11223344556677889900aabbccddeeff");
+    let messages = [unhex("9872ad089e452c7b6e283dfac2a80d58e8d0ff71cc4d5e310a1debdda4a45f02")];
+
+    let signature = bbs::sign(&secret_key, &public_key, &header, &messages).expect("should sign");
+    assert_eq!(
+        hex(&signature),
+        "88c0eb3bc1d97610c3a66d8a3a73f260f95a3028bccf7fff7d9851e2acd9f3f32fdf58a5b34d12df8177adf37aa318a20f72be7d37a8e8d8441d1bc0bc75543c681bf061ce7e7f6091fe78c1cb8af103"
+    );
+
+    bbs::verify(&public_key, &signature, &header, &messages).expect("should verify");
+    bbs::verify(&public_key, &signature, b"other header", &messages)
+        .expect_err("should not verify");
+}
+
+// A proof should verify for the disclosed messages only.
+#[test]
+fn proof() {
+    let secret_key = SecretKey::random().expect("should generate");
+    let public_key = secret_key.public_key();
+    let messages = [b"first".as_slice(), b"second", b"third"];
+    let signature = bbs::sign(&secret_key, &public_key, b"header", &messages).expect("should sign");
+
+    let proof = bbs::proof_gen(&public_key, &signature, b"header", b"nonce", &messages, &[0, 2])
+        .expect("should generate proof");
+    bbs::proof_verify(&public_key, &proof, b"header", b"nonce", &[b"first", b"third"], &[0, 2])
+        .expect("should verify");
+    bbs::proof_verify(&public_key, &proof, b"header", b"nonce", &[b"first", b"other"], &[0, 2])
+        .expect_err("should reject a different message");
+    bbs::proof_verify(&public_key, &proof, b"header", b"replay", &[b"first", b"third"], &[0, 2])
+        .expect_err("should reject a different presentation header");
+}
+
+// The `bbs-2023` test vector key pair should encode to its multikey.
+#[test]
+fn multikey() {
+    let secret_key = SecretKey::from_bytes(&unhex(
+        "66d36e118832af4c5e28b2dfe1b9577857e57b042a33e06bdea37b811ed09ee0",
+    ))
+    .expect("should decode");
+    let multikey = "zUC7DerdEmfZ8f4pFajXgGwJoMkV1ofMTmEG5UoNvnWiPiLuGKNeqgRpLH2TV4Xe5mJ2cXV76gRN7LFQwapF1VFu6x2yrr5ci1mXqC1WNUrnHnLgvfZfMH7h6xP6qsf9EKRQrPQ";
+
+    let public_key = secret_key.public_key();
+    assert_eq!(public_key.to_multikey(), multikey);
+    assert_eq!(PublicKey::from_multikey(multikey).expect("should decode"), public_key);
+    assert_eq!(
+        KeyFormat::from(public_key),
+        KeyFormat::Multikey {
+            public_key_multibase: multikey.to_string()
+        }
+    );
+}
+
+// The holder should be able to disclose the mandatory statements and a
+// selection of the others, and the verifier to check the derived proof.
+#[test]
+fn selective_disclosure() {
+    let secret_key = SecretKey::from_bytes(&unhex(
+        "66d36e118832af4c5e28b2dfe1b9577857e57b042a33e06bdea37b811ed09ee0",
+    ))
+    .expect("should decode");
+    let public_key = secret_key.public_key();
+
+    let mandatory_pointers =
+        ["/issuer", "/credentialSubject/sailNumber", "/credentialSubject/sails/1"]
+            .map(String::from)
+            .to_vec();
+    let options = BaseOptions {
+        mandatory_pointers,
+        hmac_key: Some([0x11; 32]),
+    };
+    let secured = bbs::add_base_proof(&credential(), &config(&public_key), &secret_key, &options)
+        .expect("should add base proof");
+
+    // the holder discloses the first board
+    let selective_pointers = ["/credentialSubject/boards/0".to_string()];
+    let derived = bbs::derive_proof(&secured, &selective_pointers, b"presentation")
+        .expect("should derive proof");
+
+    let subject = &derived["credentialSubject"];
+    assert_eq!(subject["sailNumber"], "Earth101");
+    assert_eq!(subject["sails"].as_array().map(Vec::len), Some(1));
+    assert_eq!(subject["boards"][0]["boardName"], "CompFoil170");
+    assert_eq!(subject["boards"].as_array().map(Vec::len), Some(1));
+
+    let (unsecured, proof) = split(&derived);
+    bbs::verify_derived_proof(&unsecured, &proof, &public_key).expect("should verify");
+
+    // a modified disclosure should not verify
+    let mut tampered = unsecured;
+    tampered["credentialSubject"]["boards"][0]["year"] = json!(2024);
+    bbs::verify_derived_proof(&tampered, &proof, &public_key).expect_err("should not verify");
+
+    // derived proofs are unlinkable
+    let other = bbs::derive_proof(&secured, &selective_pointers, b"presentation")
+        .expect("should derive proof");
+    assert_ne!(other["proof"]["proofValue"], derived["proof"]["proofValue"]);
+
+    // only derived proofs can be verified
+    let (unsecured, proof) = split(&secured);
+    bbs::verify_derived_proof(&unsecured, &proof, &public_key)
+        .expect_err("should not verify a base proof");
+}
+
+// A pointer that does not match the document should be rejected.
+#[test]
+fn invalid_pointer() {
+    let secret_key = SecretKey::random().expect("should generate");
+    let options = BaseOptions {
+        mandatory_pointers: vec!["/credentialSubject/wings".to_string()],
+        ..BaseOptions::default()
+    };
+    bbs::add_base_proof(&credential(), &config(&secret_key.public_key()), &secret_key, &options)
+        .expect_err("should reject pointer");
+}
+
+fn config(public_key: &PublicKey) -> Proof {
+    common::config(&public_key.to_multikey(), bbs::CRYPTOSUITE)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+    })
+}
+
+fn unhex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("hex"))
+        .collect()
+}
@@ -0,0 +1,47 @@
+//! Fixtures shared by the selective disclosure cryptosuite tests.
+
+use credibil_did::Proof;
+use serde_json::{Value, json};
+
+/// The `vc-di-bbs` and `vc-di-ecdsa` example windsurfing credential.
+pub fn credential() -> Value {
+    json!({
+        "@context": [
+            "https://www.w3.org/ns/credentials/v2",
+            {"@vocab": "https://windsurf.grotto-networking.com/selective#"}
+        ],
+        "type": ["VerifiableCredential"],
+        "issuer": "https://vc.example/windsurf/racecommittee",
+        "credentialSubject": {
+            "sailNumber": "Earth101",
+            "sails": [
+                {"size": 5.5, "sailName": "Kihei", "year": 2023},
+                {"size": 6.1, "sailName": "Lahaina", "year": 2023}
+            ],
+            "boards": [
+                {"boardName": "CompFoil170", "brand": "Wailea", "year": 2022},
+                {"boardName": "Kanaha Custom", "brand": "Wailea", "year": 2019}
+            ]
+        }
+    })
+}
+
+/// A proof configuration for the cryptosuite, verified by the `did:key`
+/// for the multikey.
+pub fn config(multikey: &str, cryptosuite: &str) -> Proof {
+    Proof {
+        type_: "DataIntegrityProof".to_string(),
+        cryptosuite: Some(cryptosuite.to_string()),
+        proof_purpose: "assertionMethod".to_string(),
+        verification_method: format!("did:key:{multikey}#{multikey}"),
+        ..Proof::default()
+    }
+}
+
+/// Split a secured document into the unsecured document and its proof.
+pub fn split(secured: &Value) -> (Value, Proof) {
+    let mut unsecured = secured.clone();
+    let proof =
+        unsecured.as_object_mut().and_then(|map| map.remove("proof")).expect("should have proof");
+    (unsecured, serde_json::from_value(proof).expect("should deserialize"))
+}
@@ -0,0 +1,162 @@
+//! Tests for the JCS cryptosuite key operations.
+
+use credibil_did::ProofKey;
+use credibil_jose::PublicKeyJwk;
+use multibase::Base;
+use p256::ecdsa::signature::Signer;
+use serde_json::json;
+
+// Multikeys should be identified by their multicodec prefix.
+#[test]
+fn multikey_type() {
+    let p256 = ProofKey::from_multikey("zDnaepBuvsQ8cpsWrVKw8fbpGpvPeNSjVPTWoq6cRqaYzBKVP")
+        .expect("should decode");
+    assert_eq!(p256, ProofKey::P256);
+    assert_eq!(p256.jcs_cryptosuite(), "ecdsa-jcs-2019");
+
+    let ed25519 = ProofKey::from_multikey("z6MkmM42vxfqZQsv4ehtTjFFxQ4sQKS2w6WR7emozFAn5cxu")
+        .expect("should decode");
+    assert_eq!(ed25519, ProofKey::Ed25519);
+    assert_eq!(ed25519.jcs_cryptosuite(), "eddsa-jcs-2022");
+}
+
+// A P-256 signature over the signing payload should verify with the
+// signer's multikey and fail for any other payload.
+#[test]
+fn p256_verify() {
+    let signing_key = p256::ecdsa::SigningKey::from_slice(&[7; 32]).expect("should create key");
+    let public_key = signing_key.verifying_key().to_encoded_point(false);
+    let multikey = ProofKey::P256.multikey(public_key.as_bytes()).expect("should encode");
+    assert!(multikey.starts_with("zDn"));
+
+    let payload = ProofKey::P256
+        .jcs_payload(&json!({"type": "DataIntegrityProof"}), &json!({"id": "urn:example"}))
+        .expect("should create payload");
+    assert_eq!(payload.len(), 64);
+
+    let signature: p256::ecdsa::Signature = signing_key.sign(&payload);
+    ProofKey::P256.verify(&multikey, &payload, &signature.to_bytes()).expect("should verify");
+    ProofKey::P256
+        .verify(&multikey, b"tampered", &signature.to_bytes())
+        .expect_err("should not verify");
+    ProofKey::P384
+        .verify(&multikey, &payload, &signature.to_bytes())
+        .expect_err("should reject key type mismatch");
+}
+
+// P-384 proofs should use SHA-384 and verify with the signer's multikey.
+#[test]
+fn p384_verify() {
+    let signing_key = p384::ecdsa::SigningKey::from_slice(&[9; 48]).expect("should create key");
+    let public_key = signing_key.verifying_key().to_encoded_point(true);
+    let multikey = ProofKey::P384.multikey(public_key.as_bytes()).expect("should encode");
+    assert!(multikey.starts_with("z82"));
+
+    let payload = ProofKey::P384
+        .jcs_payload(&json!({"type": "DataIntegrityProof"}), &json!({"id": "urn:example"}))
+        .expect("should create payload");
+    assert_eq!(payload.len(), 96);
+
+    let signature: p384::ecdsa::Signature = signing_key.sign(&payload);
+    ProofKey::P384.verify(&multikey, &payload, &signature.to_bytes()).expect("should verify");
+}
+
+// An EC JWK should encode to the same multikey as its SEC1 point.
+#[test]
+fn jwk_multikey() {
+    let signing_key = p256::ecdsa::SigningKey::from_slice(&[7; 32]).expect("should create key");
+    let point = signing_key.verifying_key().to_encoded_point(false);
+    let (Some(x), Some(y)) = (point.x(), point.y()) else {
+        panic!("point should have coordinates");
+    };
+
+    let jwk: PublicKeyJwk = serde_json::from_value(json!({
+        "kty": "EC",
+        "crv": "P-256",
+        "x": Base::Base64Url.encode(x),
+        "y": Base::Base64Url.encode(y)
+    }))
+    .expect("should deserialize");
+
+    let multikey = ProofKey::jwk_multikey(&jwk).expect("should encode");
+    assert_eq!(multikey, ProofKey::P256.multikey(point.as_bytes()).expect("should encode"));
+    assert_eq!(ProofKey::from_multikey(&multikey).expect("should decode").jws_algorithm(), "ES256");
+}
+
+// Published vc-di-ecdsa key pairs: https://www.w3.org/TR/vc-di-ecdsa/#test-vectors
+const W3C_P256_SECRET: &str = "z42tqZ5smVag3DtDhjY9YfVwTMyVHW6SCHJi2ZMrD23DGYS3";
+const W3C_P256_PUBLIC: &str = "zDnaekGZTbQBerwcehBSXLqAg6s55hVEBms1zFy89VHXtJSa9";
+const W3C_P384_SECRET: &str =
+    "z2fanyY7zgwNpZGxX5fXXibvScNaUWNprHU9dKx7qpVj7mws9J8LLt4mDB5TyH2GLHWkUc";
+const W3C_P384_PUBLIC: &str =
+    "z82LkuBieyGShVBhvtE2zoiD6Kma4tJGFtkAhxR5pfkp5QPw4LutoYWhvQCnGjdVn14kujQ";
+
+// Decode a multikey secret key, checking its multicodec prefix.
+fn secret_key(multikey: &str, prefix: [u8; 2]) -> Vec<u8> {
+    let (base, bytes) = multibase::decode(multikey).expect("should decode");
+    assert_eq!(base, Base::Base58Btc);
+    assert_eq!(bytes[..2], prefix);
+    bytes[2..].to_vec()
+}
+
+// The test vectors' unsecured credential and `ecdsa-jcs-2019` proof options.
+fn w3c_payload(proof_key: ProofKey, public: &str) -> Vec<u8> {
+    let context = json!([
+        "https://www.w3.org/ns/credentials/v2",
+        "https://www.w3.org/ns/credentials/examples/v2"
+    ]);
+    let document = json!({
+        "@context": context,
+        "id": "urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33",
+        "type": ["VerifiableCredential", "AlumniCredential"],
+        "name": "Alumni Credential",
+        "description": "A minimum viable example of an Alumni Credential.",
+        "issuer": "https://vc.example/issuers/5678",
+        "validFrom": "2023-01-01T00:00:00Z",
+        "credentialSubject": {
+            "id": "did:example:abcdefgh",
+            "alumniOf": "The School of Examples"
+        }
+    });
+    let options = json!({
+        "@context": context,
+        "type": "DataIntegrityProof",
+        "cryptosuite": "ecdsa-jcs-2019",
+        "created": "2023-02-24T23:36:38Z",
+        "verificationMethod": format!("did:key:{public}#{public}"),
+        "proofPurpose": "assertionMethod"
+    });
+    proof_key.jcs_payload(&options, &document).expect("should create payload")
+}
+
+// The published P-256 key pair should encode and decode as multikeys, and
+// proofs signed with the secret key verify with the public key.
+#[test]
+fn w3c_p256() {
+    let secret = secret_key(W3C_P256_SECRET, [0x86, 0x26]);
+    let signing_key = p256::ecdsa::SigningKey::from_slice(&secret).expect("should create key");
+    let public_key = signing_key.verifying_key().to_encoded_point(true);
+    let multikey = ProofKey::P256.multikey(public_key.as_bytes()).expect("should encode");
+    assert_eq!(multikey, W3C_P256_PUBLIC);
+    assert_eq!(ProofKey::from_multikey(W3C_P256_PUBLIC).expect("should decode"), ProofKey::P256);
+
+    let payload = w3c_payload(ProofKey::P256, W3C_P256_PUBLIC);
+    let signature: p256::ecdsa::Signature = signing_key.sign(&payload);
+    ProofKey::P256.verify(W3C_P256_PUBLIC, &payload, &signature.to_bytes()).expect("should verify");
+}
+
+// The published P-384 key pair should encode and decode as multikeys, and
+// proofs signed with the secret key verify with the public key.
+#[test]
+fn w3c_p384() {
+    let secret = secret_key(W3C_P384_SECRET, [0x87, 0x26]);
+    let signing_key = p384::ecdsa::SigningKey::from_slice(&secret).expect("should create key");
+    let public_key = signing_key.verifying_key().to_encoded_point(true);
+    let multikey = ProofKey::P384.multikey(public_key.as_bytes()).expect("should encode");
+    assert_eq!(multikey, W3C_P384_PUBLIC);
+    assert_eq!(ProofKey::from_multikey(W3C_P384_PUBLIC).expect("should decode"), ProofKey::P384);
+
+    let payload = w3c_payload(ProofKey::P384, W3C_P384_PUBLIC);
+    let signature: p384::ecdsa::Signature = signing_key.sign(&payload);
+    ProofKey::P384.verify(W3C_P384_PUBLIC, &payload, &signature.to_bytes()).expect("should verify");
+}
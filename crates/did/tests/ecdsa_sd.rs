@@ -0,0 +1,152 @@
+//! Tests for the `ecdsa-sd-2023` cryptosuite.
+
+mod common;
+
+use credibil_did::ecdsa_sd::{self, BaseOptions};
+use credibil_did::{Proof, ProofKey};
+use credibil_ecc::{Algorithm, Curve, Keyring, PublicKey, Signer};
+use p256::ecdsa::signature::Signer as _;
+use p256::ecdsa::{Signature, SigningKey};
+use serde_json::json;
+use test_utils::Vault;
+
+use self::common::{credential, split};
+
+// An issuer's P-256 key, held outside the keyring (as in an HSM).
+struct Issuer(SigningKey);
+
+impl Issuer {
+    fn new(secret: u8) -> Self {
+        Self(SigningKey::from_slice(&[secret; 32]).expect("should create key"))
+    }
+
+    fn multikey(&self) -> String {
+        let public_key = self.0.verifying_key().to_encoded_point(true);
+        ProofKey::P256.multikey(public_key.as_bytes()).expect("should encode")
+    }
+}
+
+impl Signer for Issuer {
+    async fn try_sign(&self, msg: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let signature: Signature = self.0.sign(msg);
+        Ok(signature.to_vec())
+    }
+
+    async fn verifying_key(&self) -> anyhow::Result<PublicKey> {
+        let public_key = self.0.verifying_key().to_encoded_point(true);
+        let public_key = PublicKey::from_slice(public_key.as_bytes())?;
+        Ok(public_key)
+    }
+
+    async fn algorithm(&self) -> anyhow::Result<Algorithm> {
+        Ok(Algorithm::ES256)
+    }
+}
+
+fn config(multikey: &str) -> Proof {
+    common::config(multikey, ecdsa_sd::CRYPTOSUITE)
+}
+
+// The issuer's signer should sign the base proof, and the derived proof
+// should only verify using the issuer's key.
+#[tokio::test]
+async fn issuer_signer() {
+    let issuer = Issuer::new(7);
+    let options = BaseOptions {
+        mandatory_pointers: vec!["/issuer".to_string()],
+        hmac_key: Some([0x11; 32]),
+    };
+    let secured =
+        ecdsa_sd::add_base_proof(&credential(), &config(&issuer.multikey()), &issuer, &options)
+            .await
+            .expect("should add base proof");
+
+    let selective_pointers = ["/credentialSubject/sails/0".to_string()];
+    let derived =
+        ecdsa_sd::derive_proof(&secured, &selective_pointers).expect("should derive proof");
+    assert_eq!(derived["credentialSubject"]["sails"][0]["sailName"], "Kihei");
+
+    let (unsecured, proof) = split(&derived);
+    ecdsa_sd::verify_derived_proof(&unsecured, &proof, &issuer.multikey()).expect("should verify");
+    ecdsa_sd::verify_derived_proof(&unsecured, &proof, &Issuer::new(9).multikey())
+        .expect_err("should not verify with another key");
+
+    // each disclosed statement is signed separately
+    let mut tampered = unsecured;
+    tampered["credentialSubject"]["sails"][0]["size"] = json!(7.0);
+    ecdsa_sd::verify_derived_proof(&tampered, &proof, &issuer.multikey())
+        .expect_err("should not verify a changed statement");
+
+    // only derived proofs can be verified
+    let (unsecured, proof) = split(&secured);
+    ecdsa_sd::verify_derived_proof(&unsecured, &proof, &issuer.multikey())
+        .expect_err("should not verify a base proof");
+}
+
+// Unlike `bbs-2023`, derived proofs reuse the issuer's signatures, so the
+// same disclosure produces the same proof.
+#[tokio::test]
+async fn linkable() {
+    let issuer = Issuer::new(7);
+    let options = BaseOptions {
+        mandatory_pointers: vec!["/issuer".to_string()],
+        ..BaseOptions::default()
+    };
+    let secured =
+        ecdsa_sd::add_base_proof(&credential(), &config(&issuer.multikey()), &issuer, &options)
+            .await
+            .expect("should add base proof");
+
+    let selective_pointers = ["/credentialSubject/boards/1".to_string()];
+    let first = ecdsa_sd::derive_proof(&secured, &selective_pointers).expect("should derive proof");
+    let second =
+        ecdsa_sd::derive_proof(&secured, &selective_pointers).expect("should derive proof");
+    assert_eq!(first["proof"]["proofValue"], second["proof"]["proofValue"]);
+}
+
+// Base proofs should only be signed using a P-256 key.
+#[tokio::test]
+async fn p256_only() {
+    let signer =
+        Keyring::generate(&Vault, "esd", "signing", Curve::Ed25519).await.expect("should generate");
+    let multikey = Issuer::new(7).multikey();
+    ecdsa_sd::add_base_proof(&credential(), &config(&multikey), &signer, &BaseOptions::default())
+        .await
+        .expect_err("should reject an Ed25519 signer");
+}
+
+// Disclosing only the mandatory statements should verify.
+#[tokio::test]
+async fn mandatory_only() {
+    let issuer = Issuer::new(7);
+    let options = BaseOptions {
+        mandatory_pointers: vec!["/issuer".to_string()],
+        ..BaseOptions::default()
+    };
+    let secured =
+        ecdsa_sd::add_base_proof(&credential(), &config(&issuer.multikey()), &issuer, &options)
+            .await
+            .expect("should add base proof");
+    let derived = ecdsa_sd::derive_proof(&secured, &[]).expect("should derive proof");
+    assert!(derived.get("credentialSubject").is_none());
+
+    let (unsecured, proof) = split(&derived);
+    ecdsa_sd::verify_derived_proof(&unsecured, &proof, &issuer.multikey()).expect("should verify");
+}
+
+// A pointer that does not match the document should be rejected.
+#[tokio::test]
+async fn invalid_pointer() {
+    let issuer = Issuer::new(7);
+    let secured = ecdsa_sd::add_base_proof(
+        &credential(),
+        &config(&issuer.multikey()),
+        &issuer,
+        &BaseOptions::default(),
+    )
+    .await
+    .expect("should add base proof");
+
+    ecdsa_sd::derive_proof(&secured, &["/credentialSubject/wings".to_string()])
+        .expect_err("should reject pointer");
+}
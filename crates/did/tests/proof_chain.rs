@@ -0,0 +1,65 @@
+//! Tests for ordering proof sets and proof chains.
+
+use credibil_did::{OneOrMany, Proof, order_proofs};
+
+fn proof(id: &str, previous: &[&str]) -> Proof {
+    Proof {
+        id: Some(id.to_string()),
+        type_: "DataIntegrityProof".to_string(),
+        previous_proof: match previous {
+            [] => None,
+            [one] => Some(OneOrMany::One((*one).to_string())),
+            many => Some(OneOrMany::Many(many.iter().map(ToString::to_string).collect())),
+        },
+        ..Proof::default()
+    }
+}
+
+fn ids(proofs: &[&Proof]) -> Vec<String> {
+    proofs.iter().filter_map(|proof| proof.id.clone()).collect()
+}
+
+// Proofs in a set should keep their order.
+#[test]
+fn proof_set() {
+    let proofs = [proof("urn:a", &[]), proof("urn:b", &[]), proof("urn:c", &[])];
+    let ordered = order_proofs(&proofs).expect("should order");
+    assert_eq!(ids(&ordered), ["urn:a", "urn:b", "urn:c"]);
+}
+
+// Proofs in a chain should follow the proofs they reference.
+#[test]
+fn proof_chain() {
+    let proofs =
+        [proof("urn:c", &["urn:a", "urn:b"]), proof("urn:b", &["urn:a"]), proof("urn:a", &[])];
+    let ordered = order_proofs(&proofs).expect("should order");
+    assert_eq!(ids(&ordered), ["urn:a", "urn:b", "urn:c"]);
+}
+
+// A reference to a proof that is not in the set should be rejected.
+#[test]
+fn missing_link() {
+    let proofs = [proof("urn:a", &[]), proof("urn:b", &["urn:x"])];
+    let err = order_proofs(&proofs).expect_err("should fail");
+    assert!(err.to_string().contains("urn:x"));
+}
+
+// Cyclic references, including a proof referencing itself, should be
+// rejected.
+#[test]
+fn cycle() {
+    let proofs =
+        [proof("urn:a", &["urn:c"]), proof("urn:b", &["urn:a"]), proof("urn:c", &["urn:b"])];
+    let err = order_proofs(&proofs).expect_err("should fail");
+    assert!(err.to_string().contains("cycle"));
+
+    let proofs = [proof("urn:a", &["urn:a"])];
+    order_proofs(&proofs).expect_err("should fail");
+}
+
+// Proof identifiers must be unique.
+#[test]
+fn repeated_id() {
+    let proofs = [proof("urn:a", &[]), proof("urn:a", &[])];
+    order_proofs(&proofs).expect_err("should fail");
+}
@@ -0,0 +1,181 @@
+//! Tests for JSON-LD to RDF conversion and RDF Dataset Canonicalization.
+
+use credibil_did::rdf;
+use serde_json::json;
+
+// A credential should canonicalize to the N-Quads in the `eddsa-rdfc-2022`
+// test vectors.
+#[test]
+fn credential() {
+    let credential = json!({
+        "@context": [
+            "https://www.w3.org/ns/credentials/v2",
+            "https://www.w3.org/ns/credentials/examples/v2"
+        ],
+        "id": "urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33",
+        "type": ["VerifiableCredential", "AlumniCredential"],
+        "name": "Alumni Credential",
+        "description": "A minimum viable example of an Alumni Credential.",
+        "issuer": "https://vc.example/issuers/5678",
+        "validFrom": "2023-01-01T00:00:00Z",
+        "credentialSubject": {
+            "id": "did:example:abcdefgh",
+            "alumniOf": "The School of Examples"
+        }
+    });
+
+    let canonical = rdf::canonicalize(&credential).expect("should canonicalize");
+    let expected = concat!(
+        "<did:example:abcdefgh> <https://www.w3.org/ns/credentials/examples#alumniOf> \"The School of Examples\" .\n",
+        "<urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://www.w3.org/2018/credentials#VerifiableCredential> .\n",
+        "<urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://www.w3.org/ns/credentials/examples#AlumniCredential> .\n",
+        "<urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33> <https://schema.org/description> \"A minimum viable example of an Alumni Credential.\" .\n",
+        "<urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33> <https://schema.org/name> \"Alumni Credential\" .\n",
+        "<urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33> <https://www.w3.org/2018/credentials#credentialSubject> <did:example:abcdefgh> .\n",
+        "<urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33> <https://www.w3.org/2018/credentials#issuer> <https://vc.example/issuers/5678> .\n",
+        "<urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33> <https://www.w3.org/2018/credentials#validFrom> \"2023-01-01T00:00:00Z\"^^<http://www.w3.org/2001/XMLSchema#dateTime> .\n",
+    );
+    assert_eq!(canonical, expected);
+}
+
+// A proof configuration should canonicalize to the N-Quads in the
+// `eddsa-rdfc-2022` test vectors, with its blank node labelled `c14n0`.
+#[test]
+fn proof_config() {
+    let config = json!({
+        "@context": [
+            "https://www.w3.org/ns/credentials/v2",
+            "https://www.w3.org/ns/credentials/examples/v2"
+        ],
+        "type": "DataIntegrityProof",
+        "cryptosuite": "eddsa-rdfc-2022",
+        "created": "2023-02-24T23:36:38Z",
+        "verificationMethod": "did:key:z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2#z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2",
+        "proofPurpose": "assertionMethod"
+    });
+
+    let canonical = rdf::canonicalize(&config).expect("should canonicalize");
+    let expected = concat!(
+        "_:c14n0 <http://purl.org/dc/terms/created> \"2023-02-24T23:36:38Z\"^^<http://www.w3.org/2001/XMLSchema#dateTime> .\n",
+        "_:c14n0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://w3id.org/security#DataIntegrityProof> .\n",
+        "_:c14n0 <https://w3id.org/security#cryptosuite> \"eddsa-rdfc-2022\"^^<https://w3id.org/security#cryptosuiteString> .\n",
+        "_:c14n0 <https://w3id.org/security#proofPurpose> <https://w3id.org/security#assertionMethod> .\n",
+        "_:c14n0 <https://w3id.org/security#verificationMethod> <did:key:z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2#z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2> .\n",
+    );
+    assert_eq!(canonical, expected);
+}
+
+// Datasets differing only in their blank node labels should canonicalize
+// identically, including when the blank nodes are indistinguishable by their
+// first-degree hashes.
+#[test]
+fn blank_nodes() {
+    let ring = |labels: [&str; 4]| {
+        json!({
+            "@context": {"@vocab": "https://example.com/"},
+            "@graph": [
+                {"@id": format!("_:{}", labels[0]), "next": {"@id": format!("_:{}", labels[1])}},
+                {"@id": format!("_:{}", labels[1]), "next": {"@id": format!("_:{}", labels[2])}},
+                {"@id": format!("_:{}", labels[2]), "next": {"@id": format!("_:{}", labels[3])}},
+                {"@id": format!("_:{}", labels[3]), "next": {"@id": format!("_:{}", labels[0])}}
+            ]
+        })
+    };
+
+    let first = rdf::canonicalize(&ring(["a", "b", "c", "d"])).expect("should canonicalize");
+    let second = rdf::canonicalize(&ring(["q", "d", "z", "a"])).expect("should canonicalize");
+    assert_eq!(first, second);
+    assert_eq!(first.lines().count(), 4);
+}
+
+// Contexts must be bundled and protected terms cannot be redefined.
+#[test]
+fn invalid_context() {
+    let remote = json!({"@context": "https://example.com/context", "name": "Alice"});
+    assert!(rdf::canonicalize(&remote).is_err());
+
+    let redefined = json!({
+        "@context": ["https://www.w3.org/ns/credentials/v2", {"name": "https://example.com/name"}],
+        "name": "Alice"
+    });
+    assert!(rdf::canonicalize(&redefined).is_err());
+}
+
+// Every bundled context should load and be usable for expansion.
+#[test]
+fn bundled_contexts() {
+    for (url, _) in rdf::CONTEXTS {
+        rdf::expand(&json!({"@context": url, "@id": "urn:example"}))
+            .unwrap_or_else(|e| panic!("{url} should expand: {e}"));
+    }
+}
+
+// Legacy proof options should expand using the suite's type-scoped context.
+#[test]
+fn legacy_proof_options() {
+    let options = json!({
+        "@context": [
+            "https://www.w3.org/2018/credentials/v1",
+            "https://w3id.org/security/suites/ed25519-2020/v1"
+        ],
+        "type": "Ed25519Signature2020",
+        "created": "2021-11-13T18:19:39Z",
+        "verificationMethod": "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK#z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK",
+        "proofPurpose": "assertionMethod"
+    });
+
+    let canonical = rdf::canonicalize(&options).expect("should canonicalize");
+    let expected = concat!(
+        "_:c14n0 <http://purl.org/dc/terms/created> \"2021-11-13T18:19:39Z\"^^<http://www.w3.org/2001/XMLSchema#dateTime> .\n",
+        "_:c14n0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://w3id.org/security#Ed25519Signature2020> .\n",
+        "_:c14n0 <https://w3id.org/security#proofPurpose> <https://w3id.org/security#assertionMethod> .\n",
+        "_:c14n0 <https://w3id.org/security#verificationMethod> <did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK#z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK> .\n",
+    );
+    assert_eq!(canonical, expected);
+}
+
+// A dataset is a set of quads, so duplicate quads should be canonicalized
+// once.
+#[test]
+fn duplicate_quads() {
+    let document = json!({
+        "@context": {"@vocab": "https://example.com/"},
+        "name": "Alice",
+        "knows": {"name": "Bob"}
+    });
+    let quads =
+        rdf::to_rdf(&rdf::expand(&document).expect("should expand")).expect("should convert");
+    let canonical = rdf::canonicalize_quads(&quads).expect("should canonicalize");
+
+    let duplicated = [quads.clone(), quads].concat();
+    assert_eq!(rdf::canonicalize_quads(&duplicated).expect("should canonicalize"), canonical);
+    assert_eq!(canonical.lines().count(), 3);
+}
+
+// Contexts imported by a bundled context should also be bundled.
+#[test]
+fn bundled_imports() {
+    fn imports(value: &serde_json::Value, found: &mut Vec<String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                if let Some(serde_json::Value::String(url)) = map.get("@import") {
+                    found.push(url.clone());
+                }
+                map.values().for_each(|v| imports(v, found));
+            }
+            serde_json::Value::Array(values) => values.iter().for_each(|v| imports(v, found)),
+            _ => {}
+        }
+    }
+
+    for (url, context) in rdf::CONTEXTS {
+        let mut found = vec![];
+        imports(&serde_json::from_str(context).expect("should parse"), &mut found);
+        for import in found {
+            assert!(
+                rdf::CONTEXTS.iter().any(|(bundled, _)| *bundled == import),
+                "{url} imports {import}, which is not bundled"
+            );
+        }
+    }
+}
@@ -0,0 +1,46 @@
+//! Tests for updating an existing `did:web` document.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use credibil_did::web::{CreateBuilder, UpdateBuilder};
+use credibil_did::{DocumentBuilder, FixedClock, KeyId, Service, VerificationMethod};
+use credibil_ecc::{Curve, Keyring, Signer};
+use credibil_jose::PublicKeyJwk;
+use test_utils::Vault;
+
+// Updating a document should keep existing content and metadata while
+// applying the changes.
+#[tokio::test]
+async fn update_ok() {
+    let signer =
+        Keyring::generate(&Vault, "wu", "signing", Curve::Ed25519).await.expect("should generate");
+    let verifying_key = signer.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+
+    let start: DateTime<Utc> = "2025-01-01T00:00:00Z".parse().expect("should parse");
+    let vm = VerificationMethod::build().key(jwk).key_id(KeyId::Index("key-0".to_string()));
+    let builder = DocumentBuilder::new().verification_method(vm);
+    let document = CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(builder)
+        .clock(FixedClock(start))
+        .build()
+        .expect("should build document");
+    let created = document.did_document_metadata.clone().expect("should have metadata");
+
+    let svc = Service::build()
+        .id("whois")
+        .service_type("LinkedVerifiablePresentation")
+        .endpoint("https://example.com/.well-known/whois");
+    let builder = DocumentBuilder::from(document.clone()).service(svc);
+    let updated = UpdateBuilder::new(builder)
+        .clock(FixedClock(start + TimeDelta::days(1)))
+        .build()
+        .expect("should update document");
+
+    assert_eq!(updated.id, document.id);
+    assert_eq!(updated.verification_method, document.verification_method);
+    assert_eq!(updated.service.as_ref().map(Vec::len), Some(1));
+
+    let metadata = updated.did_document_metadata.expect("should have metadata");
+    assert_eq!(metadata.created, created.created);
+    assert_eq!(metadata.updated, Some(start + TimeDelta::days(1)));
+}
@@ -0,0 +1,146 @@
+//! Tests for incremental verification of a `did:webvh` log using a checkpoint.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use credibil_did::webvh::{Checkpoint, CreateBuilder, CreateResult, LogEntry, UpdateBuilder};
+use credibil_did::{
+    DocumentBuilder, FixedClock, KeyId, Service, SteppingClock, VerificationMethod,
+};
+use credibil_ecc::{Curve, Entry, Keyring, Signer};
+use credibil_jose::PublicKeyJwk;
+use test_utils::Vault;
+
+async fn create(signer: &Entry) -> CreateResult {
+    let verifying_key = signer.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let update_multi = jwk.to_multibase().expect("should get multibase");
+
+    let vm = VerificationMethod::build()
+        .key(update_multi.clone())
+        .key_id(KeyId::Authorization(update_multi.clone()));
+    let builder = DocumentBuilder::new().verification_method(vm);
+
+    CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(builder)
+        .update_keys(vec![update_multi])
+        .signer(signer)
+        .build()
+        .await
+        .expect("should build document")
+}
+
+async fn update(signer: &Entry, log: &[LogEntry], endpoint: &str) -> Vec<LogEntry> {
+    let Some(last) = log.last() else {
+        panic!("log should not be empty");
+    };
+    let service = Service::build()
+        .id(format!("svc-{}", log.len()))
+        .service_type("LinkedVerifiablePresentation")
+        .endpoint(endpoint);
+    let builder = DocumentBuilder::from(last.state.clone()).service(service);
+
+    UpdateBuilder::new()
+        .document(builder)
+        .log_entries(log.to_vec())
+        .signer(signer)
+        .build()
+        .await
+        .expect("should update document")
+        .log_entries
+}
+
+// New entries should be verified against the checkpoint, with or without the
+// entries the checkpoint already covers.
+#[tokio::test]
+async fn extend_ok() {
+    let signer =
+        Keyring::generate(&Vault, "wcp", "signing", Curve::Ed25519).await.expect("should generate");
+    let create_result = create(&signer).await;
+    let checkpoint = Checkpoint::verify(&create_result.log, None).await.expect("should verify");
+    assert_eq!(checkpoint.version_number, 1);
+
+    let log = update(&signer, &create_result.log, "https://example.com/one").await;
+    let log = update(&signer, &log, "https://example.com/two").await;
+
+    // entries from the checkpoint's version onward
+    let extended = checkpoint.extend(&log[0..], None).await.expect("should extend");
+    assert_eq!(extended.version_number, 3);
+    assert_eq!(extended.version_id, log[2].version_id);
+
+    // entries after the checkpoint only (no overlap)
+    checkpoint.extend(&log[1..], None).await.expect_err("should require overlap");
+
+    // checkpoint at the tip with no new entries
+    let unchanged = extended.extend(&log[2..], None).await.expect("should extend");
+    assert_eq!(unchanged.version_id, extended.version_id);
+}
+
+// A log rewritten after the checkpoint was taken should fail to extend.
+#[tokio::test]
+async fn rewritten_log() {
+    let signer =
+        Keyring::generate(&Vault, "wcr", "signing", Curve::Ed25519).await.expect("should generate");
+    let create_result = create(&signer).await;
+
+    let original = update(&signer, &create_result.log, "https://example.com/original").await;
+    let checkpoint = Checkpoint::verify(&original, None).await.expect("should verify");
+
+    let rewritten = update(&signer, &create_result.log, "https://example.com/rewritten").await;
+    let err = checkpoint.extend(&rewritten, None).await.expect_err("should detect rewrite");
+    assert!(err.to_string().contains("rewritten"));
+}
+
+// Entries should be checked against the provided clock: an entry created
+// after the clock's current time is rejected.
+#[tokio::test]
+async fn verify_with_clock() {
+    let signer =
+        Keyring::generate(&Vault, "wcc", "signing", Curve::Ed25519).await.expect("should generate");
+    let verifying_key = signer.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let update_multi = jwk.to_multibase().expect("should get multibase");
+
+    let start: DateTime<Utc> = "2025-01-01T00:00:00Z".parse().expect("should parse");
+    let vm = VerificationMethod::build()
+        .key(update_multi.clone())
+        .key_id(KeyId::Authorization(update_multi.clone()));
+    let create_result = CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(DocumentBuilder::new().verification_method(vm))
+        .update_keys(vec![update_multi])
+        .clock(SteppingClock::new(start))
+        .signer(&signer)
+        .build()
+        .await
+        .expect("should build document");
+
+    let before = FixedClock(start - TimeDelta::days(1));
+    Checkpoint::verify_with(&create_result.log, None, &before)
+        .await
+        .expect_err("should reject an entry from the future");
+    let checkpoint = Checkpoint::verify_with(&create_result.log, None, &FixedClock(start))
+        .await
+        .expect("should verify");
+
+    let update_time = start + TimeDelta::days(1);
+    let service = Service::build()
+        .id("svc-1")
+        .service_type("LinkedVerifiablePresentation")
+        .endpoint("https://example.com/one");
+    let builder = DocumentBuilder::from(create_result.document.clone()).service(service);
+    let log = UpdateBuilder::new()
+        .document(builder)
+        .log_entries(create_result.log)
+        .clock(SteppingClock::new(update_time))
+        .signer(&signer)
+        .build()
+        .await
+        .expect("should update document")
+        .log_entries;
+
+    checkpoint
+        .extend_with(&log, None, &FixedClock(start))
+        .await
+        .expect_err("should reject an entry from the future");
+    let extended =
+        checkpoint.extend_with(&log, None, &FixedClock(update_time)).await.expect("should extend");
+    assert_eq!(extended.version_number, 2);
+}
@@ -0,0 +1,120 @@
+//! Tests for building and resolving `did:webvh` logs with an injected clock
+//! and identifier source.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use credibil_did::webvh::{self, CreateBuilder, CreateResult, UpdateBuilder};
+use credibil_did::{
+    DocumentBuilder, FixedClock, KeyId, SequentialIds, Service, SteppingClock, VerificationMethod,
+};
+use credibil_ecc::{Curve, Entry, Keyring, Signer};
+use credibil_jose::PublicKeyJwk;
+use test_utils::Vault;
+
+fn start() -> DateTime<Utc> {
+    "2025-01-01T00:00:00Z".parse().expect("should parse")
+}
+
+async fn create(signer: &Entry, update_multi: &str) -> CreateResult {
+    let vm = VerificationMethod::build()
+        .key(update_multi.to_string())
+        .key_id(KeyId::Authorization(update_multi.to_string()));
+    let builder = DocumentBuilder::new().verification_method(vm);
+
+    CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(builder)
+        .update_keys(vec![update_multi.to_string()])
+        .clock(SteppingClock::new(start()))
+        .id_source(SequentialIds::new())
+        .signer(signer)
+        .build()
+        .await
+        .expect("should build document")
+}
+
+// Building the same log twice with a stepping clock and sequential identifiers
+// should produce byte-for-byte identical output.
+#[tokio::test]
+async fn reproducible_log() {
+    let signer =
+        Keyring::generate(&Vault, "wck", "signing", Curve::Ed25519).await.expect("should generate");
+    let verifying_key = signer.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let update_multi = jwk.to_multibase().expect("should get multibase");
+
+    let first = create(&signer, &update_multi).await;
+    let second = create(&signer, &update_multi).await;
+
+    let first_json = serde_json::to_string(&first.log).expect("should serialize");
+    let second_json = serde_json::to_string(&second.log).expect("should serialize");
+    assert_eq!(first_json, second_json);
+
+    let proof = &first.log[0].proof[0];
+    assert_eq!(proof.id.as_deref(), Some("urn:uuid:00000000-0000-0000-0000-000000000001"));
+}
+
+// A log should only resolve once the clock has reached the time of the last
+// entry.
+#[tokio::test]
+async fn resolve_at_time() {
+    let signer =
+        Keyring::generate(&Vault, "wcr", "signing", Curve::Ed25519).await.expect("should generate");
+    let verifying_key = signer.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let update_multi = jwk.to_multibase().expect("should get multibase");
+
+    let create_result = create(&signer, &update_multi).await;
+
+    let service = Service::build()
+        .id("whois")
+        .service_type("LinkedVerifiablePresentation")
+        .endpoint("https://credibil.io/.well-known/whois");
+    let builder = DocumentBuilder::from(create_result.document).service(service);
+    let update_time = start() + TimeDelta::days(1);
+
+    let update_result = UpdateBuilder::new()
+        .document(builder)
+        .log_entries(create_result.log)
+        .clock(SteppingClock::new(update_time))
+        .signer(&signer)
+        .build()
+        .await
+        .expect("should update document");
+    assert_eq!(update_result.log_entries[1].version_time, update_time);
+
+    let before = FixedClock(start());
+    webvh::resolve_log_with(&update_result.log_entries, None, None, &before)
+        .await
+        .expect_err("should not resolve before the update");
+
+    let after = FixedClock(update_time);
+    webvh::resolve_log_with(&update_result.log_entries, None, None, &after)
+        .await
+        .expect("should resolve at the time of the update");
+}
+
+// A new document should be created at the injected clock's time, which is
+// also the create entry's version time.
+#[tokio::test]
+async fn create_time() {
+    let signer =
+        Keyring::generate(&Vault, "wct", "signing", Curve::Ed25519).await.expect("should generate");
+    let verifying_key = signer.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let update_multi = jwk.to_multibase().expect("should get multibase");
+
+    let vm = VerificationMethod::build()
+        .key(update_multi.clone())
+        .key_id(KeyId::Authorization(update_multi.clone()));
+    let result = CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(DocumentBuilder::new().verification_method(vm))
+        .update_keys(vec![update_multi])
+        .clock(FixedClock(start()))
+        .signer(&signer)
+        .build()
+        .await
+        .expect("should build document");
+
+    assert_eq!(result.log[0].version_time, start());
+    let metadata = result.document.did_document_metadata.expect("should have metadata");
+    assert_eq!(metadata.created, start());
+}
@@ -0,0 +1,98 @@
+//! Tests for detecting forks (equivocation) between `did:webvh` logs.
+
+use credibil_did::webvh::{
+    self, CreateBuilder, CreateResult, EquivocationEvidence, SourcedLog, UpdateBuilder,
+};
+use credibil_did::{DocumentBuilder, KeyId, Service, VerificationMethod};
+use credibil_ecc::{Curve, Entry, Keyring, Signer};
+use credibil_jose::PublicKeyJwk;
+use test_utils::Vault;
+
+async fn create(signer: &Entry) -> CreateResult {
+    let verifying_key = signer.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let update_multi = jwk.to_multibase().expect("should get multibase");
+
+    let vm = VerificationMethod::build()
+        .key(update_multi.clone())
+        .key_id(KeyId::Authorization(update_multi.clone()));
+    let builder = DocumentBuilder::new().verification_method(vm);
+
+    CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(builder)
+        .update_keys(vec![update_multi])
+        .signer(signer)
+        .build()
+        .await
+        .expect("should build document")
+}
+
+async fn update(signer: &Entry, create_result: &CreateResult, endpoint: &str) -> SourcedLog {
+    let service = Service::build()
+        .id("whois")
+        .service_type("LinkedVerifiablePresentation")
+        .endpoint(endpoint);
+    let builder = DocumentBuilder::from(create_result.document.clone()).service(service);
+
+    let result = UpdateBuilder::new()
+        .document(builder)
+        .log_entries(create_result.log.clone())
+        .signer(signer)
+        .build()
+        .await
+        .expect("should update document");
+
+    SourcedLog {
+        source: endpoint.to_string(),
+        log: result.log_entries,
+    }
+}
+
+// Two logs with conflicting updates to the same version should produce
+// verifiable evidence of equivocation.
+#[tokio::test]
+async fn fork_detected() {
+    let signer =
+        Keyring::generate(&Vault, "weq", "signing", Curve::Ed25519).await.expect("should generate");
+    let create_result = create(&signer).await;
+
+    let mirror_1 = update(&signer, &create_result, "https://mirror-1.example.com").await;
+    let mirror_2 = update(&signer, &create_result, "https://mirror-2.example.com").await;
+
+    let report =
+        webvh::detect_equivocation(&[mirror_1, mirror_2]).await.expect("should compare logs");
+    assert_eq!(report.last_common_version, Some(create_result.log[0].version_id.clone()));
+
+    let evidence = report.evidence.expect("should have evidence");
+    assert_eq!(evidence.shared.len(), 1);
+    assert_eq!(evidence.branches.len(), 2);
+
+    // evidence should verify after publishing
+    let json = serde_json::to_string(&evidence).expect("should serialize");
+    let evidence: EquivocationEvidence = serde_json::from_str(&json).expect("should deserialize");
+    evidence.verify().await.expect("should verify evidence");
+
+    // a single branch is not evidence of equivocation
+    let mut partial = evidence;
+    partial.branches.truncate(1);
+    partial.verify().await.expect_err("should not verify");
+}
+
+// A log that is a prefix of another is lagging, not forked.
+#[tokio::test]
+async fn lagging_log() {
+    let signer =
+        Keyring::generate(&Vault, "wlg", "signing", Curve::Ed25519).await.expect("should generate");
+    let create_result = create(&signer).await;
+    let updated = update(&signer, &create_result, "https://origin.example.com").await;
+
+    let stale = SourcedLog {
+        source: "https://stale.example.com".to_string(),
+        log: create_result.log.clone(),
+    };
+
+    let report = webvh::detect_equivocation(&[updated, stale]).await.expect("should compare logs");
+    assert!(report.evidence.is_none());
+    assert_eq!(report.lagging, vec!["https://stale.example.com".to_string()]);
+    assert_eq!(report.last_common_version, Some(create_result.log[0].version_id.clone()));
+}
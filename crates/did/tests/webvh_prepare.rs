@@ -0,0 +1,100 @@
+//! Tests for two-phase (prepare/finalize) construction of `did:webvh` log
+//! entries using detached signatures.
+
+use credibil_did::webvh::{self, CreateBuilder, DeactivateBuilder, DetachedSignature, Prepared};
+use credibil_did::{DocumentBuilder, KeyId, VerificationMethod};
+use credibil_ecc::{Curve, Entry, Keyring, Signer};
+use credibil_jose::PublicKeyJwk;
+use multibase::Base;
+use test_utils::Vault;
+
+// Sign each signing request as an offline signer would.
+async fn sign_requests(prepared: &Prepared, signer: &Entry) -> Vec<DetachedSignature> {
+    let mut signatures = vec![];
+    for request in prepared.requests() {
+        let (_, payload) = multibase::decode(&request.payload).expect("should decode payload");
+        let signature = signer.sign(&payload).await;
+        signatures.push(DetachedSignature {
+            version_id: request.version_id.clone(),
+            verification_key: request.verification_key.clone(),
+            signature: multibase::encode(Base::Base58Btc, signature),
+        });
+    }
+    signatures
+}
+
+// Prepare a create and a deactivate operation, sign the payloads offline and
+// finalize. The resulting log should resolve without errors.
+#[tokio::test]
+async fn prepare_finalize() {
+    let signer =
+        Keyring::generate(&Vault, "wpf", "signing", Curve::Ed25519).await.expect("should generate");
+    let verifying_key = signer.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let update_multi = jwk.to_multibase().expect("should get multibase");
+
+    let id_entry =
+        Keyring::generate(&Vault, "wpf", "id", Curve::Ed25519).await.expect("should generate");
+    let verifying_key = id_entry.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let id_multi = jwk.to_multibase().expect("should get key");
+
+    let vm = VerificationMethod::build()
+        .key(update_multi.clone())
+        .key_id(KeyId::Authorization(id_multi));
+    let builder = DocumentBuilder::new().verification_method(vm);
+
+    let prepared = CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(builder)
+        .update_keys(vec![update_multi.clone()])
+        .prepare()
+        .expect("should prepare");
+    assert_eq!(prepared.requests().count(), 1);
+
+    // prepared output should survive a round trip to and from the offline signer
+    let json = serde_json::to_string(&prepared).expect("should serialize");
+    let prepared: Prepared = serde_json::from_str(&json).expect("should deserialize");
+
+    let signatures = sign_requests(&prepared, &signer).await;
+    let create_result = prepared.finalize(&signatures).await.expect("should finalize");
+    assert_eq!(create_result.log.len(), 1);
+    assert_eq!(create_result.did, create_result.document.id);
+
+    webvh::resolve_log(&create_result.log, None, None).await.expect("should resolve log");
+
+    // --- Deactivate ----------------------------------------------------------
+
+    let prepared = DeactivateBuilder::from(&create_result.log)
+        .expect("should create builder")
+        .prepare()
+        .expect("should prepare");
+
+    let signatures = sign_requests(&prepared, &signer).await;
+    let deactivate_result = prepared.finalize(&signatures).await.expect("should finalize");
+    assert_eq!(deactivate_result.log.len(), 2);
+
+    webvh::resolve_log(&deactivate_result.log, None, None).await.expect("should resolve log");
+}
+
+// A log entry without signatures should not finalize.
+#[tokio::test]
+async fn finalize_unsigned() {
+    let signer =
+        Keyring::generate(&Vault, "wpu", "signing", Curve::Ed25519).await.expect("should generate");
+    let verifying_key = signer.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let update_multi = jwk.to_multibase().expect("should get multibase");
+
+    let vm = VerificationMethod::build()
+        .key(update_multi.clone())
+        .key_id(KeyId::Authorization(update_multi.clone()));
+    let builder = DocumentBuilder::new().verification_method(vm);
+
+    let prepared = CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(builder)
+        .update_keys(vec![update_multi])
+        .prepare()
+        .expect("should prepare");
+
+    prepared.finalize(&[]).await.expect_err("should fail without signatures");
+}
@@ -0,0 +1,75 @@
+//! Tests for key pre-rotation of a `did:webvh` document using a keyring entry.
+
+use credibil_did::webvh::{self, CreateBuilder, DeactivateBuilder, KeyRotation, UpdateBuilder};
+use credibil_did::{DocumentBuilder, KeyId, VerificationMethod};
+use credibil_ecc::{Curve, Keyring, Signer};
+use credibil_jose::PublicKeyJwk;
+use test_utils::Vault;
+
+// Create, update and deactivate a `did:webvh` document, rotating the update key
+// on each operation. The resulting log should resolve without errors.
+#[tokio::test]
+async fn rotate_ok() {
+    let entry =
+        Keyring::generate(&Vault, "wkr", "signing", Curve::Ed25519).await.expect("should generate");
+    let rotation = KeyRotation::new(entry).await.expect("should create rotation");
+
+    let id_entry =
+        Keyring::generate(&Vault, "wkr", "id", Curve::Ed25519).await.expect("should generate");
+    let verifying_key = id_entry.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let id_multi = jwk.to_multibase().expect("should get key");
+
+    let vm = VerificationMethod::build()
+        .key(rotation.update_key().to_string())
+        .key_id(KeyId::Authorization(id_multi));
+    let builder = DocumentBuilder::new().verification_method(vm);
+
+    let create_result = CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(builder)
+        .key_rotation(&rotation)
+        .build()
+        .await
+        .expect("should build document");
+
+    let params = &create_result.log[0].parameters;
+    assert_eq!(params.update_keys, vec![rotation.update_key().to_string()]);
+    assert_eq!(params.next_key_hashes, Some(vec![rotation.next_key_hash()]));
+
+    // --- Update --------------------------------------------------------------
+
+    let committed = rotation.next_key().to_string();
+    let rotation = rotation.rotate(&Vault).await.expect("should rotate");
+    assert_eq!(rotation.update_key(), committed);
+
+    let builder = DocumentBuilder::from(create_result.document.clone());
+    let update_result = UpdateBuilder::new()
+        .document(builder)
+        .log_entries(create_result.log)
+        .key_rotation(&rotation)
+        .build()
+        .await
+        .expect("should build document");
+
+    let params = &update_result.log_entries[1].parameters;
+    assert_eq!(params.update_keys, vec![rotation.update_key().to_string()]);
+    assert_eq!(params.next_key_hashes, Some(vec![rotation.next_key_hash()]));
+
+    webvh::resolve_log(&update_result.log_entries, None, None).await.expect("should resolve log");
+
+    // --- Deactivate ----------------------------------------------------------
+
+    let rotation = rotation.rotate(&Vault).await.expect("should rotate");
+    let deactivate_result = DeactivateBuilder::from(&update_result.log_entries)
+        .expect("should create builder")
+        .key_rotation(&rotation)
+        .expect("should rotate keys on builder")
+        .build()
+        .await
+        .expect("should build deactivated document");
+
+    // Should have 4 log entries: create, update, nullify next keys, deactivate.
+    assert_eq!(deactivate_result.log.len(), 4);
+
+    webvh::resolve_log(&deactivate_result.log, None, None).await.expect("should resolve log");
+}
@@ -1,7 +1,7 @@
 //! Tests for the update of an existing `did:webvh` document and associated log
 //! entry.
 
-use credibil_did::webvh::{CreateBuilder, UpdateBuilder, Witness, WitnessWeight};
+use credibil_did::webvh::{self, CreateBuilder, UpdateBuilder, Witness, WitnessWeight};
 use credibil_did::{DocumentBuilder, KeyId, Service, VerificationMethod};
 use credibil_ecc::{Curve, Keyring, NextKey, Signer};
 use credibil_jose::PublicKeyJwk;
@@ -117,3 +117,61 @@ async fn update_ok() {
     let logs = serde_json::to_string(&result.log_entries).expect("should serialize log entries");
     println!("{logs}");
 }
+
+// Rebuilding a resolved document should keep its relationships, `alsoKnownAs`
+// and `controller` without duplicating them, and keep its creation time but
+// not metadata describing the resolved version.
+#[tokio::test]
+async fn update_from_resolved() {
+    let signer =
+        Keyring::generate(&Vault, "wur", "signing", Curve::Ed25519).await.expect("should generate");
+    let verifying_key = signer.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let update_multi = jwk.to_multibase().expect("should get multibase");
+
+    let vm = VerificationMethod::build()
+        .key(update_multi.clone())
+        .key_id(KeyId::Authorization(update_multi.clone()));
+    let builder = DocumentBuilder::new()
+        .verification_method(vm)
+        .authentication(update_multi.clone())
+        .also_known_as("https://example.com")
+        .add_controller("did:example:controller");
+
+    let create_result = CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(builder)
+        .update_keys(vec![update_multi.clone()])
+        .signer(&signer)
+        .build()
+        .await
+        .expect("should build document");
+    let resolved =
+        webvh::resolve_log(&create_result.log, None, None).await.expect("should resolve");
+    let authentication = resolved.authentication.clone().expect("should have authentication");
+    assert_eq!(authentication.len(), 1);
+
+    let svc = Service::build()
+        .id("whois")
+        .service_type("LinkedVerifiablePresentation")
+        .endpoint("https://example.com/.well-known/whois");
+    let builder =
+        DocumentBuilder::from(resolved.clone()).authentication(update_multi.clone()).service(svc);
+    let result = UpdateBuilder::new()
+        .document(builder)
+        .log_entries(create_result.log)
+        .signer(&signer)
+        .build()
+        .await
+        .expect("should update document");
+
+    let state = &result.log_entries.last().expect("should have entry").state;
+    assert_eq!(state.authentication, Some(authentication));
+    assert_eq!(state.also_known_as, resolved.also_known_as);
+    assert_eq!(state.controller, resolved.controller);
+    assert_eq!(state.service.as_ref().map(Vec::len), Some(1));
+
+    let metadata = state.did_document_metadata.as_ref().expect("should have metadata");
+    let created = resolved.did_document_metadata.expect("should have metadata").created;
+    assert_eq!(metadata.created, created);
+    assert!(metadata.additional.is_none());
+}
@@ -1,8 +1,12 @@
 //! Tests to verify log entries.
 
-use credibil_did::webvh::{self, CreateBuilder, Witness, WitnessWeight};
-use credibil_did::{DocumentBuilder, KeyId, Service, VerificationMethod};
-use credibil_ecc::{Curve, Keyring, NextKey, Signer};
+use credibil_did::webvh::{
+    self, CreateBuilder, DeactivateBuilder, UpdateBuilder, Witness, WitnessEntry, WitnessWeight,
+};
+use credibil_did::{
+    DocumentBuilder, FixedClock, KeyId, SequentialIds, Service, VerificationMethod,
+};
+use credibil_ecc::{Curve, Entry, Keyring, NextKey, Signer};
 use credibil_jose::PublicKeyJwk;
 use test_utils::Vault;
 
@@ -107,3 +111,304 @@ async fn complex_proof() {
 
     webvh::verify_proofs(&result.log[0]).await.expect("should verify proof");
 }
+
+// Create a document requiring two update key signatures. Should verify when
+// signed by both update keys and fail when signed by only one.
+#[tokio::test]
+async fn threshold_proof() {
+    let signer_1 = Keyring::generate(&Vault, "wvt", "signing-1", Curve::Ed25519)
+        .await
+        .expect("should generate");
+    let verifying_key = signer_1.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let update_multi_1 = jwk.to_multibase().expect("should get multibase");
+
+    let signer_2 = Keyring::generate(&Vault, "wvt", "signing-2", Curve::Ed25519)
+        .await
+        .expect("should generate");
+    let verifying_key = signer_2.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let update_multi_2 = jwk.to_multibase().expect("should get multibase");
+
+    let vm = VerificationMethod::build()
+        .key(update_multi_1.clone())
+        .key_id(KeyId::Index("key-0".to_string()));
+    let builder = DocumentBuilder::new().verification_method(vm);
+
+    let result = CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(builder)
+        .update_keys(vec![update_multi_1.clone(), update_multi_2.clone()])
+        .update_threshold(2)
+        .signers(&[&signer_1, &signer_2])
+        .build()
+        .await
+        .expect("should build document");
+
+    let parameters = &result.log[0].parameters;
+    assert_eq!(parameters.update_threshold, Some(2));
+    assert_eq!(parameters.profile, Some(vec![webvh::MULTISIG_PROFILE.to_string()]));
+
+    let entry = &result.log[0];
+    let signers = webvh::verify_signers(entry, &entry.parameters.update_keys)
+        .await
+        .expect("should verify proof");
+    assert_eq!(signers, 2);
+
+    // A single signature does not meet the threshold.
+    let vm = VerificationMethod::build()
+        .key(update_multi_1.clone())
+        .key_id(KeyId::Index("key-0".to_string()));
+    let builder = DocumentBuilder::new().verification_method(vm);
+
+    let result = CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(builder)
+        .update_keys(vec![update_multi_1, update_multi_2])
+        .update_threshold(2)
+        .signer(&signer_1)
+        .build()
+        .await
+        .expect("should build document");
+
+    webvh::verify_proofs(&result.log[0]).await.expect_err("should not meet threshold");
+    webvh::resolve_log(&result.log, None, None).await.expect_err("should not resolve");
+}
+
+// Add a second update key's proof chained to the first. The chain should
+// verify and a proof referencing a missing proof should be rejected.
+#[tokio::test]
+async fn chained_proof() {
+    let signer_1 = Keyring::generate(&Vault, "wvc", "signing-1", Curve::Ed25519)
+        .await
+        .expect("should generate");
+    let verifying_key = signer_1.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let update_multi_1 = jwk.to_multibase().expect("should get multibase");
+
+    let signer_2 = Keyring::generate(&Vault, "wvc", "signing-2", Curve::Ed25519)
+        .await
+        .expect("should generate");
+    let verifying_key = signer_2.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let update_multi_2 = jwk.to_multibase().expect("should get multibase");
+
+    let vm = VerificationMethod::build()
+        .key(update_multi_1.clone())
+        .key_id(KeyId::Index("key-0".to_string()));
+    let builder = DocumentBuilder::new().verification_method(vm);
+
+    let result = CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(builder)
+        .update_keys(vec![update_multi_1, update_multi_2])
+        .signer(&signer_1)
+        .build()
+        .await
+        .expect("should build document");
+
+    let mut entry = result.log[0].clone();
+    let first = entry.proof[0].id.clone().expect("proof should have an id");
+    let chained = entry.chained_proof(&signer_2, &[first]).await.expect("should chain proof");
+    entry.proof.push(chained);
+
+    webvh::verify_proofs(&entry).await.expect("should verify chain");
+    let signers = webvh::verify_signers(&entry, &entry.parameters.update_keys)
+        .await
+        .expect("should verify chain");
+    assert_eq!(signers, 2);
+
+    // A proof cannot chain to a proof that is not on the log entry.
+    entry
+        .chained_proof(&signer_2, &["urn:uuid:missing".to_string()])
+        .await
+        .expect_err("should not chain to a missing proof");
+
+    let mut broken = entry.clone();
+    broken.proof[1].previous_proof = Some("urn:uuid:missing".to_string().into());
+    webvh::verify_proofs(&broken).await.expect_err("should reject a broken chain");
+}
+
+// A deactivated entry clears its update keys, so must be signed by the
+// previous entry's update keys. A proof from any other key should be rejected.
+#[tokio::test]
+async fn deactivated_proof() {
+    let signer =
+        Keyring::generate(&Vault, "wvd", "signing", Curve::Ed25519).await.expect("should generate");
+    let verifying_key = signer.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let update_multi = jwk.to_multibase().expect("should get multibase");
+
+    let vm = VerificationMethod::build()
+        .key(update_multi.clone())
+        .key_id(KeyId::Index("key-0".to_string()));
+    let builder = DocumentBuilder::new().verification_method(vm);
+
+    let result = CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(builder)
+        .update_keys(vec![update_multi])
+        .signer(&signer)
+        .build()
+        .await
+        .expect("should build document");
+
+    let deactivated = DeactivateBuilder::from(&result.log)
+        .expect("should create builder")
+        .signer(&signer)
+        .build()
+        .await
+        .expect("should deactivate");
+    webvh::resolve_log(&deactivated.log, None, None).await.expect("should resolve");
+
+    // re-sign the deactivation with a key that was never authorized
+    let other =
+        Keyring::generate(&Vault, "wvd", "other", Curve::Ed25519).await.expect("should generate");
+    let mut log = deactivated.log;
+    let last = log.last_mut().expect("should have entries");
+    last.proof.clear();
+    last.sign(&other).await.expect("should sign");
+
+    webvh::resolve_log(&log, None, None).await.expect_err("should reject unauthorized key");
+}
+
+// Under the multisig profile, an entry should be authorized by the previous
+// entry's update keys and threshold. An entry signed only by keys it
+// introduces itself should be rejected, however many of them sign.
+#[tokio::test]
+async fn self_authorized_proof() {
+    let signer_1 = Keyring::generate(&Vault, "wvs", "signing-1", Curve::Ed25519)
+        .await
+        .expect("should generate");
+    let signer_2 = Keyring::generate(&Vault, "wvs", "signing-2", Curve::Ed25519)
+        .await
+        .expect("should generate");
+    let update_keys = vec![multikey(&signer_1).await, multikey(&signer_2).await];
+
+    let vm = VerificationMethod::build()
+        .key(update_keys[0].clone())
+        .key_id(KeyId::Index("key-0".to_string()));
+    let result = CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(DocumentBuilder::new().verification_method(vm))
+        .update_keys(update_keys)
+        .update_threshold(2)
+        .signers(&[&signer_1, &signer_2])
+        .build()
+        .await
+        .expect("should build document");
+    let parameters = &result.log[0].parameters;
+    assert_eq!(parameters.profile, Some(vec![webvh::MULTISIG_PROFILE.to_string()]));
+
+    let other_1 =
+        Keyring::generate(&Vault, "wvs", "other-1", Curve::Ed25519).await.expect("should generate");
+    let other_2 =
+        Keyring::generate(&Vault, "wvs", "other-2", Curve::Ed25519).await.expect("should generate");
+    let other_keys = vec![multikey(&other_1).await, multikey(&other_2).await];
+
+    // signed only by the keys the entry introduces
+    let update = UpdateBuilder::new()
+        .document(DocumentBuilder::from(result.document.clone()))
+        .log_entries(result.log.clone())
+        .rotate_keys(&other_keys, &[])
+        .signers(&[&other_1, &other_2])
+        .build()
+        .await
+        .expect("should build update");
+    webvh::resolve_log(&update.log_entries, None, None)
+        .await
+        .expect_err("should reject self-authorized keys");
+
+    // lowering the threshold still requires the current threshold
+    let update = UpdateBuilder::new()
+        .document(DocumentBuilder::from(result.document.clone()))
+        .log_entries(result.log.clone())
+        .update_threshold(1)
+        .signer(&signer_1)
+        .build()
+        .await
+        .expect("should build update");
+    webvh::resolve_log(&update.log_entries, None, None)
+        .await
+        .expect_err("should not meet the current threshold");
+
+    // the current update keys can rotate to the new keys
+    let update = UpdateBuilder::new()
+        .document(DocumentBuilder::from(result.document))
+        .log_entries(result.log)
+        .rotate_keys(&other_keys, &[])
+        .signers(&[&signer_1, &signer_2])
+        .build()
+        .await
+        .expect("should build update");
+    webvh::resolve_log(&update.log_entries, None, None).await.expect("should resolve");
+}
+
+async fn multikey(entry: &Entry) -> String {
+    let verifying_key = entry.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    jwk.to_multibase().expect("should get multibase")
+}
+
+// Each witness's proof chain should be verified separately: a witness with a
+// broken chain is not counted but does not prevent other witnesses' proofs
+// from counting, and a witness's weight is only counted once.
+#[tokio::test]
+async fn witness_chains() {
+    let signer =
+        Keyring::generate(&Vault, "wvw", "signing", Curve::Ed25519).await.expect("should generate");
+    let update_multi = multikey(&signer).await;
+    let witness_1 = Keyring::generate(&Vault, "wvw", "witness-1", Curve::Ed25519)
+        .await
+        .expect("should generate");
+    let multi_1 = multikey(&witness_1).await;
+    let witness_2 = Keyring::generate(&Vault, "wvw", "witness-2", Curve::Ed25519)
+        .await
+        .expect("should generate");
+    let multi_2 = multikey(&witness_2).await;
+
+    let vm = VerificationMethod::build()
+        .key(update_multi.clone())
+        .key_id(KeyId::Index("key-0".to_string()));
+    let witness = Witness {
+        threshold: 1,
+        witnesses: vec![
+            WitnessWeight {
+                id: format!("did:key:{multi_1}#{multi_1}"),
+                weight: 1,
+            },
+            WitnessWeight {
+                id: format!("did:key:{multi_2}#{multi_2}"),
+                weight: 1,
+            },
+        ],
+    };
+    let result = CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(DocumentBuilder::new().verification_method(vm))
+        .update_keys(vec![update_multi])
+        .witness(&witness)
+        .signer(&signer)
+        .build()
+        .await
+        .expect("should build document");
+    let entry = &result.log[0];
+
+    // witness 1 chains to the controller's proof, twice
+    let controller = entry.proof[0].id.clone().expect("proof should have an id");
+    let created = "2025-01-01T00:00:00Z".parse().expect("should parse");
+    let chained = entry
+        .chained_proof_with(&witness_1, &[controller], &FixedClock(created), &SequentialIds::new())
+        .await
+        .expect("should chain proof");
+    assert_eq!(chained.id.as_deref(), Some("urn:uuid:00000000-0000-0000-0000-000000000001"));
+    assert_eq!(chained.created, Some(created));
+    let repeated = entry.proof(&witness_1).await.expect("should get witness proof");
+
+    // witness 2's chain references a missing proof
+    let valid = entry.proof(&witness_2).await.expect("should get witness proof");
+    let mut dangling = entry.proof(&witness_2).await.expect("should get witness proof");
+    dangling.previous_proof = Some("urn:uuid:missing".to_string().into());
+
+    let witness_entries = vec![WitnessEntry {
+        version_id: entry.version_id.clone(),
+        proof: vec![chained, repeated, valid, dangling],
+    }];
+    let weight =
+        webvh::verify_witness(entry, &witness_entries).await.expect("should meet threshold");
+    assert_eq!(weight, 1);
+}
@@ -0,0 +1,216 @@
+//! Tests for resolving a `did:webvh` log cross-checked with watchers.
+
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow};
+use credibil_did::webvh::{
+    self, CreateBuilder, CreateResult, LogEntry, UpdateBuilder, Watcher, Witness, WitnessEntry,
+    WitnessWeight,
+};
+use credibil_did::{DocumentBuilder, KeyId, Service, VerificationMethod};
+use credibil_ecc::{Curve, Entry, Keyring, Signer};
+use credibil_jose::PublicKeyJwk;
+use test_utils::Vault;
+
+const WATCHER: &str = "https://watcher.example.com";
+
+// Local stand-in for watcher services.
+#[derive(Default)]
+struct LocalWatcher {
+    logs: HashMap<String, Vec<LogEntry>>,
+    witness: HashMap<String, Vec<WitnessEntry>>,
+}
+
+impl Watcher for LocalWatcher {
+    async fn log(&self, watcher: &str, _did: &str) -> Result<Vec<LogEntry>> {
+        self.logs.get(watcher).cloned().ok_or_else(|| anyhow!("watcher not found"))
+    }
+
+    async fn witness(&self, watcher: &str, _did: &str) -> Result<Vec<WitnessEntry>> {
+        Ok(self.witness.get(watcher).cloned().unwrap_or_default())
+    }
+}
+
+async fn create(signer: &Entry, witness: Option<&Witness>) -> CreateResult {
+    let verifying_key = signer.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let update_multi = jwk.to_multibase().expect("should get multibase");
+
+    let vm = VerificationMethod::build()
+        .key(update_multi.clone())
+        .key_id(KeyId::Authorization(update_multi.clone()));
+    let builder = DocumentBuilder::new().verification_method(vm);
+
+    let mut builder = CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(builder)
+        .update_keys(vec![update_multi])
+        .watchers(vec![WATCHER.to_string()]);
+    if let Some(witness) = witness {
+        builder = builder.witness(witness);
+    }
+    builder.signer(signer).build().await.expect("should build document")
+}
+
+async fn update(signer: &Entry, create_result: &CreateResult, endpoint: &str) -> Vec<LogEntry> {
+    let service = Service::build()
+        .id("whois")
+        .service_type("LinkedVerifiablePresentation")
+        .endpoint(endpoint);
+    let builder = DocumentBuilder::from(create_result.document.clone()).service(service);
+
+    UpdateBuilder::new()
+        .document(builder)
+        .log_entries(create_result.log.clone())
+        .signer(signer)
+        .build()
+        .await
+        .expect("should update document")
+        .log_entries
+}
+
+// A watcher holding a newer log than the origin should be used to resolve the
+// latest document.
+#[tokio::test]
+async fn watcher_ahead() {
+    let signer =
+        Keyring::generate(&Vault, "wwa", "signing", Curve::Ed25519).await.expect("should generate");
+    let create_result = create(&signer, None).await;
+    assert_eq!(create_result.log[0].parameters.watchers, Some(vec![WATCHER.to_string()]));
+
+    let updated = update(&signer, &create_result, "https://example.com/whois").await;
+    let watcher = LocalWatcher {
+        logs: HashMap::from([(WATCHER.to_string(), updated)]),
+        ..LocalWatcher::default()
+    };
+
+    let result = webvh::resolve_log_watched(&create_result.log, &watcher, None, None)
+        .await
+        .expect("should resolve");
+    assert_eq!(result.ahead, vec![WATCHER.to_string()]);
+    assert_eq!(result.log.len(), 2);
+    assert!(result.document.service.is_some());
+
+    // an unreachable watcher should not prevent resolution
+    let result =
+        webvh::resolve_log_watched(&create_result.log, &LocalWatcher::default(), None, None)
+            .await
+            .expect("should resolve");
+    assert_eq!(result.unavailable, vec![WATCHER.to_string()]);
+    assert_eq!(result.log.len(), 1);
+}
+
+// A watcher log that conflicts with the origin log should be reported and
+// the origin log used.
+#[tokio::test]
+async fn watcher_conflict() {
+    let signer =
+        Keyring::generate(&Vault, "wwc", "signing", Curve::Ed25519).await.expect("should generate");
+    let create_result = create(&signer, None).await;
+
+    let origin = update(&signer, &create_result, "https://origin.example.com/whois").await;
+    let forked = update(&signer, &create_result, "https://forked.example.com/whois").await;
+    let watcher = LocalWatcher {
+        logs: HashMap::from([(WATCHER.to_string(), forked.clone())]),
+        ..LocalWatcher::default()
+    };
+
+    let result =
+        webvh::resolve_log_watched(&origin, &watcher, None, None).await.expect("should resolve");
+    assert_eq!(result.conflicting, vec![WATCHER.to_string()]);
+    assert_eq!(result.log.last().map(|e| &e.version_id), origin.last().map(|e| &e.version_id));
+
+    let report = webvh::detect_equivocation(&[
+        webvh::SourcedLog {
+            source: "origin".to_string(),
+            log: origin,
+        },
+        webvh::SourcedLog {
+            source: WATCHER.to_string(),
+            log: forked,
+        },
+    ])
+    .await
+    .expect("should compare logs");
+    assert!(report.evidence.is_some());
+}
+
+// A watcher serving a log that fails verification should be reported and
+// the origin log used.
+#[tokio::test]
+async fn watcher_faulty() {
+    let signer =
+        Keyring::generate(&Vault, "wwf", "signing", Curve::Ed25519).await.expect("should generate");
+    let create_result = create(&signer, None).await;
+
+    let mut tampered = update(&signer, &create_result, "https://example.com/whois").await;
+    tampered[1].proof.clear();
+    let watcher = LocalWatcher {
+        logs: HashMap::from([(WATCHER.to_string(), tampered)]),
+        ..LocalWatcher::default()
+    };
+
+    let result = webvh::resolve_log_watched(&create_result.log, &watcher, None, None)
+        .await
+        .expect("should resolve");
+    assert_eq!(result.faulty, vec![WATCHER.to_string()]);
+    assert!(result.ahead.is_empty());
+    assert_eq!(result.log.len(), 1);
+}
+
+// Entries a watcher holds ahead of the origin should only be used once their
+// witness proofs (held by the watcher) meet the threshold.
+#[tokio::test]
+async fn watcher_witness() {
+    let signer =
+        Keyring::generate(&Vault, "wwt", "signing", Curve::Ed25519).await.expect("should generate");
+    let witness_signer =
+        Keyring::generate(&Vault, "wwt", "witness", Curve::Ed25519).await.expect("should generate");
+    let verifying_key = witness_signer.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let witness_multi = jwk.to_multibase().expect("should get multibase");
+    let witness = Witness {
+        threshold: 1,
+        witnesses: vec![WitnessWeight {
+            id: format!("did:key:{witness_multi}#{witness_multi}"),
+            weight: 1,
+        }],
+    };
+
+    let create_result = create(&signer, Some(&witness)).await;
+    let origin_proofs = vec![WitnessEntry {
+        version_id: create_result.log[0].version_id.clone(),
+        proof: vec![create_result.log[0].proof(&witness_signer).await.expect("should prove")],
+    }];
+    let updated = update(&signer, &create_result, "https://example.com/whois").await;
+
+    // without the watcher's witness proofs, the new entry is not used
+    let watcher = LocalWatcher {
+        logs: HashMap::from([(WATCHER.to_string(), updated.clone())]),
+        ..LocalWatcher::default()
+    };
+    let result =
+        webvh::resolve_log_watched(&create_result.log, &watcher, Some(&origin_proofs), None)
+            .await
+            .expect("should resolve");
+    assert_eq!(result.ahead, vec![WATCHER.to_string()]);
+    assert_eq!(result.unwitnessed, vec![updated[1].version_id.clone()]);
+    assert_eq!(result.log.len(), 1);
+    assert!(result.document.service.is_none());
+
+    // with them, it is
+    let watcher_proofs = vec![WitnessEntry {
+        version_id: updated[1].version_id.clone(),
+        proof: vec![updated[1].proof(&witness_signer).await.expect("should prove")],
+    }];
+    let watcher = LocalWatcher {
+        logs: HashMap::from([(WATCHER.to_string(), updated)]),
+        witness: HashMap::from([(WATCHER.to_string(), watcher_proofs)]),
+    };
+    let result =
+        webvh::resolve_log_watched(&create_result.log, &watcher, Some(&origin_proofs), None)
+            .await
+            .expect("should resolve");
+    assert!(result.unwitnessed.is_empty());
+    assert_eq!(result.log.len(), 2);
+    assert!(result.document.service.is_some());
+}
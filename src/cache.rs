@@ -0,0 +1,284 @@
+//! # Caching Resolver
+//!
+//! A [`Resolver`] wrapper that caches resolved DID documents (and verified
+//! `did:webvh` logs) keyed by DID.
+//!
+//! Cached `did:webvh` entries expire according to the log's `ttl` parameter
+//! while `did:web` entries use a configurable default. Once expired, a
+//! `did:webvh` log is re-fetched and only the entries added since it was
+//! verified are checked (see [`Checkpoint`]).
+//!
+//! Documents that fail verification are cached (for a shorter period) so
+//! repeated lookups of an invalid DID don't hit the network. Errors fetching
+//! the document are not cached. Concurrent requests for the same DID share a
+//! single fetch.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+use credibil_did::webvh::{self, Checkpoint, LogEntry};
+use credibil_did::{Document, Method, Url};
+
+use crate::provider::Resolver;
+use crate::resolve::{fetch, fetch_document, parse_log};
+
+/// Default time to cache `did:web` documents.
+const WEB_TTL: Duration = Duration::from_secs(300);
+
+/// Default time to cache `did:webvh` documents when the log does not set a
+/// `ttl`.
+const WEBVH_TTL: Duration = Duration::from_secs(3600);
+
+/// Default time to cache failed resolutions.
+const NEGATIVE_TTL: Duration = Duration::from_secs(30);
+
+type FlightLocks = Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>>;
+
+/// A [`Resolver`] that caches resolved DID documents.
+///
+/// Wraps another resolver, passing through raw [`Resolver::resolve`] requests
+/// and caching the results of [`Resolver::document`].
+#[derive(Clone)]
+pub struct CachingResolver<R> {
+    inner: R,
+    web_ttl: Duration,
+    webvh_ttl: Duration,
+    negative_ttl: Duration,
+    entries: Arc<Mutex<HashMap<String, CacheEntry>>>,
+    in_flight: FlightLocks,
+}
+
+#[derive(Clone)]
+struct CacheEntry {
+    resolved: Result<Resolved, String>,
+    expires: Instant,
+
+    // the latest verified state of a `did:webvh` log, kept across failed
+    // refreshes so a rewritten log is not accepted once the error expires
+    checkpoint: Option<Checkpoint>,
+}
+
+#[derive(Clone)]
+struct Resolved {
+    document: Document,
+
+    // the verified log for `did:webvh` DIDs
+    log: Option<Arc<Vec<LogEntry>>>,
+
+    // documents resolved from the log for versioned queries
+    versions: Arc<Mutex<HashMap<String, Document>>>,
+}
+
+impl<R: Resolver> CachingResolver<R> {
+    /// Create a new `CachingResolver` wrapping the provided resolver.
+    #[must_use]
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            web_ttl: WEB_TTL,
+            webvh_ttl: WEBVH_TTL,
+            negative_ttl: NEGATIVE_TTL,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Set the time to cache `did:web` documents. Defaults to 5 minutes.
+    #[must_use]
+    pub const fn web_ttl(mut self, ttl: Duration) -> Self {
+        self.web_ttl = ttl;
+        self
+    }
+
+    /// Set the time to cache `did:webvh` documents when the log does not
+    /// specify a `ttl`. Defaults to 1 hour.
+    #[must_use]
+    pub const fn webvh_ttl(mut self, ttl: Duration) -> Self {
+        self.webvh_ttl = ttl;
+        self
+    }
+
+    /// Set the time to cache failed resolutions. Defaults to 30 seconds.
+    #[must_use]
+    pub const fn negative_ttl(mut self, ttl: Duration) -> Self {
+        self.negative_ttl = ttl;
+        self
+    }
+
+    /// Remove any cached entry for the DID.
+    pub fn invalidate(&self, did: &str) {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner).remove(did);
+    }
+
+    /// Remove all cached entries.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner).clear();
+    }
+
+    // Return the unexpired cache entry for the DID, if any.
+    fn cached(&self, did: &str) -> Option<CacheEntry> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.get(did).filter(|entry| entry.expires > Instant::now()).cloned()
+    }
+
+    // Return the checkpoint of a (possibly expired) cache entry for the DID.
+    fn checkpoint(&self, did: &str) -> Option<Checkpoint> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.get(did).and_then(|entry| entry.checkpoint.clone())
+    }
+
+    // Fetch and verify the DID document, caching the result.
+    //
+    // Errors fetching the document (or log) may be transient so are returned
+    // without caching. Verification failures are cached.
+    async fn fetch(&self, did: &str, did_url: &Url) -> Result<CacheEntry> {
+        let checkpoint = self.checkpoint(did);
+
+        let (resolved, checkpoint) = match did_url.method {
+            Method::WebVh => {
+                let log_url = Url {
+                    path: None,
+                    ..did_url.clone()
+                };
+                let body = fetch(&log_url.to_webvh_http()?, &self.inner).await?;
+                match verify_log(&body, checkpoint.as_ref()).await {
+                    Ok((resolved, checkpoint)) => (Ok(resolved), Some(checkpoint)),
+                    Err(e) => (Err(e), checkpoint),
+                }
+            }
+            _ => {
+                let body = fetch(&did_url.to_web_http(), &self.inner).await?;
+                let resolved = serde_json::from_slice(&body)
+                    .map(|document| Resolved {
+                        document,
+                        log: None,
+                        versions: Arc::default(),
+                    })
+                    .map_err(|e| anyhow!("failed to deserialize DID document: {e}"));
+                (resolved, None)
+            }
+        };
+
+        let ttl = match &resolved {
+            Ok(Resolved { log: Some(log), .. }) => log
+                .last()
+                .map(|e| e.parameters.ttl)
+                .filter(|ttl| *ttl > 0)
+                .map_or(self.webvh_ttl, Duration::from_secs),
+            Ok(_) => self.web_ttl,
+            Err(_) => self.negative_ttl,
+        };
+        let entry = CacheEntry {
+            resolved: resolved.map_err(|e| e.to_string()),
+            expires: Instant::now() + ttl,
+            checkpoint,
+        };
+
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(did.to_string(), entry.clone());
+        Ok(entry)
+    }
+
+    // Take the lock used to deduplicate concurrent fetches for the DID.
+    fn flight(&self, did: &str) -> Flight {
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(PoisonError::into_inner);
+        Flight {
+            in_flight: Arc::clone(&self.in_flight),
+            did: did.to_string(),
+            lock: Arc::clone(in_flight.entry(did.to_string()).or_default()),
+        }
+    }
+}
+
+// Verify a `did:webvh` log. When the log has been verified before, only the
+// entries added since the checkpoint are verified.
+async fn verify_log(
+    body: &[u8], checkpoint: Option<&Checkpoint>,
+) -> Result<(Resolved, Checkpoint)> {
+    let log = parse_log(body)?;
+    let checkpoint = match checkpoint {
+        Some(checkpoint) => checkpoint.extend(&log, None).await?,
+        None => Checkpoint::verify(&log, None).await?,
+    };
+    let resolved = Resolved {
+        document: checkpoint.document.clone(),
+        log: Some(Arc::new(log)),
+        versions: Arc::default(),
+    };
+    Ok((resolved, checkpoint))
+}
+
+// A DID's fetch lock. Removed from the in-flight map when dropped (including
+// when the request is cancelled) unless other requests are waiting on it.
+struct Flight {
+    in_flight: FlightLocks,
+    did: String,
+    lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl Drop for Flight {
+    fn drop(&mut self) {
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(PoisonError::into_inner);
+        let current = in_flight.get(&self.did).is_some_and(|lock| Arc::ptr_eq(lock, &self.lock));
+        // one reference held by the map and one by this flight
+        if current && Arc::strong_count(&self.lock) <= 2 {
+            in_flight.remove(&self.did);
+        }
+    }
+}
+
+impl<R: Resolver> Resolver for CachingResolver<R> {
+    async fn resolve(&self, url: &str) -> Result<Vec<u8>> {
+        self.inner.resolve(url).await
+    }
+
+    async fn document(&self, did_url: &Url) -> Result<Document> {
+        // `did:key` documents are derived from the DID so are not cached
+        if did_url.method == Method::Key {
+            return fetch_document(did_url, &self.inner).await;
+        }
+
+        let did = format!("did:{}:{}", did_url.method, did_url.id);
+        let entry = if let Some(entry) = self.cached(&did) {
+            entry
+        } else {
+            // single flight: wait for any fetch in progress, then re-check
+            let flight = self.flight(&did);
+            let _guard = flight.lock.lock().await;
+            match self.cached(&did) {
+                Some(entry) => entry,
+                None => self.fetch(&did, did_url).await?,
+            }
+        };
+
+        let resolved = entry.resolved.map_err(|e| anyhow!(e))?;
+
+        // resolve versioned requests from the cached (verified) log, once
+        if let (Some(log), Some(query)) = (&resolved.log, &did_url.query) {
+            if query.version_id.is_some() || query.version_time.is_some() {
+                let key = serde_json::to_string(query)?;
+                let cached = resolved
+                    .versions
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .get(&key)
+                    .cloned();
+                if let Some(document) = cached {
+                    return Ok(document);
+                }
+                let document = webvh::resolve_log(log, None, Some(query)).await?;
+                resolved
+                    .versions
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .insert(key, document.clone());
+                return Ok(document);
+            }
+        }
+        Ok(resolved.document)
+    }
+}
@@ -0,0 +1,446 @@
+//! # Data Integrity
+//!
+//! Securing arbitrary JSON documents with [W3C Data Integrity] proofs.
+//!
+//! Proofs are added using a [`Signature`] signer and verified by resolving
+//! the proof's verification method and checking it is authorized for the
+//! proof's purpose (its verification relationship).
+//!
+//! A document may hold several proofs: a proof set of independent proofs or
+//! a proof chain, where each proof references earlier proofs by `id` using
+//! `previousProof` and covers the document together with those proofs.
+//!
+//! Supported cryptosuites:
+//!
+//! - `eddsa-jcs-2022` and `eddsa-rdfc-2022` (Ed25519 signers)
+//! - `ecdsa-jcs-2019` and `ecdsa-rdfc-2019` (P-256 and P-384 signers)
+//!
+//! Documents secured with the `bbs-2023` or `ecdsa-sd-2023` selective
+//! disclosure cryptosuites (see [`credibil_did::bbs`] and
+//! [`credibil_did::ecdsa_sd`]) can be verified once the holder has derived a
+//! proof. The verification method must be a BLS12-381 G2 or P-256 multikey,
+//! respectively.
+//!
+//! RDFC and selective disclosure cryptosuites require the document's
+//! `@context` to reference only the contexts bundled with
+//! [`credibil_did::rdf`].
+//!
+//! The legacy `Ed25519Signature2020` and `JsonWebSignature2020` proof types
+//! can also be verified when enabled by [`VerifyOptions::legacy`].
+//!
+//! [W3C Data Integrity]: https://www.w3.org/TR/vc-data-integrity
+
+mod legacy;
+
+use anyhow::{Result, anyhow, bail};
+use chrono::{DateTime, TimeDelta, Utc};
+use credibil_did::bbs::{self, PublicKey};
+use credibil_did::{KeyFormat, KeyPurpose, OneOrMany, Proof, ProofKey, ecdsa_sd, order_proofs};
+use credibil_ecc::Signer;
+use multibase::Base;
+use serde_json::Value;
+
+use crate::LEEWAY;
+use crate::provider::{Resolver, Signature, VerifyBy};
+use crate::resolve::authorized_method;
+
+/// Proof type for Data Integrity proofs.
+const DATA_INTEGRITY_PROOF: &str = "DataIntegrityProof";
+
+/// The canonicalization algorithm used to create a proof, selecting between
+/// the JCS and RDFC variants of the signer's cryptosuite.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Canonicalization {
+    /// JSON Canonicalization Scheme (`*-jcs-*` cryptosuites).
+    #[default]
+    Jcs,
+
+    /// RDF Dataset Canonicalization (`*-rdfc-*` cryptosuites).
+    Rdfc,
+}
+
+/// Options used when adding a proof.
+#[derive(Clone, Debug)]
+pub struct ProofOptions {
+    /// The purpose of the proof. The signing key must be authorized for the
+    /// corresponding verification relationship.
+    pub purpose: KeyPurpose,
+
+    /// When the proof was created. Defaults to the current time.
+    pub created: Option<DateTime<Utc>>,
+
+    /// When the proof expires.
+    pub expires: Option<DateTime<Utc>>,
+
+    /// The security domains in which the proof is meant to be used.
+    pub domain: Option<Vec<String>>,
+
+    /// A challenge provided by the verifier to mitigate replay attacks.
+    pub challenge: Option<String>,
+
+    /// The canonicalization algorithm. Defaults to JCS.
+    pub canonicalization: Canonicalization,
+
+    /// An identifier for the proof, allowing later proofs to chain to it.
+    pub id: Option<String>,
+
+    /// The identifiers of existing proofs on the document that the proof
+    /// chains to. The proof covers the document together with these proofs.
+    pub previous_proof: Option<Vec<String>>,
+}
+
+impl Default for ProofOptions {
+    fn default() -> Self {
+        Self {
+            purpose: KeyPurpose::AssertionMethod,
+            created: None,
+            expires: None,
+            domain: None,
+            challenge: None,
+            canonicalization: Canonicalization::Jcs,
+            id: None,
+            previous_proof: None,
+        }
+    }
+}
+
+/// Options used when verifying a proof.
+#[derive(Clone, Debug)]
+pub struct VerifyOptions {
+    /// The expected purpose of the proof.
+    pub purpose: KeyPurpose,
+
+    /// The domain the proof must have been created for, if any.
+    pub domain: Option<String>,
+
+    /// The challenge the proof must have been created with, if any.
+    pub challenge: Option<String>,
+
+    /// The time to check the proof's `created` and `expires` against.
+    /// Defaults to the current time.
+    pub now: Option<DateTime<Utc>>,
+
+    /// Whether to accept proofs using the legacy `Ed25519Signature2020` and
+    /// `JsonWebSignature2020` proof types. Defaults to `false`.
+    pub legacy: bool,
+}
+
+impl Default for VerifyOptions {
+    fn default() -> Self {
+        Self {
+            purpose: KeyPurpose::AssertionMethod,
+            domain: None,
+            challenge: None,
+            now: None,
+            legacy: false,
+        }
+    }
+}
+
+/// Add a Data Integrity proof to a JSON document.
+///
+/// The cryptosuite is chosen from the signer's algorithm and the requested
+/// canonicalization. The proof's verification method is the signer's key ID
+/// or, if the signer provides a raw key, the equivalent `did:key` DID URL.
+///
+/// Any existing proofs are kept and the new proof added to the proof set.
+/// When `previous_proof` is set, the new proof chains to those proofs.
+///
+/// # Errors
+///
+/// Returns an error if the document is not a JSON object, if the proof's
+/// `id` is already used, if a previous proof is not on the document, if the
+/// signer's algorithm is not supported, or if the document cannot be
+/// canonicalized.
+pub async fn add_proof(
+    document: &Value, signer: &impl Signature, options: &ProofOptions,
+) -> Result<Value> {
+    if !document.is_object() {
+        bail!("document must be a JSON object");
+    }
+
+    let proof_key = ProofKey::from_algorithm(&signer.algorithm().await?)?;
+    let verification_method = match signer.verification_method().await? {
+        VerifyBy::KeyId(kid) => kid,
+        VerifyBy::Jwk(_) => {
+            let multikey = proof_key.multikey(&signer.verifying_key().await?.to_bytes())?;
+            format!("did:key:{multikey}#{multikey}")
+        }
+    };
+
+    let cryptosuite = match options.canonicalization {
+        Canonicalization::Jcs => proof_key.jcs_cryptosuite(),
+        Canonicalization::Rdfc => proof_key.rdfc_cryptosuite(),
+    };
+
+    let mut proof = Proof {
+        type_: DATA_INTEGRITY_PROOF.to_string(),
+        cryptosuite: Some(cryptosuite.to_string()),
+        proof_purpose: proof_purpose(&options.purpose)?.to_string(),
+        verification_method,
+        created: Some(options.created.unwrap_or_else(Utc::now)),
+        expires: options.expires,
+        domain: options.domain.clone().map(OneOrMany::Many),
+        challenge: options.challenge.clone(),
+        id: options.id.clone(),
+        previous_proof: options.previous_proof.clone().map(OneOrMany::Many),
+        ..Proof::default()
+    };
+
+    let mut proofs = existing_proofs(document)?;
+    if let Some(id) = &proof.id {
+        if proofs.iter().any(|existing| existing.get("id").and_then(Value::as_str) == Some(id)) {
+            bail!("proof id {id} is already used");
+        }
+    }
+
+    let unsecured = unsecured(document);
+    let covered = chained(&unsecured, &proofs, &proof)?;
+    let payload = signing_payload(proof_key, &covered, &proof)?;
+    let signature = signer.sign(&payload).await;
+    proof.proof_value = Some(multibase::encode(Base::Base58Btc, signature));
+
+    let mut secured = unsecured;
+    proofs.push(serde_json::to_value(&proof)?);
+    secured["proof"] = if proofs.len() == 1 { proofs.remove(0) } else { Value::Array(proofs) };
+    Ok(secured)
+}
+
+/// Verify the Data Integrity proof on a JSON document.
+///
+/// The proof's verification method is resolved and must be authorized for
+/// the expected proof purpose. The proof's `created`, `expires`, `domain` and
+/// `challenge` are checked against the options, allowing a minute of clock
+/// skew for `created`.
+///
+/// Use [`verify_proofs`] for documents secured by a proof set or chain.
+///
+/// # Errors
+///
+/// Returns an error if the document does not have exactly one proof or if
+/// the proof is not valid.
+pub async fn verify_proof(
+    document: &Value, resolver: &impl Resolver, options: &VerifyOptions,
+) -> Result<Proof> {
+    let mut proofs = verify_proofs(document, resolver, options).await?;
+    if proofs.len() > 1 {
+        bail!("document has more than one proof");
+    }
+    Ok(proofs.remove(0))
+}
+
+/// Verify every Data Integrity proof on a JSON document secured by a proof
+/// set or proof chain.
+///
+/// Chained proofs are verified after the proofs they reference, over the
+/// document together with those proofs. Each proof is checked as for
+/// [`verify_proof`]. The proofs are returned in the order verified.
+///
+/// # Errors
+///
+/// Returns an error if the document has no proof, if a proof chain is broken
+/// or cyclic, or if any proof is not valid.
+pub async fn verify_proofs(
+    document: &Value, resolver: &impl Resolver, options: &VerifyOptions,
+) -> Result<Vec<Proof>> {
+    let values = existing_proofs(document)?;
+    if values.is_empty() {
+        bail!("document has no proof");
+    }
+    let proofs = values
+        .iter()
+        .map(|value| serde_json::from_value(value.clone()))
+        .collect::<Result<Vec<Proof>, _>>()?;
+
+    let unsecured = unsecured(document);
+    let mut verified = Vec::with_capacity(proofs.len());
+    for proof in order_proofs(&proofs)? {
+        let covered = chained(&unsecured, &values, proof)?;
+        verify_one(&covered, proof, resolver, options).await?;
+        verified.push(proof.clone());
+    }
+    Ok(verified)
+}
+
+// Verify a single proof over the document it covers.
+async fn verify_one(
+    covered: &Value, proof: &Proof, resolver: &impl Resolver, options: &VerifyOptions,
+) -> Result<()> {
+    check_proof(proof, options)?;
+
+    let multikey = resolve_key(&proof.verification_method, &options.purpose, resolver).await?;
+    match proof.type_.as_str() {
+        legacy::ED25519_SIGNATURE_2020 => legacy::verify_ed25519_2020(covered, proof, &multikey),
+        legacy::JSON_WEB_SIGNATURE_2020 => legacy::verify_jws_2020(covered, proof, &multikey),
+        _ => verify_data_integrity(covered, proof, &multikey),
+    }
+}
+
+// Verify a `DataIntegrityProof` using the multikey public key.
+fn verify_data_integrity(covered: &Value, proof: &Proof, multikey: &str) -> Result<()> {
+    if proof.cryptosuite.as_deref() == Some(bbs::CRYPTOSUITE) {
+        return bbs::verify_derived_proof(covered, proof, &PublicKey::from_multikey(multikey)?);
+    }
+    if proof.cryptosuite.as_deref() == Some(ecdsa_sd::CRYPTOSUITE) {
+        return ecdsa_sd::verify_derived_proof(covered, proof, multikey);
+    }
+
+    let proof_key = ProofKey::from_multikey(multikey)?;
+    let cryptosuite = proof.cryptosuite.as_deref();
+    if cryptosuite != Some(proof_key.jcs_cryptosuite())
+        && cryptosuite != Some(proof_key.rdfc_cryptosuite())
+    {
+        bail!("cryptosuite does not match the verification method's key type");
+    }
+
+    let Some(proof_value) = &proof.proof_value else {
+        bail!("proof value is missing");
+    };
+    let (base, signature) = multibase::decode(proof_value)?;
+    if base != Base::Base58Btc {
+        bail!("unsupported multibase encoding");
+    }
+
+    let payload = signing_payload(proof_key, covered, proof)?;
+    proof_key.verify(multikey, &payload, &signature)
+}
+
+// Check the proof's properties against the verification options.
+fn check_proof(proof: &Proof, options: &VerifyOptions) -> Result<()> {
+    match proof.type_.as_str() {
+        DATA_INTEGRITY_PROOF => {
+            if !matches!(
+                proof.cryptosuite.as_deref(),
+                Some(
+                    "eddsa-jcs-2022"
+                        | "ecdsa-jcs-2019"
+                        | "eddsa-rdfc-2022"
+                        | "ecdsa-rdfc-2019"
+                        | bbs::CRYPTOSUITE
+                        | ecdsa_sd::CRYPTOSUITE
+                )
+            ) {
+                bail!(
+                    "unsupported cryptosuite: {}",
+                    proof.cryptosuite.as_deref().unwrap_or_default()
+                );
+            }
+        }
+        legacy::ED25519_SIGNATURE_2020 | legacy::JSON_WEB_SIGNATURE_2020 if options.legacy => {}
+        type_ => bail!("unsupported proof type: {type_}"),
+    }
+    if proof.proof_purpose != proof_purpose(&options.purpose)? {
+        bail!("proof purpose {} does not match the expected purpose", proof.proof_purpose);
+    }
+
+    // allow for clock skew between the signer and the verifier
+    let now = options.now.unwrap_or_else(Utc::now);
+    if proof.created.is_some_and(|created| created > now + TimeDelta::seconds(LEEWAY)) {
+        bail!("proof was created in the future");
+    }
+    if proof.expires.is_some_and(|expires| expires <= now) {
+        bail!("proof has expired");
+    }
+
+    if let Some(domain) = &options.domain {
+        let domains = proof.domain.as_ref().map(OneOrMany::to_vec).unwrap_or_default();
+        if !domains.contains(domain) {
+            bail!("proof was not created for domain {domain}");
+        }
+    }
+    if options.challenge.is_some() && proof.challenge != options.challenge {
+        bail!("proof challenge does not match");
+    }
+    Ok(())
+}
+
+// Resolve the (multikey) public key for the verification method, checking it
+// is authorized for the proof purpose.
+async fn resolve_key(
+    verification_method: &str, purpose: &KeyPurpose, resolver: &impl Resolver,
+) -> Result<String> {
+    match authorized_method(verification_method, purpose, resolver).await?.key {
+        KeyFormat::JsonWebKey { public_key_jwk } => ProofKey::jwk_multikey(&public_key_jwk),
+        KeyFormat::Multikey { public_key_multibase } => Ok(public_key_multibase),
+    }
+}
+
+// The proof purpose corresponding to a verification relationship.
+fn proof_purpose(purpose: &KeyPurpose) -> Result<&'static str> {
+    Ok(match purpose {
+        KeyPurpose::Authentication => "authentication",
+        KeyPurpose::AssertionMethod => "assertionMethod",
+        KeyPurpose::KeyAgreement => "keyAgreement",
+        KeyPurpose::CapabilityInvocation => "capabilityInvocation",
+        KeyPurpose::CapabilityDelegation => "capabilityDelegation",
+        KeyPurpose::VerificationMethod => bail!("proofs require a verification relationship"),
+    })
+}
+
+// The document without any proof.
+fn unsecured(document: &Value) -> Value {
+    let mut unsecured = document.clone();
+    if let Value::Object(map) = &mut unsecured {
+        map.remove("proof");
+    }
+    unsecured
+}
+
+// The proofs already on a document, as a proof set.
+fn existing_proofs(document: &Value) -> Result<Vec<Value>> {
+    match document.get("proof") {
+        None => Ok(Vec::new()),
+        Some(Value::Array(proofs)) => Ok(proofs.clone()),
+        Some(proof @ Value::Object(_)) => Ok(vec![proof.clone()]),
+        Some(_) => bail!("proof must be an object or an array of objects"),
+    }
+}
+
+// The document covered by a proof: the unsecured document, together with the
+// proofs it chains to.
+fn chained(unsecured: &Value, proofs: &[Value], proof: &Proof) -> Result<Value> {
+    let previous = proof.previous_proofs();
+    if previous.is_empty() {
+        return Ok(unsecured.clone());
+    }
+
+    let matching = previous
+        .iter()
+        .map(|id| {
+            proofs
+                .iter()
+                .find(|p| p.get("id").and_then(Value::as_str) == Some(id))
+                .cloned()
+                .ok_or_else(|| anyhow!("previous proof {id} is not on the document"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut covered = unsecured.clone();
+    covered["proof"] = Value::Array(matching);
+    Ok(covered)
+}
+
+// Generate the bytes to sign: the hash of the canonicalized proof
+// configuration followed by the hash of the canonicalized document, using
+// the canonicalization algorithm of the proof's cryptosuite.
+fn signing_payload(proof_key: ProofKey, unsecured: &Value, proof: &Proof) -> Result<Vec<u8>> {
+    let config = proof_config(unsecured, proof)?;
+    if proof.cryptosuite.as_deref() == Some(proof_key.rdfc_cryptosuite()) {
+        return proof_key.rdfc_payload(&config, unsecured);
+    }
+    proof_key.jcs_payload(&config, unsecured)
+}
+
+// The proof configuration (or options) signed with the document: the proof
+// without its signature, sharing the document's `@context`.
+fn proof_config(unsecured: &Value, proof: &Proof) -> Result<Value> {
+    let mut config = serde_json::to_value(proof)?;
+    if let Value::Object(map) = &mut config {
+        map.remove("proofValue");
+        map.remove("jws");
+        if let Some(context) = unsecured.get("@context") {
+            map.insert("@context".to_string(), context.clone());
+        }
+    }
+    Ok(config)
+}
@@ -0,0 +1,92 @@
+//! # Legacy Proofs
+//!
+//! Verification of proofs made with the Linked Data Signature suites that
+//! preceded the Data Integrity cryptosuites, so documents from older issuers
+//! can be accepted during a migration:
+//!
+//! - [`Ed25519Signature2020`]: an Ed25519 signature, encoded as a multibase
+//!   `proofValue`.
+//! - [`JsonWebSignature2020`]: a detached JWS with an unencoded payload
+//!   ([RFC 7797]) in the proof's `jws` property, signed using `EdDSA`,
+//!   `ES256` or `ES384`.
+//!
+//! Both suites sign the SHA-256 hash of the URDNA2015 (RDFC-1.0)
+//! canonicalized proof options followed by the hash of the canonicalized
+//! document.
+//!
+//! [`Ed25519Signature2020`]: https://w3c.github.io/vc-di-eddsa/#the-ed25519signature2020-suite
+//! [`JsonWebSignature2020`]: https://w3c-ccg.github.io/lds-jws2020
+//! [RFC 7797]: https://www.rfc-editor.org/rfc/rfc7797
+
+use anyhow::{Result, anyhow, bail};
+use base64ct::{Base64UrlUnpadded, Encoding};
+use credibil_did::{Proof, ProofKey, rdf};
+use multibase::Base;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::proof_config;
+
+/// Proof type for legacy Ed25519 signatures.
+pub(super) const ED25519_SIGNATURE_2020: &str = "Ed25519Signature2020";
+
+/// Proof type for legacy detached JWS signatures.
+pub(super) const JSON_WEB_SIGNATURE_2020: &str = "JsonWebSignature2020";
+
+// Verify an `Ed25519Signature2020` proof using the multikey public key.
+pub(super) fn verify_ed25519_2020(covered: &Value, proof: &Proof, multikey: &str) -> Result<()> {
+    let Some(proof_value) = &proof.proof_value else {
+        bail!("proof value is missing");
+    };
+    let (base, signature) = multibase::decode(proof_value)?;
+    if base != Base::Base58Btc {
+        bail!("unsupported multibase encoding");
+    }
+    ProofKey::Ed25519.verify(multikey, &signed_data(covered, proof)?, &signature)
+}
+
+// Verify a `JsonWebSignature2020` proof using the multikey public key.
+pub(super) fn verify_jws_2020(covered: &Value, proof: &Proof, multikey: &str) -> Result<()> {
+    let Some(jws) = &proof.jws else {
+        bail!("proof jws is missing");
+    };
+    let mut parts = jws.split('.');
+    let (Some(header), Some(""), Some(signature), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        bail!("proof jws is not a detached JWS");
+    };
+
+    let decode = |part: &str| {
+        Base64UrlUnpadded::decode_vec(part).map_err(|e| anyhow!("JWS is not base64url: {e}"))
+    };
+    let protected: Value =
+        serde_json::from_slice(&decode(header)?).map_err(|e| anyhow!("invalid JWS header: {e}"))?;
+
+    // the payload is not base64url-encoded
+    let critical = protected
+        .get("crit")
+        .and_then(Value::as_array)
+        .is_some_and(|crit| crit.iter().any(|param| param == "b64"));
+    if protected.get("b64") != Some(&Value::Bool(false)) || !critical {
+        bail!("proof jws must have an unencoded payload");
+    }
+
+    let proof_key = ProofKey::from_multikey(multikey)?;
+    if protected.get("alg").and_then(Value::as_str) != Some(proof_key.jws_algorithm()) {
+        bail!("JWS algorithm does not match the verification method's key type");
+    }
+
+    let data = signed_data(covered, proof)?;
+    let signing_input = [header.as_bytes(), b".", data.as_slice()].concat();
+    proof_key.verify(multikey, &signing_input, &decode(signature)?)
+}
+
+// The data signed by the legacy suites: the hash of the canonicalized proof
+// options followed by the hash of the canonicalized document.
+fn signed_data(covered: &Value, proof: &Proof) -> Result<Vec<u8>> {
+    let options = proof_config(covered, proof)?;
+    let options_hash = Sha256::digest(rdf::canonicalize(&options)?.as_bytes());
+    let document_hash = Sha256::digest(rdf::canonicalize(covered)?.as_bytes());
+    Ok([options_hash.as_slice(), document_hash.as_slice()].concat())
+}
@@ -5,10 +5,22 @@
 //! serialized to a JSON object.
 
 mod document;
+mod file;
+mod manage;
+mod resolve;
 
 use anyhow::Error;
 
-pub use self::document::{DocumentRequest, DocumentResponse};
+pub use self::document::{Deactivated, DocumentRequest, DocumentResponse};
+pub use self::file::{FileRequest, FileResponse};
+pub use self::manage::{
+    CreateRequest, DeactivateRequest, ManageResponse, Signed, UpdateRequest, WebClaims,
+    WebOperation, document_hash,
+};
+pub use self::resolve::{
+    DID_JSON, DID_LD_JSON, DID_RESOLUTION, ResolutionError, ResolutionMetadata, ResolutionResult,
+    ResolveRequest, ResolveResponse,
+};
 
 /// Result type for Token Status endpoints.
 type Result<T> = anyhow::Result<T, Error>;
@@ -7,7 +7,8 @@ use credibil_did::web::create_did;
 use serde::{Deserialize, Serialize};
 
 use crate::handlers::{Error, Result};
-use crate::provider::Proof;
+use crate::provider::{Proof, Registration, Registry};
+use crate::web::is_deactivated;
 
 /// Used to query the document endpoint in order to return a DID document.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -20,29 +21,64 @@ pub struct DocumentRequest {
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct DocumentResponse(pub Document);
 
+/// Error returned when the requested DID has been deactivated.
+///
+/// Hosts should respond with HTTP 410 (Gone) when the handler's error
+/// downcasts to this type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Deactivated(pub String);
+
+impl std::fmt::Display for Deactivated {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} has been deactivated", self.0)
+    }
+}
+
+impl std::error::Error for Deactivated {}
+
 /// Document request handler.
 ///
+/// The DID is resolved from the request URL's host and path and must be hosted
+/// by `owner`.
+///
 /// # Errors
 ///
 /// Returns an `OpenID4VP` error if the request is invalid or if the provider is
 /// not available.
-async fn document(
-    _owner: &str, proof: &impl Proof, request: DocumentRequest,
+pub(super) async fn document(
+    owner: &str, provider: &(impl Proof + Registry), request: DocumentRequest,
 ) -> Result<DocumentResponse> {
     let url = request.url.trim_end_matches("/did.json").trim_end_matches("/.well-known");
     let did = create_did(url)?;
+    let registration = hosted(owner, &did, provider).await?;
 
-    let document =
-        proof.get(&did, &did).await?.ok_or_else(|| anyhow!("document not found for did: {did}"))?;
+    let document = provider
+        .get(&registration.owner, &did)
+        .await?
+        .ok_or_else(|| anyhow!("document not found for did: {did}"))?;
+    if is_deactivated(&document) {
+        return Err(Deactivated(did).into());
+    }
 
     Ok(DocumentResponse(document))
 }
 
-impl<P: Proof> Handler<DocumentResponse, P> for Request<DocumentRequest> {
+/// Find the registration for the DID (or location key), checking it is hosted
+/// by `owner`.
+pub(super) async fn hosted(
+    owner: &str, location_key: &str, registry: &impl Registry,
+) -> Result<Registration> {
+    match registry.hosted(location_key).await? {
+        Some(registration) if registration.owner == owner => Ok(registration),
+        _ => Err(anyhow!("no DID hosted for {location_key}")),
+    }
+}
+
+impl<P: Proof + Registry> Handler<DocumentResponse, P> for Request<DocumentRequest> {
     type Error = Error;
 
-    async fn handle(self, owner: &str, proof: &P) -> Result<Response<DocumentResponse>> {
-        Ok(document(owner, proof, self.body).await?.into())
+    async fn handle(self, owner: &str, provider: &P) -> Result<Response<DocumentResponse>> {
+        Ok(document(owner, provider, self.body).await?.into())
     }
 }
 
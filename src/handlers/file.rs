@@ -0,0 +1,138 @@
+//! # DID File Endpoint
+//!
+//! Serves the files published at a DID's HTTPS location: the `did:webvh` log
+//! (`did.jsonl`), its witness proofs (`did-witness.json`) and the DID document
+//! (`did.json`).
+
+use anyhow::bail;
+use credibil_core::api::{Body, Handler, Request, Response};
+use credibil_did::Document;
+use credibil_did::webvh::{self, LogEntry, WitnessEntry};
+use serde::{Deserialize, Serialize};
+
+use crate::handlers::document::{self, Deactivated, DocumentRequest, hosted};
+use crate::handlers::{Error, Result};
+use crate::provider::{LogStore, Proof, Registration, Registry};
+use crate::web::is_deactivated;
+
+/// Request for a file published at a DID's HTTPS location.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FileRequest {
+    /// The URL of the requested file. For example,
+    /// `https://example.com/dids/alice/did.jsonl`.
+    pub url: String,
+}
+
+/// The requested file.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FileResponse {
+    /// The `did:webvh` log (`did.jsonl`).
+    Log(Vec<LogEntry>),
+
+    /// The `did:webvh` witness proofs (`did-witness.json`).
+    Witness(Vec<WitnessEntry>),
+
+    /// The DID document (`did.json`). For `did:webvh` DIDs, this is the
+    /// parallel `did:web` document derived from the latest log entry.
+    Document(Document),
+}
+
+impl FileResponse {
+    /// The media type to serve the file with.
+    #[must_use]
+    pub const fn content_type(&self) -> &'static str {
+        match self {
+            Self::Log(_) => "application/jsonl",
+            Self::Witness(_) | Self::Document(_) => "application/json",
+        }
+    }
+
+    /// Serialize the file contents: JSON Lines for the log, JSON otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file contents cannot be serialized.
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        match self {
+            Self::Log(log) => {
+                let mut data = vec![];
+                for entry in log {
+                    serde_json::to_writer(&mut data, entry)?;
+                    data.push(b'\n');
+                }
+                Ok(data)
+            }
+            Self::Witness(witness) => Ok(serde_json::to_vec(witness)?),
+            Self::Document(document) => Ok(serde_json::to_vec(document)?),
+        }
+    }
+}
+
+/// File request handler.
+///
+/// The file name is the last segment of the URL path and the DID's location
+/// is the remainder (less any `/.well-known` segment). The DID is resolved
+/// from the location and must be hosted by `owner`.
+///
+/// # Errors
+///
+/// Returns an error if the file is not one served by the handler, if there is
+/// no DID at the location, or the DID has been deactivated (`did.json` only).
+async fn file(
+    owner: &str, provider: &(impl Proof + LogStore + Registry), request: FileRequest,
+) -> Result<FileResponse> {
+    let path = request.url.split(['?', '#']).next().unwrap_or_default();
+    let Some((base, name)) = path.rsplit_once('/') else {
+        bail!("invalid file URL: {}", request.url);
+    };
+    let file = match name {
+        "did.jsonl" => File::Log,
+        "did-witness.json" => File::Witness,
+        "did.json" => File::Document,
+        _ => bail!("file not found: {name}"),
+    };
+    let base = base.trim_end_matches("/.well-known");
+    let location = webvh::parse_url(base)?;
+    let key = format!("did:webvh:{location}");
+
+    // `did.json` is a `did:web` document when there is no log at the location
+    if file == File::Document && provider.hosted(&key).await?.is_none() {
+        let request = DocumentRequest {
+            url: path.to_string(),
+        };
+        return Ok(FileResponse::Document(document::document(owner, provider, request).await?.0));
+    }
+
+    let Registration { owner, did } = hosted(owner, &key, provider).await?;
+    match file {
+        File::Log => Ok(FileResponse::Log(provider.read(&owner, &did, ..).await?)),
+        File::Witness => Ok(FileResponse::Witness(provider.get_witness(&owner, &did).await?)),
+        File::Document => {
+            let log = provider.read(&owner, &did, ..).await?;
+            let document = webvh::resolve_log(&log, None, None).await?;
+            if is_deactivated(&document) {
+                return Err(Deactivated(did).into());
+            }
+            Ok(FileResponse::Document(webvh::to_web_document(&document)?))
+        }
+    }
+}
+
+// The files served by the handler.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum File {
+    Log,
+    Witness,
+    Document,
+}
+
+impl<P: Proof + LogStore + Registry> Handler<FileResponse, P> for Request<FileRequest> {
+    type Error = Error;
+
+    async fn handle(self, owner: &str, provider: &P) -> Result<Response<FileResponse>> {
+        Ok(file(owner, provider, self.body).await?.into())
+    }
+}
+
+impl Body for FileRequest {}
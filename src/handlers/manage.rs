@@ -0,0 +1,361 @@
+//! # DID Management Endpoint
+//!
+//! Create, update and deactivate hosted DIDs. Requests must be signed by the
+//! DID controller and are verified before the `Provider` is changed:
+//!
+//! - `did:web` requests are a compact JWS signed by one of the DID's
+//!   `authentication` keys. The JWS claims ([`WebClaims`]) are bound to the
+//!   owner the request is made to, are short-lived and, for changes to an
+//!   existing DID, are bound to the current version of its document so they
+//!   cannot be replayed.
+//! - `did:webvh` requests are new log entries secured with Data Integrity
+//!   proofs by the DID's update keys.
+
+use anyhow::{anyhow, bail};
+use chrono::Utc;
+use credibil_core::api::{Body, Handler, Request, Response};
+use credibil_did::web::UpdateBuilder;
+use credibil_did::webvh::{self, LogEntry};
+use credibil_did::{Document, DocumentBuilder, KeyPurpose};
+use multibase::Base;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::LEEWAY;
+use crate::handlers::document::hosted;
+use crate::handlers::{Error, Result};
+use crate::jws::{self, Compact};
+use crate::provider::{LogStore, Proof, Provider, Registration, host_did, location_key};
+use crate::web::{self, is_deactivated};
+
+/// Signed content of a management request.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Signed {
+    /// A compact JWS with [`WebClaims`] as its payload, for `did:web` DIDs.
+    ///
+    /// The JWS `kid` must reference an `authentication` key of the DID: in the
+    /// new document for creation, otherwise in the current document.
+    Jws(String),
+
+    /// New log entries, for `did:webvh` DIDs, each secured with Data Integrity
+    /// proofs by the DID's update keys.
+    Log(Vec<LogEntry>),
+}
+
+/// The claims of a `did:web` management JWS.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WebClaims {
+    /// The owner (tenant) the request is made to.
+    pub aud: String,
+
+    /// The time the request was signed, in seconds since the Unix epoch.
+    pub iat: i64,
+
+    /// The time after which the request must be rejected, in seconds since the
+    /// Unix epoch. At most 5 minutes after `iat`.
+    pub exp: i64,
+
+    /// The [`document_hash`] of the DID's current document, as served by the
+    /// document endpoint. Required to update or deactivate a DID.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prior: Option<String>,
+
+    /// The operation requested.
+    #[serde(flatten)]
+    pub operation: WebOperation,
+}
+
+/// A `did:web` management operation.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "operation", rename_all = "camelCase")]
+pub enum WebOperation {
+    /// Create the DID document.
+    Create {
+        /// The new DID document.
+        document: Document,
+    },
+
+    /// Replace the DID document.
+    Update {
+        /// The updated DID document.
+        document: Document,
+    },
+
+    /// Deactivate the DID.
+    Deactivate {
+        /// The DID to deactivate.
+        did: String,
+    },
+}
+
+/// Request to create a hosted DID.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreateRequest {
+    /// The signed DID document or log.
+    pub signed: Signed,
+}
+
+/// Request to update a hosted DID.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UpdateRequest {
+    /// The DID to update.
+    pub did: String,
+
+    /// The signed DID document or new log entries.
+    pub signed: Signed,
+}
+
+/// Request to deactivate a hosted DID.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeactivateRequest {
+    /// The DID to deactivate.
+    pub did: String,
+
+    /// The signed deactivation or new log entries.
+    pub signed: Signed,
+}
+
+/// Response containing the DID document following the change.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ManageResponse(pub Document);
+
+/// The hash identifying a version of a DID document: the base58btc-encoded
+/// SHA-256 hash of the JCS-canonicalized document, including its metadata.
+///
+/// # Errors
+///
+/// Returns an error if the document cannot be canonicalized.
+pub fn document_hash(document: &Document) -> anyhow::Result<String> {
+    let canonical = serde_json_canonicalizer::to_string(document)?;
+    Ok(multibase::encode(Base::Base58Btc, Sha256::digest(canonical.as_bytes())))
+}
+
+// The longest a signed request is accepted for.
+const MAX_AGE: i64 = 300;
+
+/// Create request handler.
+///
+/// The DID is hosted for `owner`.
+///
+/// # Errors
+///
+/// Returns an error if the request is not signed by the DID controller or if
+/// another DID is hosted at the DID's location.
+async fn create(
+    owner: &str, provider: &impl Provider, request: CreateRequest,
+) -> Result<ManageResponse> {
+    match request.signed {
+        Signed::Jws(jws) => {
+            let compact = jws::decode(&jws)?;
+            let claims: WebClaims = serde_json::from_slice(&compact.payload)?;
+            check_claims(&claims, owner, None)?;
+            let WebOperation::Create { document } = claims.operation else {
+                bail!("JWS is not for a create operation");
+            };
+            if !document.id.starts_with("did:web:") {
+                bail!("signed document is not a `did:web` document");
+            }
+            authenticate(&compact, &document)?;
+
+            let store = Proof::put(provider, owner, &document);
+            host_did(provider, owner, &document.id, store).await?;
+            Ok(ManageResponse(document))
+        }
+        Signed::Log(entries) => {
+            let document = webvh::resolve_log(&entries, None, None).await?;
+            let store = LogStore::append(provider, owner, &document.id, None, &entries);
+            host_did(provider, owner, &document.id, store).await?;
+            Ok(ManageResponse(document))
+        }
+    }
+}
+
+/// Update request handler.
+///
+/// The DID must be hosted by `owner`.
+///
+/// # Errors
+///
+/// Returns an error if the DID is not hosted, has been deactivated, or the
+/// request is not signed by the DID controller.
+async fn update(
+    owner: &str, provider: &impl Provider, request: UpdateRequest,
+) -> Result<ManageResponse> {
+    let Registration { owner, did } = registration(owner, &request.did, provider).await?;
+
+    match request.signed {
+        Signed::Jws(jws) => {
+            let current = current(&owner, &did, provider).await?;
+            let compact = jws::decode(&jws)?;
+            let claims: WebClaims = serde_json::from_slice(&compact.payload)?;
+            check_claims(&claims, &owner, Some(&current))?;
+            let WebOperation::Update { document } = claims.operation else {
+                bail!("JWS is not for an update operation");
+            };
+            if document.id != did {
+                bail!("signed document is not for {did}");
+            }
+            authenticate(&compact, &current)?;
+
+            // metadata is maintained by the host
+            let mut builder = DocumentBuilder::from(document);
+            if let Some(metadata) = current.did_document_metadata {
+                builder = builder.metadata(metadata);
+            }
+            let document = UpdateBuilder::new(builder).build()?;
+            Proof::put(provider, &owner, &document).await?;
+            Ok(ManageResponse(document))
+        }
+        Signed::Log(entries) => {
+            let document = append_log(&owner, &did, &entries, provider).await?;
+            Ok(ManageResponse(document))
+        }
+    }
+}
+
+/// Deactivate request handler.
+///
+/// The DID must be hosted by `owner`.
+///
+/// # Errors
+///
+/// Returns an error if the DID is not hosted, has already been deactivated,
+/// or the request is not signed by the DID controller.
+async fn deactivate(
+    owner: &str, provider: &impl Provider, request: DeactivateRequest,
+) -> Result<ManageResponse> {
+    let Registration { owner, did } = registration(owner, &request.did, provider).await?;
+
+    match request.signed {
+        Signed::Jws(jws) => {
+            let current = current(&owner, &did, provider).await?;
+            let compact = jws::decode(&jws)?;
+            let claims: WebClaims = serde_json::from_slice(&compact.payload)?;
+            check_claims(&claims, &owner, Some(&current))?;
+            let WebOperation::Deactivate { did: signed_did } = claims.operation else {
+                bail!("JWS is not for a deactivate operation");
+            };
+            if signed_did != did {
+                bail!("signed deactivation is not for {did}");
+            }
+            authenticate(&compact, &current)?;
+
+            let document = web::deactivate(&owner, &did, provider).await?;
+            Ok(ManageResponse(document))
+        }
+        Signed::Log(entries) => {
+            let document = append_log(&owner, &did, &entries, provider).await?;
+            if !is_deactivated(&document) {
+                bail!("log entries do not deactivate {did}");
+            }
+            Ok(ManageResponse(document))
+        }
+    }
+}
+
+// Find the registration for a hosted DID.
+async fn registration(
+    owner: &str, did: &str, provider: &impl Provider,
+) -> anyhow::Result<Registration> {
+    let registration = hosted(owner, &location_key(did)?, provider).await?;
+    if registration.did != did {
+        bail!("{did} is not hosted");
+    }
+    Ok(registration)
+}
+
+// Load the current `did:web` document, provided it has not been deactivated.
+async fn current(owner: &str, did: &str, provider: &impl Provider) -> anyhow::Result<Document> {
+    if !did.starts_with("did:web:") {
+        bail!("JWS requests are only supported for `did:web` DIDs");
+    }
+    let document = Proof::get(provider, owner, did)
+        .await?
+        .ok_or_else(|| anyhow!("document not found for did: {did}"))?;
+    if is_deactivated(&document) {
+        bail!("{did} has been deactivated");
+    }
+    Ok(document)
+}
+
+// Check the claims are for `owner`, have not expired and, for an existing
+// DID, are for the `current` version of its document.
+fn check_claims(claims: &WebClaims, owner: &str, current: Option<&Document>) -> anyhow::Result<()> {
+    if claims.aud != owner {
+        bail!("request is not for this host");
+    }
+    let now = Utc::now().timestamp();
+    if claims.iat > now + LEEWAY || claims.exp - claims.iat > MAX_AGE {
+        bail!("request has an invalid lifetime");
+    }
+    if now >= claims.exp {
+        bail!("request has expired");
+    }
+    if let Some(current) = current {
+        if claims.prior.as_deref() != Some(document_hash(current)?.as_str()) {
+            bail!("request is not for the current version of {}", current.id);
+        }
+    }
+    Ok(())
+}
+
+// Verify the JWS was signed by an `authentication` key in the document.
+fn authenticate(compact: &Compact<'_>, document: &Document) -> anyhow::Result<()> {
+    let Some(kid) = &compact.header.kid else {
+        bail!("JWS header has no `kid`");
+    };
+    let Some(vm) = document.authorized_method(kid, &KeyPurpose::Authentication) else {
+        bail!("{kid} is not an authentication key for {}", document.id);
+    };
+    compact.verify(&vm.key.multibase()?)
+}
+
+// Verify new log entries against the stored log and append them. Resolving
+// the log checks each new entry is signed by the update keys in effect before
+// it, so only the DID controller can extend the log.
+async fn append_log(
+    owner: &str, did: &str, entries: &[LogEntry], provider: &impl Provider,
+) -> anyhow::Result<Document> {
+    let log = LogStore::read(provider, owner, did, ..).await?;
+    let Some(tip) = log.last().map(|entry| entry.version_id.clone()) else {
+        bail!("log not found for did: {did}");
+    };
+
+    let mut updated = log;
+    updated.extend_from_slice(entries);
+    let document = webvh::resolve_log(&updated, None, None).await?;
+
+    LogStore::append(provider, owner, did, Some(&tip), entries).await?;
+    Ok(document)
+}
+
+impl<P: Provider> Handler<ManageResponse, P> for Request<CreateRequest> {
+    type Error = Error;
+
+    async fn handle(self, owner: &str, provider: &P) -> Result<Response<ManageResponse>> {
+        Ok(create(owner, provider, self.body).await?.into())
+    }
+}
+
+impl Body for CreateRequest {}
+
+impl<P: Provider> Handler<ManageResponse, P> for Request<UpdateRequest> {
+    type Error = Error;
+
+    async fn handle(self, owner: &str, provider: &P) -> Result<Response<ManageResponse>> {
+        Ok(update(owner, provider, self.body).await?.into())
+    }
+}
+
+impl Body for UpdateRequest {}
+
+impl<P: Provider> Handler<ManageResponse, P> for Request<DeactivateRequest> {
+    type Error = Error;
+
+    async fn handle(self, owner: &str, provider: &P) -> Result<Response<ManageResponse>> {
+        Ok(deactivate(owner, provider, self.body).await?.into())
+    }
+}
+
+impl Body for DeactivateRequest {}
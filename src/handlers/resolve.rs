@@ -0,0 +1,299 @@
+//! # DID Resolution Endpoint
+//!
+//! Implements the [DID Resolution HTTPS binding] (`/1.0/identifiers/{did-url}`)
+//! so the crate can be run as a resolver driver for the DID methods it
+//! supports.
+//!
+//! [DID Resolution HTTPS binding]: https://w3c.github.io/did-resolution/#bindings-https
+
+use std::str::FromStr;
+
+use credibil_core::api::{Body, Handler, Request, Response};
+use credibil_did::{Document, DocumentMetadata, Method, Url};
+use serde::{Deserialize, Serialize};
+
+use crate::handlers::{Error, Result};
+use crate::provider::Resolver;
+use crate::resolve::{Unavailable, resolve_document};
+use crate::web::is_deactivated;
+
+/// Media type of a DID document in JSON.
+pub const DID_JSON: &str = "application/did+json";
+
+/// Media type of a DID document in JSON-LD.
+pub const DID_LD_JSON: &str = "application/did+ld+json";
+
+/// Media type of a DID resolution result.
+pub const DID_RESOLUTION: &str = "application/ld+json;profile=\"https://w3id.org/did-resolution\"";
+
+const RESOLUTION_CONTEXT: &str = "https://w3id.org/did-resolution/v1";
+
+/// Request to resolve a DID URL.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveRequest {
+    /// The (percent-decoded) DID URL to resolve. A leading
+    /// `/1.0/identifiers/` path is removed.
+    pub did_url: String,
+
+    /// The value of the request's `Accept` header, used to select the
+    /// representation returned. Media ranges are tried in order of their
+    /// quality (`q`) values.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accept: Option<String>,
+}
+
+/// The requested representation of the resolved DID.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ResolveResponse {
+    /// The DID document as JSON (`application/did+json`).
+    Json {
+        /// The DID document, without metadata.
+        #[serde(flatten)]
+        document: Document,
+
+        /// Whether the DID has been deactivated. Not serialized.
+        #[serde(skip)]
+        deactivated: bool,
+    },
+
+    /// The DID document as JSON-LD (`application/did+ld+json`).
+    JsonLd {
+        /// The DID document, without metadata.
+        #[serde(flatten)]
+        document: Document,
+
+        /// Whether the DID has been deactivated. Not serialized.
+        #[serde(skip)]
+        deactivated: bool,
+    },
+
+    /// The DID resolution result, including metadata. Always used when
+    /// resolution fails.
+    Result(ResolutionResult),
+}
+
+impl ResolveResponse {
+    /// The media type to serve the response with.
+    #[must_use]
+    pub const fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json { .. } => DID_JSON,
+            Self::JsonLd { .. } => DID_LD_JSON,
+            Self::Result(_) => DID_RESOLUTION,
+        }
+    }
+
+    /// The HTTP status code to serve the response with.
+    #[must_use]
+    pub fn status(&self) -> u16 {
+        match self {
+            Self::Json { deactivated, .. } | Self::JsonLd { deactivated, .. } => {
+                if *deactivated {
+                    410
+                } else {
+                    200
+                }
+            }
+            Self::Result(result) => match &result.did_resolution_metadata.error {
+                Some(ResolutionError::InvalidDid) => 400,
+                Some(ResolutionError::NotFound) => 404,
+                Some(ResolutionError::RepresentationNotSupported) => 406,
+                Some(ResolutionError::MethodNotSupported) => 501,
+                None if result.did_document_metadata.as_ref().and_then(|md| md.deactivated)
+                    == Some(true) =>
+                {
+                    410
+                }
+                None => 200,
+            },
+        }
+    }
+}
+
+/// A DID resolution result.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolutionResult {
+    /// The JSON-LD context of the result.
+    #[serde(rename = "@context")]
+    pub context: String,
+
+    /// The resolved DID document. `None` if resolution failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub did_document: Option<Document>,
+
+    /// Metadata about the resolution process.
+    pub did_resolution_metadata: ResolutionMetadata,
+
+    /// Metadata about the DID document.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub did_document_metadata: Option<DocumentMetadata>,
+}
+
+/// Metadata about the DID resolution process.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolutionMetadata {
+    /// The media type of the returned DID document.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+
+    /// The error that caused resolution to fail.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ResolutionError>,
+
+    /// A description of the error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+}
+
+/// DID resolution errors.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ResolutionError {
+    /// The DID URL is not valid.
+    InvalidDid,
+
+    /// The DID document could not be found.
+    NotFound,
+
+    /// None of the requested representations are supported.
+    RepresentationNotSupported,
+
+    /// The DID method is not supported.
+    MethodNotSupported,
+}
+
+// The representations that can be requested.
+enum Representation {
+    Json,
+    JsonLd,
+    Result,
+}
+
+/// Resolve request handler.
+///
+/// Resolution failures are returned as a resolution result with error
+/// metadata rather than as an error: `notFound` when the DID's document (or
+/// log) cannot be fetched and `invalidDid` when it cannot be parsed or fails
+/// verification.
+async fn resolve(resolver: &impl Resolver, request: ResolveRequest) -> ResolveResponse {
+    let Some(representation) = negotiate(request.accept.as_deref()) else {
+        return failed(ResolutionError::RepresentationNotSupported, "unsupported media type");
+    };
+
+    let did_url = request.did_url.trim_start_matches("/1.0/identifiers/");
+    let url = match Url::from_str(did_url) {
+        Ok(url) => url,
+        Err(e) => {
+            let method = did_url.strip_prefix("did:").and_then(|id| id.split(':').next());
+            if let Some(method) = method.filter(|m| Method::from_str(m).is_err()) {
+                let message = format!("`did:{method}` is not supported");
+                return failed(ResolutionError::MethodNotSupported, message);
+            }
+            return failed(ResolutionError::InvalidDid, e.to_string());
+        }
+    };
+
+    let mut document = match resolve_document(&url, resolver).await {
+        Ok(document) => document,
+        Err(e) if e.is::<Unavailable>() => return failed(ResolutionError::NotFound, e.to_string()),
+        Err(e) => return failed(ResolutionError::InvalidDid, e.to_string()),
+    };
+    let deactivated = is_deactivated(&document);
+
+    match representation {
+        Representation::Json => {
+            document.did_document_metadata = None;
+            ResolveResponse::Json {
+                document,
+                deactivated,
+            }
+        }
+        Representation::JsonLd => {
+            document.did_document_metadata = None;
+            ResolveResponse::JsonLd {
+                document,
+                deactivated,
+            }
+        }
+        Representation::Result => {
+            let did_document_metadata = document.did_document_metadata.take();
+            ResolveResponse::Result(ResolutionResult {
+                context: RESOLUTION_CONTEXT.to_string(),
+                did_document: Some(document),
+                did_resolution_metadata: ResolutionMetadata {
+                    content_type: Some(DID_LD_JSON.to_string()),
+                    ..ResolutionMetadata::default()
+                },
+                did_document_metadata,
+            })
+        }
+    }
+}
+
+// Select the supported representation with the highest quality value from
+// the `Accept` header, preferring earlier media ranges when equal. The
+// resolution result is returned when no preference is given.
+fn negotiate(accept: Option<&str>) -> Option<Representation> {
+    let Some(accept) = accept else {
+        return Some(Representation::Result);
+    };
+
+    let mut media_ranges = vec![];
+    for media_range in accept.split(',') {
+        let mut params = media_range.split(';').map(str::trim);
+        let media_type = params.next().unwrap_or_default();
+        let mut profile = None;
+        let mut quality = 1.0;
+        for param in params {
+            if let Some(value) = param.strip_prefix("profile=") {
+                profile = Some(value.trim_matches('"'));
+            } else if let Some(value) = param.strip_prefix("q=") {
+                quality = value.parse::<f32>().unwrap_or(0.0);
+            }
+        }
+        if quality > 0.0 {
+            media_ranges.push((media_type, profile, quality));
+        }
+    }
+    // stable, so ties keep their order
+    media_ranges.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    for (media_type, profile, _) in media_ranges {
+        match (media_type, profile) {
+            (DID_JSON, _) => return Some(Representation::Json),
+            (DID_LD_JSON, _) => return Some(Representation::JsonLd),
+            ("application/ld+json", Some("https://w3id.org/did-resolution"))
+            | ("*/*" | "application/*", _) => return Some(Representation::Result),
+            _ => {}
+        }
+    }
+    None
+}
+
+// A resolution result for a failed resolution.
+fn failed(error: ResolutionError, message: impl Into<String>) -> ResolveResponse {
+    ResolveResponse::Result(ResolutionResult {
+        context: RESOLUTION_CONTEXT.to_string(),
+        did_document: None,
+        did_resolution_metadata: ResolutionMetadata {
+            content_type: None,
+            error: Some(error),
+            error_message: Some(message.into()),
+        },
+        did_document_metadata: None,
+    })
+}
+
+impl<P: Resolver> Handler<ResolveResponse, P> for Request<ResolveRequest> {
+    type Error = Error;
+
+    async fn handle(self, _owner: &str, resolver: &P) -> Result<Response<ResolveResponse>> {
+        Ok(resolve(resolver, self.body).await.into())
+    }
+}
+
+impl Body for ResolveRequest {}
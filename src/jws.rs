@@ -0,0 +1,134 @@
+//! # JWS
+//!
+//! Decoding and verification of compact JSON Web Signatures (JWS).
+//!
+//! No JWS extensions are supported, so a JWS listing `crit` header parameters
+//! is rejected. The header's `alg` must match the type of the signing key.
+
+use anyhow::{Result, anyhow, bail};
+use base64ct::{Base64UrlUnpadded, Encoding};
+use credibil_did::{KeyPurpose, ProofKey};
+use credibil_jose::{Jwt, PublicKeyJwk, decode_jws};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use crate::provider::{Resolver, VerifyBy};
+use crate::resolve_authorized_jwk;
+
+/// Verify a compact JWS and return its claims.
+///
+/// The signing key is identified by the JWS header: the `kid` DID URL is
+/// resolved using [`resolve_authorized_jwk`] and must be authorized for the
+/// key purpose (its verification relationship). Otherwise the header's
+/// embedded `jwk` is used, for which there is no DID document and so no
+/// purpose to check. The header's `alg` must match the key type.
+///
+/// # Errors
+///
+/// Returns an error if the JWS cannot be decoded, the key cannot be resolved
+/// or is not authorized for the purpose, the algorithm does not match the
+/// key, the signature is invalid, or the claims cannot be deserialized.
+pub async fn verify_jws<T: DeserializeOwned + Send>(
+    compact: &str, resolver: &impl Resolver, purpose: &KeyPurpose,
+) -> Result<T> {
+    let header = header(compact)?;
+    let jwk = match header.verify_by()? {
+        VerifyBy::KeyId(kid) => resolve_authorized_jwk(&kid, purpose, resolver).await?,
+        VerifyBy::Jwk(jwk) => jwk,
+    };
+
+    let proof_key = ProofKey::from_multikey(&ProofKey::jwk_multikey(&jwk)?)?;
+    if header.alg != proof_key.jws_algorithm() {
+        bail!("JWS algorithm {} does not match the key type", header.alg);
+    }
+
+    let jwt: Jwt<T> = decode_jws(compact, |_| {
+        let jwk = jwk.clone();
+        async move { Ok(jwk) }
+    })
+    .await?;
+    Ok(jwt.claims)
+}
+
+/// The protected header of a JWS.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct Header {
+    /// The signing algorithm.
+    pub alg: String,
+
+    /// The ID (a DID URL) of the key used to sign the JWS.
+    pub kid: Option<String>,
+
+    /// The public key used to sign the JWS.
+    pub jwk: Option<PublicKeyJwk>,
+
+    /// Extension header parameters the recipient must understand.
+    pub crit: Option<Vec<String>>,
+}
+
+/// A decoded (but unverified) compact JWS.
+pub(crate) struct Compact<'a> {
+    pub header: Header,
+    pub payload: Vec<u8>,
+    signing_input: &'a str,
+    signature: Vec<u8>,
+}
+
+/// Decode a compact JWS without verifying the signature.
+pub(crate) fn decode(compact: &str) -> Result<Compact<'_>> {
+    let mut parts = compact.split('.');
+    let (Some(header), Some(payload), Some(signature), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        bail!("JWS is not in compact serialization format");
+    };
+
+    Ok(Compact {
+        header: self::header(compact)?,
+        payload: decode_part(payload)?,
+        signing_input: &compact[..header.len() + 1 + payload.len()],
+        signature: decode_part(signature)?,
+    })
+}
+
+impl Header {
+    /// The key used to sign the JWS, preferring the `kid` to an embedded
+    /// `jwk`.
+    pub(crate) fn verify_by(&self) -> Result<VerifyBy> {
+        match (&self.kid, &self.jwk) {
+            (Some(kid), _) => Ok(VerifyBy::KeyId(kid.clone())),
+            (None, Some(jwk)) => Ok(VerifyBy::Jwk(jwk.clone())),
+            (None, None) => bail!("JWS header has no `kid` or `jwk`"),
+        }
+    }
+}
+
+impl Compact<'_> {
+    /// Verify the JWS signature using the provided (multibase-encoded) key,
+    /// checking the header's `alg` matches the key type.
+    pub(crate) fn verify(&self, multikey: &str) -> Result<()> {
+        let proof_key = ProofKey::from_multikey(multikey)?;
+        if self.header.alg != proof_key.jws_algorithm() {
+            bail!("JWS algorithm {} does not match the key type", self.header.alg);
+        }
+        proof_key.verify(multikey, self.signing_input.as_bytes(), &self.signature)
+    }
+}
+
+// Decode the protected header of a compact JWS, rejecting any critical
+// header parameters.
+fn header(compact: &str) -> Result<Header> {
+    let Some((header, _)) = compact.split_once('.') else {
+        bail!("JWS is not in compact serialization format");
+    };
+    let header: Header = serde_json::from_slice(&decode_part(header)?)
+        .map_err(|e| anyhow!("invalid JWS header: {e}"))?;
+    if let Some(name) = header.crit.iter().flatten().next() {
+        bail!("unsupported critical JWS header parameter: {name}");
+    }
+    Ok(header)
+}
+
+fn decode_part(part: &str) -> Result<Vec<u8>> {
+    Base64UrlUnpadded::decode_vec(part).map_err(|e| anyhow!("JWS is not base64url: {e}"))
+}
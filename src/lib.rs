@@ -1,56 +1,64 @@
 //! # Proof
 
-mod create;
+mod cache;
+mod data_integrity;
 mod handlers;
+mod jws;
 mod provider;
-
-use std::str::FromStr;
+mod resolve;
+mod verify;
+mod web;
+mod webvh;
 
 use anyhow::{Result, anyhow};
 pub use credibil_core::api::Client;
-use credibil_did::{Method, Resource};
+use credibil_did::KeyPurpose;
 use credibil_jose::PublicKeyJwk;
 pub use {credibil_did as did, credibil_ecc as ecc, credibil_jose as jose};
 
-pub use self::create::*;
+pub use self::cache::CachingResolver;
+pub use self::data_integrity::*;
 pub use self::handlers::*;
+pub use self::jws::verify_jws;
 pub use self::provider::*;
+use self::resolve::authorized_method;
+pub use self::resolve::{Unavailable, resolve_document, sync_log};
+pub use self::verify::*;
+pub use self::web::*;
+pub use self::webvh::*;
+
+// Allowance, in seconds, for clock skew between a signer and a verifier.
+const LEEWAY: i64 = 60;
 
 /// Retrieve the JWK specified by the provided DID URL.
 ///
 /// # Errors
 ///
-/// TODO: Document errors
+/// Returns an error if the URL is not a DID URL or does not identify a
+/// verification method in the resolved DID document.
 pub async fn resolve_jwk<'a>(
     url: impl Into<UrlType<'a>>, resolver: &impl Resolver,
 ) -> Result<PublicKeyJwk> {
-    let jwk = match url.into() {
-        UrlType::Did(url) => {
-            let did_url = credibil_did::Url::from_str(url)?;
-
-            let resource = match did_url.method {
-                Method::Key => credibil_did::key::resolve(&did_url)?,
-                Method::Web => {
-                    let web_url = did_url.to_web_http();
-                    let body = resolver.resolve(&web_url).await?;
-                    let doc = serde_json::from_slice(&body)
-                        .map_err(|e| anyhow!("failed to deserialize DID document: {e}"))?;
-                    credibil_did::resource(&did_url, &doc)?
-                }
-                Method::WebVh => unimplemented!(),
-            };
+    resolve_authorized_jwk(url, &KeyPurpose::VerificationMethod, resolver).await
+}
 
-            let Resource::VerificationMethod(vm) = resource else {
-                return Err(anyhow!("ProofType method not found"));
-            };
-            vm.key.jwk()?
-        }
-        UrlType::Url(_) => {
-            return Err(anyhow!("Only DID URLs are supported for JWK resolution"));
-        }
+/// Retrieve the JWK specified by the provided DID URL, provided its
+/// verification method is authorized for `purpose` (its verification
+/// relationship).
+///
+/// `did:key` keys are authorized for every purpose except key agreement.
+///
+/// # Errors
+///
+/// Returns an error if the URL is not a DID URL or does not identify a
+/// verification method authorized for `purpose`.
+pub async fn resolve_authorized_jwk<'a>(
+    url: impl Into<UrlType<'a>>, purpose: &KeyPurpose, resolver: &impl Resolver,
+) -> Result<PublicKeyJwk> {
+    let UrlType::Did(url) = url.into() else {
+        return Err(anyhow!("Only DID URLs are supported for JWK resolution"));
     };
-
-    Ok(jwk)
+    authorized_method(url, purpose, resolver).await?.key.jwk()
 }
 
 /// Represents a URL type that can either be a DID or a regular URL.
@@ -1,18 +1,27 @@
 //! # `Proof`
 
-use anyhow::{Result, anyhow};
+use std::collections::BTreeMap;
+use std::ops::RangeBounds;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use anyhow::{Result, anyhow, bail};
+use credibil_core::Kind;
 use credibil_core::datastore::Datastore;
-use credibil_did::Document;
-use credibil_ecc::{Entry, Signer};
+use credibil_did::webvh::{LogEntry, WitnessEntry};
+use credibil_did::{Document, KeyFormat, KeyPurpose, ProofKey, Url};
+use credibil_ecc::{Algorithm, Entry, PublicKey, Signer};
 use credibil_jose::{KeyBinding, PublicKeyJwk};
 use serde::{Deserialize, Serialize};
 
+use crate::resolve::{fetch_document, resolve_document};
+
 /// DID Provider trait.
-pub trait Provider: Proof + Clone {}
+pub trait Provider: Proof + LogStore + Registry + Clone {}
 
 /// A blanket implementation for `Provider` trait so that any type implementing
 /// the required super traits is considered a `Provider`.
-impl<T> Provider for T where T: Proof + Clone {}
+impl<T> Provider for T where T: Proof + LogStore + Registry + Clone {}
 
 /// [`Signature`] is used to provide public key material that can be used for
 /// signature verification.
@@ -36,6 +45,106 @@ impl Signature for Entry {
     }
 }
 
+/// A [`Signature`] that binds a keyring entry to a verification method in
+/// the signer's DID document.
+///
+/// Unlike the `Entry` implementation, which provides the raw public key,
+/// signatures are verified by resolving the DID URL of the verification
+/// method (returned as [`VerifyBy::KeyId`]).
+pub struct DidSigner {
+    entry: Entry,
+    key_id: String,
+}
+
+impl DidSigner {
+    /// Bind the entry to the verification method in the DID's document that
+    /// holds the entry's key and is authorized for `purpose` (its
+    /// verification relationship).
+    ///
+    /// The DID is resolved, so the key must be published in the DID's current
+    /// document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entry's key cannot be retrieved or encoded, if
+    /// the DID cannot be resolved, or if no verification method authorized
+    /// for `purpose` holds the key.
+    pub async fn new(
+        entry: Entry, did: &str, purpose: &KeyPurpose, resolver: &impl Resolver,
+    ) -> Result<Self> {
+        let proof_key = ProofKey::from_algorithm(&entry.algorithm().await?)?;
+        let multikey = proof_key.multikey(&entry.verifying_key().await?.to_bytes())?;
+        let document = resolve_document(&Url::from_str(did)?, resolver).await?;
+
+        // methods are either listed in `verificationMethod` (and referenced
+        // by the relationship) or embedded in a relationship
+        let relationships = [
+            &document.authentication,
+            &document.assertion_method,
+            &document.key_agreement,
+            &document.capability_invocation,
+            &document.capability_delegation,
+        ];
+        let embedded = relationships
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|kind| if let Kind::Object(vm) = kind { Some(&vm.id) } else { None });
+
+        let key_id = document
+            .verification_method
+            .iter()
+            .flatten()
+            .map(|vm| &vm.id)
+            .chain(embedded)
+            .find(|id| {
+                document.authorized_method(id, purpose).is_some_and(|vm| {
+                    let key = match &vm.key {
+                        KeyFormat::JsonWebKey { public_key_jwk } => {
+                            ProofKey::jwk_multikey(public_key_jwk)
+                        }
+                        KeyFormat::Multikey { public_key_multibase } => {
+                            Ok(public_key_multibase.clone())
+                        }
+                    };
+                    key.is_ok_and(|key| key == multikey)
+                })
+            })
+            .ok_or_else(|| {
+                anyhow!("{} has no {purpose:?} verification method for the key", document.id)
+            })?
+            .clone();
+
+        Ok(Self { entry, key_id })
+    }
+
+    /// The DID URL of the verification method used to verify signatures.
+    #[must_use]
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+}
+
+impl Signer for DidSigner {
+    async fn try_sign(&self, msg: &[u8]) -> Result<Vec<u8>> {
+        self.entry.try_sign(msg).await
+    }
+
+    async fn verifying_key(&self) -> Result<PublicKey> {
+        self.entry.verifying_key().await
+    }
+
+    async fn algorithm(&self) -> Result<Algorithm> {
+        self.entry.algorithm().await
+    }
+}
+
+impl Signature for DidSigner {
+    async fn verification_method(&self) -> Result<VerifyBy> {
+        Ok(VerifyBy::KeyId(self.key_id.clone()))
+    }
+}
+
 /// [`ProofResolver`] is used to proxy the resolution of a a proof.
 ///
 /// Implementers need only return the identity specified by the url. This
@@ -58,6 +167,47 @@ pub trait Resolver: Send + Sync {
     ///
     /// Returns an error if the URL cannot be resolved.
     fn resolve(&self, url: &str) -> impl Future<Output = Result<Vec<u8>>> + Send;
+
+    /// Resolve a DID URL to a DID document.
+    ///
+    /// The default implementation fetches the published document (or log)
+    /// using [`Resolver::resolve`] and verifies it on every call. Override to
+    /// add caching (see [`CachingResolver`](crate::CachingResolver)).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the DID document cannot be resolved.
+    fn document(&self, did_url: &Url) -> impl Future<Output = Result<Document>> + Send
+    where
+        Self: Sized,
+    {
+        fetch_document(did_url, self)
+    }
+}
+
+/// [`LogResolver`] extends [`Resolver`] with the ability to fetch part of a
+/// `did:webvh` log.
+///
+/// Used to synchronise a cached, verified log by fetching only the entries
+/// added since it was verified (see [`sync_log`](crate::sync_log)).
+pub trait LogResolver: Resolver {
+    /// Fetch the lines of the `did.jsonl` log at `url`, starting with the
+    /// entry with version number `from` (inclusive).
+    ///
+    /// Implementers may use an HTTP range request, a query parameter supported
+    /// by the host, or a local copy of the log. Returning earlier entries (or
+    /// the entire log) is permitted; they will be ignored.
+    ///
+    /// The default implementation fetches the entire log using
+    /// [`Resolver::resolve`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log cannot be fetched.
+    fn resolve_from(&self, url: &str, from: u64) -> impl Future<Output = Result<Vec<u8>>> + Send {
+        let _ = from;
+        self.resolve(url)
+    }
 }
 
 /// Sources of public key material supported.
@@ -142,3 +292,329 @@ impl<T: Datastore> Proof for T {
             .collect::<Result<Vec<_>>>()
     }
 }
+
+/// `LogStore` is used by implementers to persist `did:webvh` logs
+/// (`did.jsonl`) and witness proofs (`did-witness.json`).
+///
+/// Logs are append-only: new entries are only added if the log's latest entry
+/// (its tip) is unchanged since it was read.
+///
+/// The implementation for [`Datastore`] types stores each log as a single
+/// item, rewritten on every append, and makes the compare-and-append atomic
+/// using a lock held per DID within this process. `Datastore` has no
+/// conditional write, so stores shared between processes must implement
+/// `LogStore` using the store's own compare-and-swap (for example, a
+/// conditional put on the tip's version ID).
+pub trait LogStore: Send + Sync {
+    /// Append entries to the DID's log.
+    ///
+    /// The append only succeeds if the version ID of the log's latest entry
+    /// matches `tip`. Use `None` to create a new log. Entries must continue
+    /// the log's version sequence: each entry's version number follows the
+    /// previous entry's and its version ID is chained to the previous
+    /// version ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tip has changed (or the log already exists when
+    /// `tip` is `None`), if the entries do not continue the log's version
+    /// sequence, or if the entries cannot be stored.
+    fn append(
+        &self, owner: &str, did: &str, tip: Option<&str>, entries: &[LogEntry],
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Fetch the log entries with version numbers in the specified range. Use
+    /// `..` to fetch the entire log.
+    ///
+    /// Returns an empty list if there is no log for the DID.
+    fn read(
+        &self, owner: &str, did: &str, versions: impl RangeBounds<u64> + Send,
+    ) -> impl Future<Output = Result<Vec<LogEntry>>> + Send;
+
+    /// Store (replacing) the witness proofs for the DID's log.
+    fn put_witness(
+        &self, owner: &str, did: &str, witness: &[WitnessEntry],
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Fetch the witness proofs for the DID's log.
+    ///
+    /// Returns an empty list if there are no witness proofs for the DID.
+    fn get_witness(
+        &self, owner: &str, did: &str,
+    ) -> impl Future<Output = Result<Vec<WitnessEntry>>> + Send;
+}
+
+const LOG: &str = "log";
+const WITNESS: &str = "witness";
+
+// Serializes appends to each log so the compare-and-append is atomic within
+// this process.
+static APPEND_LOCKS: KeyedLocks = KeyedLocks::new();
+
+impl<T: Datastore> LogStore for T {
+    async fn append(
+        &self, owner: &str, did: &str, tip: Option<&str>, entries: &[LogEntry],
+    ) -> Result<()> {
+        if entries.is_empty() {
+            bail!("no log entries to append");
+        }
+
+        let _guard = APPEND_LOCKS.lock(format!("{owner}/{did}")).await;
+        let mut log = read_log(self, owner, did).await?;
+        let current = log.last().map(|e| e.version_id.as_str());
+        if current != tip {
+            bail!("log tip for {did} has changed: expected {tip:?}, found {current:?}");
+        }
+        check_sequence(log.last(), entries)?;
+
+        log.extend_from_slice(entries);
+        let mut data = vec![];
+        for entry in &log {
+            serde_json::to_writer(&mut data, entry)?;
+            data.push(b'\n');
+        }
+        Datastore::put(self, owner, LOG, did, &data).await
+    }
+
+    async fn read(
+        &self, owner: &str, did: &str, versions: impl RangeBounds<u64> + Send,
+    ) -> Result<Vec<LogEntry>> {
+        let log = read_log(self, owner, did).await?;
+        let mut entries = vec![];
+        for entry in log {
+            if versions.contains(&version_number(&entry)?) {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    async fn put_witness(&self, owner: &str, did: &str, witness: &[WitnessEntry]) -> Result<()> {
+        let data = serde_json::to_vec(witness)?;
+        Datastore::put(self, owner, WITNESS, did, &data).await
+    }
+
+    async fn get_witness(&self, owner: &str, did: &str) -> Result<Vec<WitnessEntry>> {
+        let Some(data) = Datastore::get(self, owner, WITNESS, did).await? else {
+            return Ok(vec![]);
+        };
+        Ok(serde_json::from_slice(&data)?)
+    }
+}
+
+// Check the entries continue the log from `tip`: version numbers increment by
+// one and each version ID is chained to the previous one.
+fn check_sequence(tip: Option<&LogEntry>, entries: &[LogEntry]) -> Result<()> {
+    let (mut number, mut previous) = match tip {
+        Some(tip) => (version_number(tip)?, tip.version_id.clone()),
+        None => (0, entries[0].parameters.scid.clone()),
+    };
+    for entry in entries {
+        number += 1;
+        if version_number(entry)? != number {
+            bail!("log entry {} is out of sequence: expected version {number}", entry.version_id);
+        }
+        entry.verify_hash(&previous)?;
+        previous.clone_from(&entry.version_id);
+    }
+    Ok(())
+}
+
+fn version_number(entry: &LogEntry) -> Result<u64> {
+    let Some((number, _)) = entry.version_id.split_once('-') else {
+        bail!("log entry version id has an unexpected format");
+    };
+    Ok(number.parse::<u64>()?)
+}
+
+// Read the entire log stored for the DID.
+async fn read_log(store: &impl Datastore, owner: &str, did: &str) -> Result<Vec<LogEntry>> {
+    let Some(data) = Datastore::get(store, owner, LOG, did).await? else {
+        return Ok(vec![]);
+    };
+    data.split(|b| *b == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| Ok(serde_json::from_slice(line)?))
+        .collect()
+}
+
+/// `Registry` is used by implementers to record which owner (tenant) hosts
+/// each DID.
+///
+/// DIDs are indexed by owner, for listing, and by location, so the owner and
+/// DID can be resolved from the host and path of a request.
+///
+/// The implementation for [`Datastore`] types checks a location is free using
+/// a lock held per location within this process. Stores shared between
+/// processes must implement `Registry` using the store's own conditional
+/// write.
+pub trait Registry: Send + Sync {
+    /// Record that `owner` hosts `did`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `owner` is empty, if a DID is already hosted at the
+    /// DID's location or if the registration cannot be stored.
+    fn register(&self, owner: &str, did: &str) -> impl Future<Output = Result<()>> + Send;
+
+    /// Remove the record that `owner` hosts `did`, freeing its location.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `did` is not hosted by `owner` or if the
+    /// registration cannot be removed.
+    fn unregister(&self, owner: &str, did: &str) -> impl Future<Output = Result<()>> + Send;
+
+    /// Find the registration for a location key (see [`location_key`]).
+    ///
+    /// Returns `None` if no DID is hosted at the location.
+    fn hosted(
+        &self, location_key: &str,
+    ) -> impl Future<Output = Result<Option<Registration>>> + Send;
+
+    /// List the DIDs hosted for `owner`.
+    fn dids(&self, owner: &str) -> impl Future<Output = Result<Vec<String>>> + Send;
+}
+
+/// The owner hosting a DID.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Registration {
+    /// The owner (tenant) hosting the DID.
+    pub owner: String,
+
+    /// The hosted DID.
+    pub did: String,
+}
+
+/// The key used to index a DID by location: the DID less any `did:webvh` SCID.
+///
+/// For example, `did:webvh:{SCID}:example.com:alice` is indexed as
+/// `did:webvh:example.com:alice` and `did:web:example.com:alice` as itself.
+///
+/// # Errors
+///
+/// Returns an error if the DID is not a valid `did:webvh` DID.
+pub fn location_key(did: &str) -> Result<String> {
+    let Some(scid_and_location) = did.strip_prefix("did:webvh:") else {
+        return Ok(did.to_string());
+    };
+    let Some((_, location)) = scid_and_location.split_once(':') else {
+        bail!("DID is not a valid did:webvh - no SCID");
+    };
+    Ok(format!("did:webvh:{location}"))
+}
+
+const DIDS: &str = "dids";
+
+// Location index entries belong to no owner, so are stored using the location
+// key in place of the owner.
+const LOCATION: &str = "location";
+
+// Serializes registrations for each location so checking the location is free
+// is atomic within this process.
+static REGISTER_LOCKS: KeyedLocks = KeyedLocks::new();
+
+impl<T: Datastore> Registry for T {
+    async fn register(&self, owner: &str, did: &str) -> Result<()> {
+        if owner.is_empty() {
+            bail!("owner is required to register {did}");
+        }
+        let key = location_key(did)?;
+
+        let _guard = REGISTER_LOCKS.lock(key.clone()).await;
+        if self.hosted(&key).await?.is_some() {
+            bail!("a DID is already hosted at the location of {did}");
+        }
+
+        let registration = Registration {
+            owner: owner.to_string(),
+            did: did.to_string(),
+        };
+        let data = serde_json::to_vec(&registration)?;
+        Datastore::put(self, &key, LOCATION, &key, &data).await?;
+        Datastore::put(self, owner, DIDS, did, did.as_bytes()).await
+    }
+
+    async fn unregister(&self, owner: &str, did: &str) -> Result<()> {
+        let key = location_key(did)?;
+
+        let _guard = REGISTER_LOCKS.lock(key.clone()).await;
+        match self.hosted(&key).await? {
+            Some(existing) if existing.owner == owner && existing.did == did => {}
+            _ => bail!("{did} is not hosted for {owner}"),
+        }
+        Datastore::delete(self, owner, DIDS, did).await?;
+        Datastore::delete(self, &key, LOCATION, &key).await
+    }
+
+    async fn hosted(&self, location_key: &str) -> Result<Option<Registration>> {
+        let Some(data) = Datastore::get(self, location_key, LOCATION, location_key).await? else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_slice(&data)?))
+    }
+
+    async fn dids(&self, owner: &str) -> Result<Vec<String>> {
+        Datastore::get_all(self, owner, DIDS)
+            .await?
+            .into_iter()
+            .map(|(_, data)| Ok(String::from_utf8(data)?))
+            .collect()
+    }
+}
+
+// Register `owner` as hosting `did` then store the DID, removing the
+// registration if the DID cannot be stored.
+pub(crate) async fn host_did(
+    registry: &impl Registry, owner: &str, did: &str, store: impl Future<Output = Result<()>>,
+) -> Result<()> {
+    registry.register(owner, did).await?;
+    if let Err(e) = store.await {
+        registry.unregister(owner, did).await?;
+        return Err(e);
+    }
+    Ok(())
+}
+
+// Async locks keyed by name, held within this process. A key's lock is
+// removed once no task holds or is waiting for it.
+struct KeyedLocks(Mutex<BTreeMap<String, Arc<tokio::sync::Mutex<()>>>>);
+
+impl KeyedLocks {
+    const fn new() -> Self {
+        Self(Mutex::new(BTreeMap::new()))
+    }
+
+    async fn lock(&'static self, key: String) -> KeyGuard {
+        let lock = {
+            let mut locks = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+            Arc::clone(locks.entry(key.clone()).or_default())
+        };
+        KeyGuard {
+            guard: Some(lock.lock_owned().await),
+            locks: self,
+            key,
+        }
+    }
+}
+
+struct KeyGuard {
+    guard: Option<tokio::sync::OwnedMutexGuard<()>>,
+    locks: &'static KeyedLocks,
+    key: String,
+}
+
+impl Drop for KeyGuard {
+    fn drop(&mut self) {
+        let mut locks = self.locks.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(guard) = self.guard.take() else {
+            return;
+        };
+        let lock = Arc::clone(tokio::sync::OwnedMutexGuard::mutex(&guard));
+        drop(guard);
+        // one reference held by the map and one by `lock`
+        if Arc::strong_count(&lock) <= 2 {
+            locks.remove(&self.key);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! # Resolve
+//!
+//! Resolution of DID documents using a [`Resolver`] to fetch the method's
+//! published artifacts (a `did.json` document or a `did.jsonl` log).
+
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+use credibil_did::webvh::{self, Checkpoint, LogEntry};
+use credibil_did::{Document, KeyPurpose, Method, Url, VerificationMethod};
+
+use crate::provider::{LogResolver, Resolver};
+
+/// Error returned when a DID's published document (or log) cannot be fetched.
+///
+/// Other resolution errors are caused by an invalid DID, or by a document (or
+/// log) that cannot be parsed or fails verification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Unavailable(pub String);
+
+impl std::fmt::Display for Unavailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for Unavailable {}
+
+/// Resolve the DID document for the provided DID URL.
+///
+/// For `did:webvh`, the DID log is fetched and verified and any `versionId`
+/// or `versionTime` query parameters are used to select the document version.
+/// `did:key` documents are derived from the DID.
+///
+/// # Errors
+///
+/// Returns an [`Unavailable`] error if the published document or log cannot
+/// be fetched. Returns other errors if the document or log cannot be parsed
+/// or the log fails verification.
+pub async fn resolve_document(did_url: &Url, resolver: &impl Resolver) -> Result<Document> {
+    resolver.document(did_url).await
+}
+
+/// Resolve the verification method identified by the DID URL, provided it is
+/// authorized for `purpose` (its verification relationship).
+///
+/// `did:key` methods are authorized for every purpose except key agreement.
+pub(crate) async fn authorized_method(
+    did_url: &str, purpose: &KeyPurpose, resolver: &impl Resolver,
+) -> Result<VerificationMethod> {
+    let document = resolve_document(&Url::from_str(did_url)?, resolver).await?;
+    document
+        .authorized_method(did_url, purpose)
+        .cloned()
+        .ok_or_else(|| anyhow!("{did_url} is not authorized for {purpose:?}"))
+}
+
+/// Fetch and verify the DID document for the provided DID URL without
+/// caching.
+pub(crate) async fn fetch_document(did_url: &Url, resolver: &impl Resolver) -> Result<Document> {
+    match did_url.method {
+        Method::Key => credibil_did::key::document(did_url),
+        Method::Web => {
+            let body = fetch(&did_url.to_web_http(), resolver).await?;
+            serde_json::from_slice(&body)
+                .map_err(|e| anyhow!("failed to deserialize DID document: {e}"))
+        }
+        Method::WebVh => {
+            let log = fetch_log(did_url, resolver).await?;
+            webvh::resolve_log(&log, None, did_url.query.as_ref()).await
+        }
+    }
+}
+
+/// Synchronise a verified `did:webvh` log, fetching only the entries added
+/// since the checkpoint was taken.
+///
+/// When no checkpoint is provided, the entire log is fetched and verified.
+/// Otherwise, entries from the checkpoint's version onward are fetched and the
+/// log is confirmed to still contain the checkpoint's (verified) version
+/// before new entries are verified.
+///
+/// # Errors
+///
+/// Returns an error if the log cannot be fetched or new entries fail
+/// verification. Fails loudly if the log no longer contains the checkpoint's
+/// version, which indicates the log has been truncated or rewritten. The
+/// checkpoint should not be discarded and the DID re-trusted without
+/// investigation.
+pub async fn sync_log(
+    did_url: &Url, checkpoint: Option<&Checkpoint>, resolver: &impl LogResolver,
+) -> Result<Checkpoint> {
+    let log_url = Url {
+        path: None,
+        ..did_url.clone()
+    };
+    let http_url = log_url.to_webvh_http()?;
+
+    let Some(checkpoint) = checkpoint else {
+        let log = parse_log(&resolver.resolve(&http_url).await?)?;
+        return Checkpoint::verify(&log, None).await;
+    };
+
+    let body = resolver.resolve_from(&http_url, checkpoint.version_number).await?;
+    let entries = parse_log(&body)?;
+    checkpoint.extend(&entries, None).await.map_err(|e| anyhow!("{log_url}: {e}"))
+}
+
+/// Fetch and parse the `did.jsonl` log for a `did:webvh` DID URL.
+///
+/// The log is not verified.
+pub(crate) async fn fetch_log(did_url: &Url, resolver: &impl Resolver) -> Result<Vec<LogEntry>> {
+    let log_url = Url {
+        path: None,
+        ..did_url.clone()
+    };
+    let body = fetch(&log_url.to_webvh_http()?, resolver).await?;
+    parse_log(&body)
+}
+
+/// Fetch a published document (or log), returning an [`Unavailable`] error if
+/// it cannot be fetched.
+pub(crate) async fn fetch(url: &str, resolver: &impl Resolver) -> Result<Vec<u8>> {
+    resolver.resolve(url).await.map_err(|e| Unavailable(format!("{url}: {e}")).into())
+}
+
+/// Parse the contents of a `did.jsonl` file into log entries.
+pub(crate) fn parse_log(body: &[u8]) -> Result<Vec<LogEntry>> {
+    let text = std::str::from_utf8(body).map_err(|e| anyhow!("DID log is not UTF-8: {e}"))?;
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).map_err(|e| anyhow!("failed to deserialize log entry: {e}"))
+        })
+        .collect()
+}
@@ -0,0 +1,177 @@
+//! # Verify
+//!
+//! Historical key validity checks. Determines whether the key referenced by a
+//! DID URL was valid at the time a signature was made and whether it has been
+//! removed from the DID document since.
+
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use credibil_did::webvh::{self, LogEntry};
+use credibil_did::{Document, KeyFormat, KeyPurpose, Method, Resource, Url};
+use credibil_jose::PublicKeyJwk;
+use serde::{Deserialize, Serialize};
+
+use crate::provider::Resolver;
+use crate::resolve::{fetch_log, resolve_document};
+
+/// The validity of a verification key at signing time and since.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyValidity {
+    /// The key material from the DID document in force at signing time. This
+    /// is `None` when the key was not in the document at that time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jwk: Option<PublicKeyJwk>,
+
+    /// Whether the key was present in the DID document in force at signing
+    /// time.
+    pub valid_at_signing: bool,
+
+    /// The version of the DID document in force at signing time. Only
+    /// available for DID methods with a verifiable history (`did:webvh`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
+
+    /// Details of the first version of the DID document (after signing) that
+    /// no longer contains the key. This is `None` when the key has not been
+    /// removed or the DID method has no verifiable history.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub removed: Option<KeyRemoval>,
+}
+
+impl KeyValidity {
+    /// Whether the key has been removed from the DID document since signing.
+    #[must_use]
+    pub const fn removed_since(&self) -> bool {
+        self.removed.is_some()
+    }
+}
+
+/// Details of the DID document version that removed a key.
+///
+/// Removal does not say why the key was removed. A key rotated out as part
+/// of routine key management and a key removed following a compromise look
+/// the same in the DID log, so verifiers should apply their own policy: for
+/// example, rejecting signatures made shortly before removal.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRemoval {
+    /// The version ID of the DID document that removed the key.
+    pub version_id: String,
+
+    /// The time the key was removed.
+    pub version_time: DateTime<Utc>,
+
+    /// Whether the key was removed by deactivating the DID.
+    pub deactivated: bool,
+}
+
+/// Check the validity of the key specified by the DID URL at the time a
+/// signature was made.
+///
+/// For `did:webvh`, the DID log is verified and the document version in force
+/// at `signed_at` is resolved using the `versionTime` query parameter.
+/// Subsequent versions are checked to determine whether the key has since been
+/// removed (or replaced by different key material).
+///
+/// Other DID methods have no verifiable history, so the current document is
+/// used to determine validity at signing time and removal is not reported.
+///
+/// # Errors
+///
+/// Returns an error if the URL is not a DID URL, or if the DID document (or
+/// log) cannot be resolved.
+pub async fn verify_at(
+    url: &str, signed_at: DateTime<Utc>, resolver: &impl Resolver,
+) -> Result<KeyValidity> {
+    let mut did_url = Url::from_str(url)?;
+    did_url.query = None;
+
+    match did_url.method {
+        Method::Key => {
+            let jwk = resource_jwk(&did_url, &credibil_did::key::resolve(&did_url)?)?;
+            Ok(KeyValidity {
+                valid_at_signing: jwk.is_some(),
+                jwk,
+                ..KeyValidity::default()
+            })
+        }
+        Method::Web => {
+            let doc = resolve_document(&did_url, resolver).await?;
+            let jwk = resource_jwk(&did_url, &Resource::Document(doc))?;
+            Ok(KeyValidity {
+                valid_at_signing: jwk.is_some(),
+                jwk,
+                ..KeyValidity::default()
+            })
+        }
+        Method::WebVh => {
+            let log = fetch_log(&did_url, resolver).await?;
+            webvh_validity(&did_url, &log, signed_at).await
+        }
+    }
+}
+
+async fn webvh_validity(
+    did_url: &Url, log: &[LogEntry], signed_at: DateTime<Utc>,
+) -> Result<KeyValidity> {
+    // verify the entire log before relying on any version
+    webvh::resolve_log(log, None, None).await?;
+
+    // the version in force is the last one created at or before signing
+    let Some(index) = log.iter().rposition(|e| e.version_time <= signed_at) else {
+        return Ok(KeyValidity::default());
+    };
+    let version_id = Some(log[index].version_id.clone());
+
+    let Some(key) = find_key(did_url, &log[index].state) else {
+        return Ok(KeyValidity {
+            version_id,
+            ..KeyValidity::default()
+        });
+    };
+
+    // find the first later version that no longer has the same key
+    let removed = log[index + 1..]
+        .iter()
+        .find(|e| e.parameters.deactivated || find_key(did_url, &e.state) != Some(key))
+        .map(|e| KeyRemoval {
+            version_id: e.version_id.clone(),
+            version_time: e.version_time,
+            deactivated: e.parameters.deactivated,
+        });
+
+    Ok(KeyValidity {
+        jwk: Some(key.jwk()?),
+        valid_at_signing: true,
+        version_id,
+        removed,
+    })
+}
+
+// Find the key material referenced by the DID URL in the document, whether
+// listed in `verificationMethod` or embedded in a verification relationship.
+fn find_key<'a>(did_url: &Url, doc: &'a Document) -> Option<&'a KeyFormat> {
+    const PURPOSES: [KeyPurpose; 6] = [
+        KeyPurpose::VerificationMethod,
+        KeyPurpose::Authentication,
+        KeyPurpose::AssertionMethod,
+        KeyPurpose::KeyAgreement,
+        KeyPurpose::CapabilityInvocation,
+        KeyPurpose::CapabilityDelegation,
+    ];
+    let id = did_url.to_string();
+    PURPOSES.iter().find_map(|purpose| doc.authorized_method(&id, purpose)).map(|vm| &vm.key)
+}
+
+// Key material from a resolved `did:key` or `did:web` resource.
+fn resource_jwk(did_url: &Url, resource: &Resource) -> Result<Option<PublicKeyJwk>> {
+    let key = match resource {
+        Resource::VerificationMethod(vm) => Some(&vm.key),
+        Resource::Document(doc) => find_key(did_url, doc),
+        Resource::Service(_) => return Err(anyhow!("DID URL does not reference a key")),
+    };
+    key.map(KeyFormat::jwk).transpose()
+}
@@ -0,0 +1,118 @@
+//! # DID Web
+//!
+//! Lifecycle operations for `did:web` documents hosted using the `Provider`.
+
+use anyhow::{Result, anyhow, bail};
+use credibil_did::web::{CreateBuilder, UpdateBuilder};
+use credibil_did::{Document, DocumentBuilder, DocumentMetadataBuilder, FromDocument, FromScratch};
+
+use crate::provider::{Proof, Provider, Registry, host_did};
+
+/// Create a new `did:web` document and save to document store for `owner`.
+///
+/// # Errors
+///
+/// Returns an error if the DID URL is invalid, if the document cannot be
+/// built, if another DID is hosted at the URL, or if the document cannot be
+/// saved to the docstore.
+pub async fn create(
+    owner: &str, url: &str, builder: DocumentBuilder<FromScratch>, provider: &impl Provider,
+) -> Result<Document> {
+    let document = CreateBuilder::new(url).document(builder).build()?;
+    host_did(provider, owner, &document.id, Proof::put(provider, owner, &document)).await?;
+    Ok(document)
+}
+
+/// Update a `did:web` document stored for `owner`.
+///
+/// The stored document is loaded and passed to `f` as a [`DocumentBuilder`]
+/// to make changes. The updated document is saved to the document store.
+///
+/// # Errors
+///
+/// Returns an error if the document cannot be found, has been deactivated,
+/// or if the updated document cannot be built or saved.
+pub async fn update(
+    owner: &str, did: &str,
+    f: impl FnOnce(DocumentBuilder<FromDocument>) -> DocumentBuilder<FromDocument>,
+    provider: &impl Provider,
+) -> Result<Document> {
+    let current = active(owner, did, provider).await?;
+    let document = UpdateBuilder::new(f(DocumentBuilder::from(current))).build()?;
+    Proof::put(provider, owner, &document).await?;
+    Ok(document)
+}
+
+/// Deactivate a `did:web` document stored for `owner`.
+///
+/// The document is kept as a tombstone with `deactivated` set in its metadata
+/// so requests for it can be answered with a 410-style response rather than
+/// 'not found'.
+///
+/// # Errors
+///
+/// Returns an error if the document cannot be found, has already been
+/// deactivated, or cannot be saved.
+pub async fn deactivate(owner: &str, did: &str, provider: &impl Provider) -> Result<Document> {
+    let current = active(owner, did, provider).await?;
+    let metadata = current
+        .did_document_metadata
+        .as_ref()
+        .map_or_else(DocumentMetadataBuilder::new, DocumentMetadataBuilder::from);
+    let builder = DocumentBuilder::from(current).metadata(metadata.deactivated(true).build());
+    let document = UpdateBuilder::new(builder).build()?;
+    Proof::put(provider, owner, &document).await?;
+    Ok(document)
+}
+
+/// List the `did:web` documents hosted for `owner`, including deactivated
+/// documents.
+///
+/// # Errors
+///
+/// Returns an error if the documents cannot be retrieved from the store.
+pub async fn list(owner: &str, provider: &impl Provider) -> Result<Vec<Document>> {
+    let mut documents = vec![];
+    for did in Registry::dids(provider, owner).await? {
+        if !did.starts_with("did:web:") {
+            continue;
+        }
+        if let Some(document) = Proof::get(provider, owner, &did).await? {
+            documents.push(document);
+        }
+    }
+    Ok(documents)
+}
+
+/// Move a `did:web` document stored before documents were scoped by owner
+/// (when the DID was used as the owner) to `owner`, registering the DID.
+///
+/// # Errors
+///
+/// Returns an error if the document cannot be found, if another DID is hosted
+/// at the DID's location, or if the document cannot be moved.
+pub async fn migrate(owner: &str, did: &str, provider: &impl Provider) -> Result<Document> {
+    let document = Proof::get(provider, did, did)
+        .await?
+        .ok_or_else(|| anyhow!("document not found for did: {did}"))?;
+    host_did(provider, owner, did, Proof::put(provider, owner, &document)).await?;
+    Proof::delete(provider, did, did).await?;
+    Ok(document)
+}
+
+/// Returns `true` if the document's metadata marks it as deactivated.
+#[must_use]
+pub fn is_deactivated(document: &Document) -> bool {
+    document.did_document_metadata.as_ref().and_then(|md| md.deactivated).unwrap_or_default()
+}
+
+// Load a stored document that has not been deactivated.
+async fn active(owner: &str, did: &str, provider: &impl Provider) -> Result<Document> {
+    let document = Proof::get(provider, owner, did)
+        .await?
+        .ok_or_else(|| anyhow!("document not found for did: {did}"))?;
+    if is_deactivated(&document) {
+        bail!("{did} has been deactivated");
+    }
+    Ok(document)
+}
@@ -0,0 +1,173 @@
+//! # DID Web with Verifiable History
+//!
+//! Lifecycle operations for `did:webvh` logs hosted using the `Provider`.
+//!
+//! Each operation loads and verifies the DID's current log from the
+//! [`LogStore`], runs the corresponding `credibil_did::webvh` builder and,
+//! once the new log verifies, appends the new entries to the stored log.
+//! Appends fail if the log has changed since it was loaded.
+
+use anyhow::{Result, bail};
+use credibil_did::webvh::{
+    CreateBuilder, CreateResult, DeactivateBuilder, DeactivateResult, KeyRotation, LogEntry,
+    UpdateBuilder, UpdateResult, resolve_log,
+};
+use credibil_did::{Document, DocumentBuilder, FromDocument, FromScratch};
+
+use crate::provider::{LogStore, Provider, Signature, host_did};
+
+/// Create a new `did:webvh` DID and save its log to the log store for
+/// `owner`.
+///
+/// The `update_keys` are the multibase-encoded public keys authorized to sign
+/// log entries. The `signer` signs the first log entry and should hold one of
+/// the update keys.
+///
+/// # Errors
+///
+/// Returns an error if the DID URL is invalid, if the log entry cannot be
+/// built or signed, if another DID is hosted at the URL, or if a log already
+/// exists for the DID.
+pub async fn webvh_create(
+    owner: &str, url: &str, builder: DocumentBuilder<FromScratch>, update_keys: Vec<String>,
+    signer: &impl Signature, provider: &impl Provider,
+) -> Result<CreateResult> {
+    let result = CreateBuilder::new(url)
+        .document(builder)
+        .update_keys(update_keys)
+        .signer(signer)
+        .build()
+        .await?;
+    let store = LogStore::append(provider, owner, &result.did, None, &result.log);
+    host_did(provider, owner, &result.did, store).await?;
+    Ok(result)
+}
+
+/// Update a stored `did:webvh` DID.
+///
+/// The current document is taken from the stored log and passed to `f` as a
+/// [`DocumentBuilder`] to make changes. The new log entry is signed by
+/// `signer` and appended to the stored log.
+///
+/// Use [`webvh_update_rotated`] if the log uses key pre-rotation.
+///
+/// # Errors
+///
+/// Returns an error if the stored log cannot be found or fails verification,
+/// if the new log entry cannot be built or signed, or if the stored log has
+/// changed in the meantime.
+pub async fn webvh_update(
+    owner: &str, did: &str,
+    f: impl FnOnce(DocumentBuilder<FromDocument>) -> DocumentBuilder<FromDocument>,
+    signer: &impl Signature, provider: &impl Provider,
+) -> Result<UpdateResult> {
+    let (log, document) = current_log(owner, did, provider).await?;
+
+    let result = UpdateBuilder::new()
+        .document(f(DocumentBuilder::from(document)))
+        .log_entries(log.clone())
+        .signer(signer)
+        .build()
+        .await?;
+    append(owner, did, &log, &result.log_entries, provider).await?;
+    Ok(result)
+}
+
+/// Update a stored `did:webvh` DID that uses key pre-rotation.
+///
+/// As for [`webvh_update`], except the update keys are rotated to the
+/// `rotation`'s update key, its next key is committed to, and the new log
+/// entry is signed by its keyring entry. The `rotation` should already have
+/// been rotated (see [`KeyRotation::rotate`]).
+///
+/// # Errors
+///
+/// Returns an error if the stored log cannot be found or fails verification,
+/// if the new log entry cannot be built or signed, or if the stored log has
+/// changed in the meantime.
+pub async fn webvh_update_rotated(
+    owner: &str, did: &str,
+    f: impl FnOnce(DocumentBuilder<FromDocument>) -> DocumentBuilder<FromDocument>,
+    rotation: &KeyRotation, provider: &impl Provider,
+) -> Result<UpdateResult> {
+    let (log, document) = current_log(owner, did, provider).await?;
+
+    let result = UpdateBuilder::new()
+        .document(f(DocumentBuilder::from(document)))
+        .log_entries(log.clone())
+        .key_rotation(rotation)
+        .build()
+        .await?;
+    append(owner, did, &log, &result.log_entries, provider).await?;
+    Ok(result)
+}
+
+/// Deactivate a stored `did:webvh` DID.
+///
+/// The deactivation log entry is signed by `signer` and appended to the
+/// stored log.
+///
+/// Use [`webvh_deactivate_rotated`] if the log uses key pre-rotation.
+///
+/// # Errors
+///
+/// Returns an error if the stored log cannot be found or fails verification,
+/// if the new log entries cannot be built or signed, or if the stored log has
+/// changed in the meantime.
+pub async fn webvh_deactivate(
+    owner: &str, did: &str, signer: &impl Signature, provider: &impl Provider,
+) -> Result<DeactivateResult> {
+    let (log, _) = current_log(owner, did, provider).await?;
+
+    let result = DeactivateBuilder::from(&log)?.signer(signer).build().await?;
+    append(owner, did, &log, &result.log, provider).await?;
+    Ok(result)
+}
+
+/// Deactivate a stored `did:webvh` DID that uses key pre-rotation.
+///
+/// The update keys are rotated to the `rotation`'s update key, which signs
+/// the deactivation log entries (one to stop pre-rotation and one to
+/// deactivate). The `rotation` should already have been rotated (see
+/// [`KeyRotation::rotate`]).
+///
+/// # Errors
+///
+/// Returns an error if the stored log cannot be found or fails verification,
+/// if the rotation's update key is not the committed next key, if the new
+/// log entries cannot be built or signed, or if the stored log has changed in
+/// the meantime.
+pub async fn webvh_deactivate_rotated(
+    owner: &str, did: &str, rotation: &KeyRotation, provider: &impl Provider,
+) -> Result<DeactivateResult> {
+    let (log, _) = current_log(owner, did, provider).await?;
+
+    let result = DeactivateBuilder::from(&log)?.key_rotation(rotation)?.build().await?;
+    append(owner, did, &log, &result.log, provider).await?;
+    Ok(result)
+}
+
+// Load and verify the stored log for the DID, returning it with the current
+// document. The document is the latest entry's state rather than the
+// resolved document so resolver metadata is not carried into new entries.
+async fn current_log(
+    owner: &str, did: &str, provider: &impl Provider,
+) -> Result<(Vec<LogEntry>, Document)> {
+    let log = LogStore::read(provider, owner, did, ..).await?;
+    let Some(last) = log.last() else {
+        bail!("log not found for did: {did}");
+    };
+    let document = last.state.clone();
+    resolve_log(&log, None, None).await?;
+    Ok((log, document))
+}
+
+// Append the entries added to `log` (to produce `updated`) to the stored log
+// once the updated log has been verified.
+async fn append(
+    owner: &str, did: &str, log: &[LogEntry], updated: &[LogEntry], provider: &impl Provider,
+) -> Result<()> {
+    resolve_log(updated, None, None).await?;
+    let tip = log.last().map(|entry| entry.version_id.as_str());
+    LogStore::append(provider, owner, did, tip, &updated[log.len()..]).await
+}
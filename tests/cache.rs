@@ -0,0 +1,242 @@
+//! Tests for the caching resolver.
+
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use credibil_proof::did::webvh::{CreateBuilder, CreateResult, LogEntry, UpdateBuilder};
+use credibil_proof::did::{DocumentBuilder, KeyId, Service, Url, VerificationMethod, web};
+use credibil_proof::ecc::{Curve, Entry, Keyring, Signer};
+use credibil_proof::jose::PublicKeyJwk;
+use credibil_proof::{CachingResolver, Resolver};
+use test_utils::Vault;
+
+// Serves a document (or log), counting requests. An empty body is served as
+// a (transient) fetch error.
+#[derive(Clone, Default)]
+struct Host {
+    body: Arc<Mutex<Vec<u8>>>,
+    delay: Duration,
+    fetches: Arc<AtomicUsize>,
+}
+
+impl Host {
+    fn serve(&self, body: Vec<u8>) {
+        *self.body.lock().expect("should lock") = body;
+    }
+
+    fn fetches(&self) -> usize {
+        self.fetches.load(Ordering::SeqCst)
+    }
+}
+
+impl Resolver for Host {
+    async fn resolve(&self, _url: &str) -> Result<Vec<u8>> {
+        self.fetches.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(self.delay).await;
+        let body = self.body.lock().expect("should lock").clone();
+        if body.is_empty() {
+            return Err(anyhow!("host unavailable"));
+        }
+        Ok(body)
+    }
+}
+
+async fn multikey(entry: &Entry) -> String {
+    let verifying_key = entry.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    jwk.to_multibase().expect("should get multibase")
+}
+
+async fn web_document() -> Vec<u8> {
+    let signer =
+        Keyring::generate(&Vault, "crw", "signing", Curve::Ed25519).await.expect("should generate");
+    let vm = VerificationMethod::build()
+        .key(multikey(&signer).await)
+        .key_id(KeyId::Index("key-0".to_string()));
+    let document = web::CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(DocumentBuilder::new().verification_method(vm))
+        .build()
+        .expect("should build document");
+    serde_json::to_vec(&document).expect("should serialize")
+}
+
+fn web_url() -> Url {
+    Url::from_str("did:web:credibil.io:issuers:example").expect("should parse")
+}
+
+fn jsonl(log: &[LogEntry]) -> Vec<u8> {
+    let mut data = vec![];
+    for entry in log {
+        serde_json::to_writer(&mut data, entry).expect("should serialize");
+        data.push(b'\n');
+    }
+    data
+}
+
+async fn webvh_log(signer: &Entry) -> CreateResult {
+    let update_multi = multikey(signer).await;
+    let vm = VerificationMethod::build()
+        .key(update_multi.clone())
+        .key_id(KeyId::Index("key-0".to_string()));
+
+    CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(DocumentBuilder::new().verification_method(vm))
+        .update_keys(vec![update_multi])
+        .signer(signer)
+        .build()
+        .await
+        .expect("should build document")
+}
+
+async fn update(signer: &Entry, created: &CreateResult, endpoint: &str) -> Vec<LogEntry> {
+    let service = Service::build()
+        .id("whois")
+        .service_type("LinkedVerifiablePresentation")
+        .endpoint(endpoint);
+    let builder = DocumentBuilder::from(created.document.clone()).service(service);
+
+    UpdateBuilder::new()
+        .document(builder)
+        .log_entries(created.log.clone())
+        .signer(signer)
+        .build()
+        .await
+        .expect("should update document")
+        .log_entries
+}
+
+// Documents should be served from the cache until they expire.
+#[tokio::test]
+async fn ttl() {
+    let host = Host::default();
+    host.serve(web_document().await);
+    let resolver = CachingResolver::new(host.clone()).web_ttl(Duration::from_millis(50));
+
+    resolver.document(&web_url()).await.expect("should resolve");
+    resolver.document(&web_url()).await.expect("should resolve");
+    assert_eq!(host.fetches(), 1);
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    resolver.document(&web_url()).await.expect("should resolve");
+    assert_eq!(host.fetches(), 2);
+
+    resolver.invalidate("did:web:credibil.io:issuers:example");
+    resolver.document(&web_url()).await.expect("should resolve");
+    assert_eq!(host.fetches(), 3);
+}
+
+// Invalid documents should be cached for the negative TTL but errors
+// fetching the document should not be cached.
+#[tokio::test]
+async fn negative_caching() {
+    let host = Host::default();
+    host.serve(b"not a document".to_vec());
+    let resolver = CachingResolver::new(host.clone()).negative_ttl(Duration::from_millis(50));
+
+    resolver.document(&web_url()).await.expect_err("should not resolve");
+    resolver.document(&web_url()).await.expect_err("should not resolve");
+    assert_eq!(host.fetches(), 1);
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    host.serve(vec![]);
+    resolver.document(&web_url()).await.expect_err("should not resolve");
+    assert_eq!(host.fetches(), 2);
+
+    // the host has recovered
+    host.serve(web_document().await);
+    resolver.document(&web_url()).await.expect("should resolve");
+    assert_eq!(host.fetches(), 3);
+}
+
+// Concurrent requests for the same DID should share a single fetch, and a
+// cancelled request should not prevent later ones.
+#[tokio::test]
+async fn single_flight() {
+    let host = Host {
+        delay: Duration::from_millis(50),
+        ..Host::default()
+    };
+    host.serve(web_document().await);
+    let resolver = CachingResolver::new(host.clone());
+
+    let url = web_url();
+    let (first, second, third) =
+        tokio::join!(resolver.document(&url), resolver.document(&url), resolver.document(&url));
+    assert!(first.is_ok() && second.is_ok() && third.is_ok());
+    assert_eq!(host.fetches(), 1);
+
+    resolver.clear();
+    tokio::time::timeout(Duration::from_millis(10), resolver.document(&url))
+        .await
+        .expect_err("should time out");
+    resolver.document(&url).await.expect("should resolve");
+    assert_eq!(host.fetches(), 3);
+}
+
+// An expired `did:webvh` log should only be accepted if it extends the log
+// verified previously.
+#[tokio::test]
+async fn webvh_checkpoint() {
+    let signer =
+        Keyring::generate(&Vault, "crv", "signing", Curve::Ed25519).await.expect("should generate");
+    let created = webvh_log(&signer).await;
+    let original = update(&signer, &created, "https://original.example.com/whois").await;
+    let rewritten = update(&signer, &created, "https://rewritten.example.com/whois").await;
+
+    let host = Host::default();
+    host.serve(jsonl(&original));
+    let resolver = CachingResolver::new(host.clone()).webvh_ttl(Duration::from_millis(50));
+
+    let did_url = Url::from_str(&created.did).expect("should parse");
+    let document = resolver.document(&did_url).await.expect("should resolve");
+    assert!(document.service.is_some());
+
+    // versioned queries are resolved from the cached log
+    let versioned =
+        Url::from_str(&format!("{}?versionId={}", created.did, created.log[0].version_id))
+            .expect("should parse");
+    let document = resolver.document(&versioned).await.expect("should resolve");
+    assert!(document.service.is_none());
+    assert_eq!(host.fetches(), 1);
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    host.serve(jsonl(&rewritten));
+    resolver.document(&did_url).await.expect_err("should reject rewritten log");
+}
+
+// A refreshed `did:webvh` log whose new entries are signed by a key they add
+// to `updateKeys` themselves should be cached as a verification failure.
+#[tokio::test]
+async fn webvh_foreign_tail() {
+    let signer =
+        Keyring::generate(&Vault, "crf", "signing", Curve::Ed25519).await.expect("should generate");
+    let created = webvh_log(&signer).await;
+
+    let foreign =
+        Keyring::generate(&Vault, "crf", "foreign", Curve::Ed25519).await.expect("should generate");
+    let extended = UpdateBuilder::new()
+        .document(DocumentBuilder::from(created.document.clone()))
+        .log_entries(created.log.clone())
+        .rotate_keys(&[multikey(&foreign).await], &[])
+        .signer(&foreign)
+        .build()
+        .await
+        .expect("should update document")
+        .log_entries;
+
+    let host = Host::default();
+    host.serve(jsonl(&created.log));
+    let resolver = CachingResolver::new(host.clone()).webvh_ttl(Duration::from_millis(50));
+
+    let did_url = Url::from_str(&created.did).expect("should parse");
+    resolver.document(&did_url).await.expect("should resolve");
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    host.serve(jsonl(&extended));
+    resolver.document(&did_url).await.expect_err("should reject a foreign-signed entry");
+    resolver.document(&did_url).await.expect_err("should cache the failure");
+    assert_eq!(host.fetches(), 2);
+}
@@ -0,0 +1,323 @@
+//! Tests for adding and verifying Data Integrity proofs.
+
+use chrono::{TimeDelta, Utc};
+use credibil_proof::did::{Document, DocumentBuilder, KeyId, KeyPurpose, VerificationMethod, web};
+use credibil_proof::ecc::{Curve, Entry, Keyring, Signer};
+use credibil_proof::jose::PublicKeyJwk;
+use credibil_proof::{
+    DidSigner, ProofOptions, Resolver, Signature, VerifyBy, VerifyOptions, add_proof, verify_proof,
+    verify_proofs,
+};
+use serde_json::{Value, json};
+use test_utils::Vault;
+
+// Serves a `did:web` document for any URL.
+struct Host(Vec<u8>);
+
+impl Resolver for Host {
+    async fn resolve(&self, _url: &str) -> anyhow::Result<Vec<u8>> {
+        Ok(self.0.clone())
+    }
+}
+
+async fn multikey(entry: &Entry) -> String {
+    let verifying_key = entry.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    jwk.to_multibase().expect("should get multibase")
+}
+
+// A document with an `assertionMethod` key (key-0) and an `authentication`
+// key (key-1).
+async fn document(assert: &Entry, auth: &Entry) -> Document {
+    let key_0 = VerificationMethod::build()
+        .key(multikey(assert).await)
+        .key_id(KeyId::Index("key-0".to_string()));
+    let key_1 = VerificationMethod::build()
+        .key(multikey(auth).await)
+        .key_id(KeyId::Index("key-1".to_string()));
+    let builder = DocumentBuilder::new()
+        .verification_method(key_0)
+        .verification_method(key_1)
+        .assertion_method("key-0".to_string())
+        .authentication("key-1".to_string());
+    web::CreateBuilder::new("https://credibil.io/issuers/integrity")
+        .document(builder)
+        .build()
+        .expect("should build document")
+}
+
+fn host(document: &Document) -> Host {
+    Host(serde_json::to_vec(document).expect("should serialize"))
+}
+
+fn credential() -> Value {
+    json!({
+        "@context": ["https://www.w3.org/ns/credentials/v2"],
+        "type": ["VerifiableCredential"],
+        "issuer": "did:web:credibil.io:issuers:integrity",
+        "credentialSubject": {"id": "did:example:subject", "name": "Alice"}
+    })
+}
+
+// A proof by a raw key should reference a `did:key` verification method,
+// which is authorized for every purpose except key agreement.
+#[tokio::test]
+async fn did_key() {
+    let signer =
+        Keyring::generate(&Vault, "dik", "signing", Curve::Ed25519).await.expect("should generate");
+    let resolver = Host(vec![]);
+
+    let secured =
+        add_proof(&credential(), &signer, &ProofOptions::default()).await.expect("should add");
+    let proof =
+        verify_proof(&secured, &resolver, &VerifyOptions::default()).await.expect("should verify");
+    assert!(proof.verification_method.starts_with("did:key:"));
+
+    let options = ProofOptions {
+        purpose: KeyPurpose::KeyAgreement,
+        ..ProofOptions::default()
+    };
+    let secured = add_proof(&credential(), &signer, &options).await.expect("should add");
+    let options = VerifyOptions {
+        purpose: KeyPurpose::KeyAgreement,
+        ..VerifyOptions::default()
+    };
+    verify_proof(&secured, &resolver, &options).await.expect_err("should not allow key agreement");
+}
+
+// The verification method should be resolved and authorized for the proof's
+// purpose, which must be the purpose expected.
+#[tokio::test]
+async fn purpose() {
+    let assert =
+        Keyring::generate(&Vault, "dip", "assert", Curve::Ed25519).await.expect("should generate");
+    let auth =
+        Keyring::generate(&Vault, "dip", "auth", Curve::Ed25519).await.expect("should generate");
+    let document = document(&assert, &auth).await;
+    let resolver = host(&document);
+
+    let signer = DidSigner::new(assert, &document.id, &KeyPurpose::AssertionMethod, &resolver)
+        .await
+        .expect("should bind key");
+    let secured =
+        add_proof(&credential(), &signer, &ProofOptions::default()).await.expect("should add");
+    let proof =
+        verify_proof(&secured, &resolver, &VerifyOptions::default()).await.expect("should verify");
+    assert_eq!(proof.verification_method, format!("{}#key-0", document.id));
+
+    // a proof for another purpose
+    let options = VerifyOptions {
+        purpose: KeyPurpose::Authentication,
+        ..VerifyOptions::default()
+    };
+    verify_proof(&secured, &resolver, &options).await.expect_err("should require the purpose");
+
+    // a key not authorized for the purpose
+    let signer = DidSigner::new(auth, &document.id, &KeyPurpose::Authentication, &resolver)
+        .await
+        .expect("should bind key");
+    let secured =
+        add_proof(&credential(), &signer, &ProofOptions::default()).await.expect("should add");
+    verify_proof(&secured, &resolver, &VerifyOptions::default())
+        .await
+        .expect_err("should not authorize an authentication key");
+}
+
+// A `DidSigner` should only bind a key published in the resolved DID document
+// for the purpose, and should identify the key by its DID URL.
+#[tokio::test]
+async fn did_signer() {
+    let assert =
+        Keyring::generate(&Vault, "dis", "assert", Curve::Ed25519).await.expect("should generate");
+    let auth =
+        Keyring::generate(&Vault, "dis", "auth", Curve::Ed25519).await.expect("should generate");
+    let other =
+        Keyring::generate(&Vault, "dis", "other", Curve::Ed25519).await.expect("should generate");
+    let document = document(&assert, &auth).await;
+    let resolver = host(&document);
+    let key_id = format!("{}#key-0", document.id);
+
+    let signer = DidSigner::new(assert, &document.id, &KeyPurpose::AssertionMethod, &resolver)
+        .await
+        .expect("should bind key");
+    assert_eq!(signer.key_id(), key_id);
+    let verify_by = signer.verification_method().await.expect("should get verification method");
+    assert_eq!(verify_by, VerifyBy::KeyId(key_id));
+
+    DidSigner::new(auth, &document.id, &KeyPurpose::AssertionMethod, &resolver)
+        .await
+        .expect_err("should not bind a key not authorized for the purpose");
+    DidSigner::new(other, &document.id, &KeyPurpose::AssertionMethod, &resolver)
+        .await
+        .expect_err("should not bind a key not in the document");
+}
+
+// A proof should only be valid between its `created` and `expires` times.
+#[tokio::test]
+async fn created_expires() {
+    let signer =
+        Keyring::generate(&Vault, "die", "signing", Curve::Ed25519).await.expect("should generate");
+    let resolver = Host(vec![]);
+    let now = Utc::now();
+
+    let options = ProofOptions {
+        created: Some(now + TimeDelta::hours(1)),
+        expires: Some(now + TimeDelta::hours(2)),
+        ..ProofOptions::default()
+    };
+    let secured = add_proof(&credential(), &signer, &options).await.expect("should add");
+
+    verify_proof(&secured, &resolver, &VerifyOptions::default())
+        .await
+        .expect_err("should not verify before it was created");
+
+    let at = |now| VerifyOptions {
+        now: Some(now),
+        ..VerifyOptions::default()
+    };
+    verify_proof(&secured, &resolver, &at(now + TimeDelta::minutes(90)))
+        .await
+        .expect("should verify while valid");
+    verify_proof(&secured, &resolver, &at(now + TimeDelta::hours(2)))
+        .await
+        .expect_err("should not verify once expired");
+}
+
+// A proof created slightly ahead of the verifier's clock should be allowed
+// for clock skew, but not one created well into the future.
+#[tokio::test]
+async fn clock_skew() {
+    let signer =
+        Keyring::generate(&Vault, "dik", "signing", Curve::Ed25519).await.expect("should generate");
+    let resolver = Host(vec![]);
+    let now = Utc::now();
+    let at = VerifyOptions {
+        now: Some(now),
+        ..VerifyOptions::default()
+    };
+
+    let options = ProofOptions {
+        created: Some(now + TimeDelta::seconds(5)),
+        ..ProofOptions::default()
+    };
+    let secured = add_proof(&credential(), &signer, &options).await.expect("should add");
+    verify_proof(&secured, &resolver, &at).await.expect("should allow for clock skew");
+
+    let options = ProofOptions {
+        created: Some(now + TimeDelta::minutes(5)),
+        ..ProofOptions::default()
+    };
+    let secured = add_proof(&credential(), &signer, &options).await.expect("should add");
+    verify_proof(&secured, &resolver, &at)
+        .await
+        .expect_err("should not verify before it was created");
+}
+
+// A proof's `domain` and `challenge` should match those expected.
+#[tokio::test]
+async fn domain_challenge() {
+    let signer =
+        Keyring::generate(&Vault, "did", "signing", Curve::Ed25519).await.expect("should generate");
+    let resolver = Host(vec![]);
+
+    let options = ProofOptions {
+        domain: Some(vec!["https://verifier.example".to_string()]),
+        challenge: Some("1235abcd6789".to_string()),
+        ..ProofOptions::default()
+    };
+    let secured = add_proof(&credential(), &signer, &options).await.expect("should add");
+
+    let expect = |domain: &str, challenge: &str| VerifyOptions {
+        domain: Some(domain.to_string()),
+        challenge: Some(challenge.to_string()),
+        ..VerifyOptions::default()
+    };
+    verify_proof(&secured, &resolver, &expect("https://verifier.example", "1235abcd6789"))
+        .await
+        .expect("should verify");
+    verify_proof(&secured, &resolver, &expect("https://other.example", "1235abcd6789"))
+        .await
+        .expect_err("should require the domain");
+    verify_proof(&secured, &resolver, &expect("https://verifier.example", "other"))
+        .await
+        .expect_err("should require the challenge");
+
+    // a proof without a challenge
+    let secured =
+        add_proof(&credential(), &signer, &ProofOptions::default()).await.expect("should add");
+    let options = VerifyOptions {
+        challenge: Some("1235abcd6789".to_string()),
+        ..VerifyOptions::default()
+    };
+    verify_proof(&secured, &resolver, &options).await.expect_err("should require a challenge");
+}
+
+// A proof should not verify once the document is changed.
+#[tokio::test]
+async fn tampered() {
+    let signer =
+        Keyring::generate(&Vault, "dit", "signing", Curve::Ed25519).await.expect("should generate");
+    let resolver = Host(vec![]);
+
+    let mut secured =
+        add_proof(&credential(), &signer, &ProofOptions::default()).await.expect("should add");
+    secured["credentialSubject"]["name"] = json!("Mallory");
+    verify_proof(&secured, &resolver, &VerifyOptions::default())
+        .await
+        .expect_err("should not verify a changed document");
+}
+
+// Proofs in a proof set or chain should each be verified, with chained
+// proofs verified after the proofs they reference.
+#[tokio::test]
+async fn proof_chain() {
+    let first =
+        Keyring::generate(&Vault, "dic", "first", Curve::Ed25519).await.expect("should generate");
+    let second =
+        Keyring::generate(&Vault, "dic", "second", Curve::Ed25519).await.expect("should generate");
+    let resolver = Host(vec![]);
+
+    let options = ProofOptions {
+        id: Some("urn:uuid:first".to_string()),
+        ..ProofOptions::default()
+    };
+    let secured = add_proof(&credential(), &first, &options).await.expect("should add");
+    let options = ProofOptions {
+        id: Some("urn:uuid:second".to_string()),
+        previous_proof: Some(vec!["urn:uuid:first".to_string()]),
+        ..ProofOptions::default()
+    };
+    let chained = add_proof(&secured, &second, &options).await.expect("should chain");
+
+    let proofs =
+        verify_proofs(&chained, &resolver, &VerifyOptions::default()).await.expect("should verify");
+    let ids = proofs.iter().map(|proof| proof.id.as_deref()).collect::<Vec<_>>();
+    assert_eq!(ids, vec![Some("urn:uuid:first"), Some("urn:uuid:second")]);
+    verify_proof(&chained, &resolver, &VerifyOptions::default())
+        .await
+        .expect_err("should require a single proof");
+
+    // a proof set
+    let set = add_proof(&secured, &second, &ProofOptions::default()).await.expect("should add");
+    let proofs =
+        verify_proofs(&set, &resolver, &VerifyOptions::default()).await.expect("should verify");
+    assert_eq!(proofs.len(), 2);
+
+    // a changed proof
+    let mut tampered = chained.clone();
+    tampered["proof"][0]["created"] = json!("2020-01-01T00:00:00Z");
+    verify_proofs(&tampered, &resolver, &VerifyOptions::default())
+        .await
+        .expect_err("should not verify a changed proof");
+
+    // a broken chain
+    let mut broken = chained;
+    broken["proof"][1]["previousProof"] = json!("urn:uuid:missing");
+    verify_proofs(&broken, &resolver, &VerifyOptions::default())
+        .await
+        .expect_err("should not verify a broken chain");
+
+    verify_proofs(&credential(), &resolver, &VerifyOptions::default())
+        .await
+        .expect_err("should require a proof");
+}
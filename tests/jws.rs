@@ -0,0 +1,149 @@
+//! Tests for verifying compact JWS signed by DID keys.
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use credibil_proof::did::{Document, DocumentBuilder, KeyId, KeyPurpose, VerificationMethod, web};
+use credibil_proof::ecc::{Curve, Entry, Keyring, Signer};
+use credibil_proof::jose::PublicKeyJwk;
+use credibil_proof::{Resolver, verify_jws};
+use serde_json::{Value, json};
+use test_utils::Vault;
+
+// Serves a `did:web` document for any URL.
+struct Host(Vec<u8>);
+
+impl Resolver for Host {
+    async fn resolve(&self, _url: &str) -> anyhow::Result<Vec<u8>> {
+        Ok(self.0.clone())
+    }
+}
+
+async fn jwk(entry: &Entry) -> PublicKeyJwk {
+    let verifying_key = entry.verifying_key().await.expect("should get key");
+    PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert")
+}
+
+// A document with an `assertionMethod` key (key-0) and an `authentication`
+// key (key-1).
+async fn document(assert: &Entry, auth: &Entry) -> Document {
+    let multikey = |jwk: PublicKeyJwk| jwk.to_multibase().expect("should get multibase");
+    let key_0 = VerificationMethod::build()
+        .key(multikey(jwk(assert).await))
+        .key_id(KeyId::Index("key-0".to_string()));
+    let key_1 = VerificationMethod::build()
+        .key(multikey(jwk(auth).await))
+        .key_id(KeyId::Index("key-1".to_string()));
+    let builder = DocumentBuilder::new()
+        .verification_method(key_0)
+        .verification_method(key_1)
+        .assertion_method("key-0".to_string())
+        .authentication("key-1".to_string());
+    web::CreateBuilder::new("https://credibil.io/issuers/jws")
+        .document(builder)
+        .build()
+        .expect("should build document")
+}
+
+async fn sign(signer: &Entry, header: &Value, claims: &Value) -> String {
+    let encode = |value: &Value| {
+        Base64UrlUnpadded::encode_string(&serde_json::to_vec(value).expect("should serialize"))
+    };
+    let signing_input = format!("{}.{}", encode(header), encode(claims));
+    let signature = signer.try_sign(signing_input.as_bytes()).await.expect("should sign");
+    format!("{signing_input}.{}", Base64UrlUnpadded::encode_string(&signature))
+}
+
+fn claims() -> Value {
+    json!({"iss": "did:web:credibil.io:issuers:jws", "nonce": "1235abcd6789"})
+}
+
+// The signing key should be resolved from the `kid` DID URL, or taken from
+// an embedded `jwk`.
+#[tokio::test]
+async fn kid_jwk() {
+    let assert =
+        Keyring::generate(&Vault, "jwk", "assert", Curve::Ed25519).await.expect("should generate");
+    let auth =
+        Keyring::generate(&Vault, "jwk", "auth", Curve::Ed25519).await.expect("should generate");
+    let document = document(&assert, &auth).await;
+    let resolver = Host(serde_json::to_vec(&document).expect("should serialize"));
+
+    let header = json!({"alg": "EdDSA", "kid": format!("{}#key-0", document.id)});
+    let compact = sign(&assert, &header, &claims()).await;
+    let verified: Value =
+        verify_jws(&compact, &resolver, &KeyPurpose::AssertionMethod).await.expect("should verify");
+    assert_eq!(verified, claims());
+
+    // signed by another key
+    let compact = sign(&auth, &header, &claims()).await;
+    verify_jws::<Value>(&compact, &resolver, &KeyPurpose::AssertionMethod)
+        .await
+        .expect_err("should not verify another key's signature");
+
+    let header = json!({"alg": "EdDSA", "jwk": jwk(&auth).await});
+    let compact = sign(&auth, &header, &claims()).await;
+    let verified: Value = verify_jws(&compact, &Host(vec![]), &KeyPurpose::AssertionMethod)
+        .await
+        .expect("should verify");
+    assert_eq!(verified, claims());
+
+    let compact = sign(&assert, &header, &claims()).await;
+    verify_jws::<Value>(&compact, &Host(vec![]), &KeyPurpose::AssertionMethod)
+        .await
+        .expect_err("should not verify with the embedded key");
+}
+
+// A `kid` should reference a verification method authorized for the purpose.
+#[tokio::test]
+async fn purpose() {
+    let assert =
+        Keyring::generate(&Vault, "jwp", "assert", Curve::Ed25519).await.expect("should generate");
+    let auth =
+        Keyring::generate(&Vault, "jwp", "auth", Curve::Ed25519).await.expect("should generate");
+    let document = document(&assert, &auth).await;
+    let resolver = Host(serde_json::to_vec(&document).expect("should serialize"));
+
+    let header = json!({"alg": "EdDSA", "kid": format!("{}#key-1", document.id)});
+    let compact = sign(&auth, &header, &claims()).await;
+    verify_jws::<Value>(&compact, &resolver, &KeyPurpose::AssertionMethod)
+        .await
+        .expect_err("should not authorize an authentication key");
+    verify_jws::<Value>(&compact, &resolver, &KeyPurpose::Authentication)
+        .await
+        .expect("should verify");
+
+    // `did:key` keys cannot be used for key agreement
+    let multikey = jwk(&auth).await.to_multibase().expect("should get multibase");
+    let header = json!({"alg": "EdDSA", "kid": format!("did:key:{multikey}#{multikey}")});
+    let compact = sign(&auth, &header, &claims()).await;
+    verify_jws::<Value>(&compact, &resolver, &KeyPurpose::Authentication)
+        .await
+        .expect("should verify");
+    verify_jws::<Value>(&compact, &resolver, &KeyPurpose::KeyAgreement)
+        .await
+        .expect_err("should not allow key agreement");
+}
+
+// The header's `alg` should match the key type, and no critical header
+// parameters are supported.
+#[tokio::test]
+async fn header() {
+    let signer =
+        Keyring::generate(&Vault, "jwa", "signing", Curve::Ed25519).await.expect("should generate");
+    let jwk = jwk(&signer).await;
+
+    let compact = sign(&signer, &json!({"alg": "ES256", "jwk": jwk}), &claims()).await;
+    verify_jws::<Value>(&compact, &Host(vec![]), &KeyPurpose::AssertionMethod)
+        .await
+        .expect_err("should reject a mismatched alg");
+
+    let header = json!({"alg": "EdDSA", "jwk": jwk, "crit": ["exp"], "exp": 0});
+    let compact = sign(&signer, &header, &claims()).await;
+    verify_jws::<Value>(&compact, &Host(vec![]), &KeyPurpose::AssertionMethod)
+        .await
+        .expect_err("should reject critical header parameters");
+
+    let compact = sign(&signer, &json!({"alg": "EdDSA"}), &claims()).await;
+    verify_jws::<Value>(&compact, &Host(vec![]), &KeyPurpose::AssertionMethod)
+        .await
+        .expect_err("should require a key");
+}
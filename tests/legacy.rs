@@ -0,0 +1,124 @@
+//! Tests for verifying legacy `Ed25519Signature2020` and `JsonWebSignature2020`
+//! proofs.
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use credibil_proof::did::rdf;
+use credibil_proof::ecc::{Curve, Entry, Keyring, Signer};
+use credibil_proof::jose::PublicKeyJwk;
+use credibil_proof::{Resolver, VerifyOptions, verify_proofs};
+use multibase::Base;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use test_utils::Vault;
+
+// `did:key` verification methods are not resolved.
+struct NoHost;
+
+impl Resolver for NoHost {
+    async fn resolve(&self, url: &str) -> anyhow::Result<Vec<u8>> {
+        Err(anyhow::anyhow!("{url} should not be resolved"))
+    }
+}
+
+async fn verification_method(entry: &Entry) -> String {
+    let verifying_key = entry.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let multikey = jwk.to_multibase().expect("should get multibase");
+    format!("did:key:{multikey}#{multikey}")
+}
+
+fn credential(suite_context: &str) -> Value {
+    json!({
+        "@context": ["https://www.w3.org/2018/credentials/v1", suite_context],
+        "id": "urn:uuid:86294362-4254-4f36-854f-3952fe42555d",
+        "type": ["VerifiableCredential"],
+        "issuer": "did:example:issuer",
+        "issuanceDate": "2021-11-13T18:19:39Z",
+        "credentialSubject": {"id": "did:example:subject"}
+    })
+}
+
+// The data signed by the legacy suites: the hash of the canonicalized proof
+// options followed by the hash of the canonicalized credential.
+fn signed_data(credential: &Value, options: &Value) -> Vec<u8> {
+    let mut options = options.clone();
+    options["@context"] = credential["@context"].clone();
+    let options_hash =
+        Sha256::digest(rdf::canonicalize(&options).expect("should canonicalize").as_bytes());
+    let document_hash =
+        Sha256::digest(rdf::canonicalize(credential).expect("should canonicalize").as_bytes());
+    [options_hash.as_slice(), document_hash.as_slice()].concat()
+}
+
+// Add a `JsonWebSignature2020` proof using the JWS header.
+async fn secure_jws(signer: &Entry, credential: &Value, options: &Value, header: Value) -> Value {
+    let header =
+        Base64UrlUnpadded::encode_string(&serde_json::to_vec(&header).expect("should serialize"));
+    let signing_input = [header.as_bytes(), b".", &signed_data(credential, options)].concat();
+    let signature = signer.try_sign(&signing_input).await.expect("should sign");
+
+    let mut proof = options.clone();
+    proof["jws"] = json!(format!("{header}..{}", Base64UrlUnpadded::encode_string(&signature)));
+    let mut secured = credential.clone();
+    secured["proof"] = proof;
+    secured
+}
+
+fn legacy() -> VerifyOptions {
+    VerifyOptions {
+        legacy: true,
+        ..VerifyOptions::default()
+    }
+}
+
+// An `Ed25519Signature2020` proof should only verify when legacy proofs are
+// enabled.
+#[tokio::test]
+async fn ed25519_signature_2020() {
+    let signer =
+        Keyring::generate(&Vault, "lge", "signing", Curve::Ed25519).await.expect("should generate");
+    let mut credential = credential("https://w3id.org/security/suites/ed25519-2020/v1");
+
+    let mut proof = json!({
+        "type": "Ed25519Signature2020",
+        "created": "2021-11-13T18:19:39Z",
+        "verificationMethod": verification_method(&signer).await,
+        "proofPurpose": "assertionMethod"
+    });
+    let signature = signer.try_sign(&signed_data(&credential, &proof)).await.expect("should sign");
+    proof["proofValue"] = json!(multibase::encode(Base::Base58Btc, signature));
+    credential["proof"] = proof;
+
+    verify_proofs(&credential, &NoHost, &VerifyOptions::default())
+        .await
+        .expect_err("should require legacy proofs to be enabled");
+    verify_proofs(&credential, &NoHost, &legacy()).await.expect("should verify");
+
+    credential["credentialSubject"]["id"] = json!("did:example:other");
+    verify_proofs(&credential, &NoHost, &legacy()).await.expect_err("should not verify");
+}
+
+// A `JsonWebSignature2020` proof should be a detached JWS with an unencoded
+// payload and an `alg` matching the key.
+#[tokio::test]
+async fn json_web_signature_2020() {
+    let signer =
+        Keyring::generate(&Vault, "lgj", "signing", Curve::Ed25519).await.expect("should generate");
+    let credential = credential("https://w3id.org/security/suites/jws-2020/v1");
+    let options = json!({
+        "type": "JsonWebSignature2020",
+        "created": "2021-11-13T18:19:39Z",
+        "verificationMethod": verification_method(&signer).await,
+        "proofPurpose": "assertionMethod"
+    });
+    let secure = |header| secure_jws(&signer, &credential, &options, header);
+
+    let secured = secure(json!({"alg": "EdDSA", "b64": false, "crit": ["b64"]})).await;
+    verify_proofs(&secured, &NoHost, &legacy()).await.expect("should verify");
+
+    let secured = secure(json!({"alg": "EdDSA"})).await;
+    verify_proofs(&secured, &NoHost, &legacy()).await.expect_err("should require b64");
+
+    let secured = secure(json!({"alg": "ES256", "b64": false, "crit": ["b64"]})).await;
+    verify_proofs(&secured, &NoHost, &legacy()).await.expect_err("should require a matching alg");
+}
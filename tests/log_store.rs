@@ -0,0 +1,141 @@
+//! Tests for the append-only `did:webvh` log store.
+
+use credibil_proof::LogStore;
+use credibil_proof::did::webvh::{CreateBuilder, LogEntry, UpdateBuilder, WitnessEntry};
+use credibil_proof::did::{DocumentBuilder, KeyId, Service, VerificationMethod};
+use credibil_proof::ecc::{Curve, Entry, Keyring, Signer};
+use credibil_proof::jose::PublicKeyJwk;
+use test_utils::{Proof, Vault};
+
+// Create a log with `count` entries.
+async fn log(signer: &Entry, count: usize) -> (String, Vec<LogEntry>) {
+    let verifying_key = signer.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let update_multi = jwk.to_multibase().expect("should get multibase");
+
+    let vm = VerificationMethod::build()
+        .key(update_multi.clone())
+        .key_id(KeyId::Index("key-0".to_string()));
+    let created = CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(DocumentBuilder::new().verification_method(vm))
+        .update_keys(vec![update_multi])
+        .signer(signer)
+        .build()
+        .await
+        .expect("should build document");
+
+    let mut log = created.log;
+    for index in 1..count {
+        let service = Service::build()
+            .id(format!("svc-{index}"))
+            .service_type("LinkedVerifiablePresentation")
+            .endpoint(format!("https://example.com/{index}"));
+        let state = log.last().expect("should have entries").state.clone();
+        log = UpdateBuilder::new()
+            .document(DocumentBuilder::from(state).service(service))
+            .log_entries(log)
+            .signer(signer)
+            .build()
+            .await
+            .expect("should update document")
+            .log_entries;
+    }
+    (created.did, log)
+}
+
+// Entries should only be appended to the tip they were created from.
+#[tokio::test]
+async fn append_tip() {
+    let signer =
+        Keyring::generate(&Vault, "lsa", "signing", Curve::Ed25519).await.expect("should generate");
+    let (did, log) = log(&signer, 3).await;
+    let store = Proof;
+
+    store.append("lsa", &did, None, &log[..1]).await.expect("should create log");
+    store
+        .append("lsa", &did, None, &log[..1])
+        .await
+        .expect_err("should not create an existing log");
+
+    let tip = log[0].version_id.as_str();
+    store.append("lsa", &did, Some(tip), &log[1..2]).await.expect("should append");
+
+    // a writer holding the old tip has been overtaken
+    store.append("lsa", &did, Some(tip), &log[2..]).await.expect_err("should reject a stale tip");
+
+    let tip = log[1].version_id.as_str();
+    store.append("lsa", &did, Some(tip), &log[2..]).await.expect("should append");
+    assert_eq!(store.read("lsa", &did, ..).await.expect("should read").len(), 3);
+}
+
+// Entries that do not continue the log's version sequence should be rejected.
+#[tokio::test]
+async fn append_sequence() {
+    let signer =
+        Keyring::generate(&Vault, "lss", "signing", Curve::Ed25519).await.expect("should generate");
+    let (did, log) = log(&signer, 3).await;
+    let store = Proof;
+
+    store.append("lss", &did, None, &log[1..2]).await.expect_err("should start at version 1");
+    store.append("lss", &did, None, &log[..1]).await.expect("should create log");
+
+    let tip = log[0].version_id.as_str();
+    store.append("lss", &did, Some(tip), &log[2..]).await.expect_err("should not skip a version");
+
+    let mut tampered = log[1].clone();
+    tampered.version_time = tampered.version_time + chrono::TimeDelta::seconds(1);
+    store
+        .append("lss", &did, Some(tip), &[tampered])
+        .await
+        .expect_err("should reject an entry not chained to the tip");
+
+    assert_eq!(store.read("lss", &did, ..).await.expect("should read").len(), 1);
+}
+
+// Reads should return the entries in the requested range of versions.
+#[tokio::test]
+async fn read_range() {
+    let signer =
+        Keyring::generate(&Vault, "lsr", "signing", Curve::Ed25519).await.expect("should generate");
+    let (did, log) = log(&signer, 3).await;
+    let store = Proof;
+
+    assert!(store.read("lsr", &did, ..).await.expect("should read").is_empty());
+    store.append("lsr", &did, None, &log).await.expect("should create log");
+
+    let versions =
+        |entries: Vec<LogEntry>| entries.into_iter().map(|e| e.version_id).collect::<Vec<_>>();
+    let all = versions(store.read("lsr", &did, ..).await.expect("should read"));
+    assert_eq!(all, versions(log.clone()));
+
+    let from = versions(store.read("lsr", &did, 2..).await.expect("should read"));
+    assert_eq!(from, versions(log[1..].to_vec()));
+
+    let between = versions(store.read("lsr", &did, 2..=2).await.expect("should read"));
+    assert_eq!(between, vec![log[1].version_id.clone()]);
+}
+
+// Witness proofs should be stored and fetched unchanged.
+#[tokio::test]
+async fn witness_round_trip() {
+    let signer =
+        Keyring::generate(&Vault, "lsw", "signing", Curve::Ed25519).await.expect("should generate");
+    let witness =
+        Keyring::generate(&Vault, "lsw", "witness", Curve::Ed25519).await.expect("should generate");
+    let (did, log) = log(&signer, 1).await;
+    let store = Proof;
+
+    assert!(store.get_witness("lsw", &did).await.expect("should get").is_empty());
+
+    let proofs = vec![WitnessEntry {
+        version_id: log[0].version_id.clone(),
+        proof: vec![log[0].proof(&witness).await.expect("should prove")],
+    }];
+    store.put_witness("lsw", &did, &proofs).await.expect("should put");
+
+    let fetched = store.get_witness("lsw", &did).await.expect("should get");
+    assert_eq!(
+        serde_json::to_value(&fetched).expect("should serialize"),
+        serde_json::to_value(&proofs).expect("should serialize")
+    );
+}
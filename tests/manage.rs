@@ -0,0 +1,259 @@
+//! Tests for signed `did:web` management requests.
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use chrono::Utc;
+use credibil_core::api::{Handler, NoHeaders, Request};
+use credibil_proof::did::web::CreateBuilder;
+use credibil_proof::did::{Document, DocumentBuilder, KeyId, VerificationMethod, webvh};
+use credibil_proof::ecc::{Curve, Entry, Keyring, Signer};
+use credibil_proof::jose::PublicKeyJwk;
+use credibil_proof::{
+    CreateRequest, DeactivateRequest, LogStore, Proof as Documents, Signed, UpdateRequest,
+    WebClaims, WebOperation, document_hash,
+};
+use test_utils::{Proof, Vault};
+
+async fn multikey(signer: &Entry) -> String {
+    let verifying_key = signer.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    jwk.to_multibase().expect("should get multibase")
+}
+
+// A document with an `authentication` key (key-0) and an `assertionMethod`
+// key (key-1).
+async fn document(url: &str, auth: &Entry, assert: &Entry) -> Document {
+    let key_0 = VerificationMethod::build()
+        .key(multikey(auth).await)
+        .key_id(KeyId::Index("key-0".to_string()));
+    let key_1 = VerificationMethod::build()
+        .key(multikey(assert).await)
+        .key_id(KeyId::Index("key-1".to_string()));
+    let builder = DocumentBuilder::new()
+        .verification_method(key_0)
+        .verification_method(key_1)
+        .authentication("key-0".to_string())
+        .assertion_method("key-1".to_string());
+    CreateBuilder::new(url).document(builder).build().expect("should build document")
+}
+
+fn claims(aud: &str, prior: Option<String>, operation: WebOperation) -> WebClaims {
+    let iat = Utc::now().timestamp();
+    WebClaims {
+        aud: aud.to_string(),
+        iat,
+        exp: iat + 60,
+        prior,
+        operation,
+    }
+}
+
+async fn sign(signer: &Entry, kid: &str, claims: &WebClaims) -> Signed {
+    let header = serde_json::json!({"alg": "EdDSA", "kid": kid});
+    let header =
+        Base64UrlUnpadded::encode_string(&serde_json::to_vec(&header).expect("should serialize"));
+    let payload =
+        Base64UrlUnpadded::encode_string(&serde_json::to_vec(claims).expect("should serialize"));
+    let signing_input = format!("{header}.{payload}");
+    let signature = signer.try_sign(signing_input.as_bytes()).await.expect("should sign");
+    Signed::Jws(format!("{signing_input}.{}", Base64UrlUnpadded::encode_string(&signature)))
+}
+
+async fn current(owner: &str, did: &str) -> Document {
+    Documents::get(&Proof, owner, did).await.expect("should get").expect("should exist")
+}
+
+async fn create(owner: &str, signed: Signed) -> anyhow::Result<Document> {
+    let request = Request {
+        body: CreateRequest { signed },
+        headers: NoHeaders,
+    };
+    Ok(request.handle(owner, &Proof).await?.body.0)
+}
+
+async fn update(owner: &str, did: &str, signed: Signed) -> anyhow::Result<Document> {
+    let request = Request {
+        body: UpdateRequest {
+            did: did.to_string(),
+            signed,
+        },
+        headers: NoHeaders,
+    };
+    Ok(request.handle(owner, &Proof).await?.body.0)
+}
+
+async fn deactivate(owner: &str, did: &str, signed: Signed) -> anyhow::Result<Document> {
+    let request = Request {
+        body: DeactivateRequest {
+            did: did.to_string(),
+            signed,
+        },
+        headers: NoHeaders,
+    };
+    Ok(request.handle(owner, &Proof).await?.body.0)
+}
+
+// Requests signed by an authentication key of the DID should be accepted, but
+// only once.
+#[tokio::test]
+async fn signed_requests() {
+    let auth =
+        Keyring::generate(&Vault, "mgs", "auth", Curve::Ed25519).await.expect("should generate");
+    let assert =
+        Keyring::generate(&Vault, "mgs", "assert", Curve::Ed25519).await.expect("should generate");
+    let document = document("https://credibil.io/manage/signed", &auth, &assert).await;
+    let did = document.id.clone();
+    let kid = format!("{did}#key-0");
+
+    let operation = WebOperation::Create { document };
+    create("mgs", sign(&auth, &kid, &claims("mgs", None, operation)).await)
+        .await
+        .expect("should create");
+
+    let mut document = current("mgs", &did).await;
+    document.also_known_as = Some(vec!["https://credibil.io/signed".to_string()]);
+    let prior = Some(document_hash(&current("mgs", &did).await).expect("should hash"));
+    let operation = WebOperation::Update { document };
+    let signed = sign(&auth, &kid, &claims("mgs", prior, operation)).await;
+    let updated = update("mgs", &did, signed.clone()).await.expect("should update");
+    assert!(updated.also_known_as.is_some());
+
+    update("mgs", &did, signed).await.expect_err("should not replay an update");
+}
+
+// Requests not signed by an authentication key of the DID, or not bound to
+// the DID, host, time and document version, should be rejected.
+#[tokio::test]
+async fn rejected_requests() {
+    let auth =
+        Keyring::generate(&Vault, "mgr", "auth", Curve::Ed25519).await.expect("should generate");
+    let assert =
+        Keyring::generate(&Vault, "mgr", "assert", Curve::Ed25519).await.expect("should generate");
+    let document = document("https://credibil.io/manage/rejected", &auth, &assert).await;
+    let did = document.id.clone();
+    let kid = format!("{did}#key-0");
+
+    let operation = WebOperation::Create { document };
+    create("mgr", sign(&auth, &kid, &claims("mgr", None, operation)).await)
+        .await
+        .expect("should create");
+
+    let document = current("mgr", &did).await;
+    let prior = Some(document_hash(&document).expect("should hash"));
+    let operation = WebOperation::Update {
+        document: document.clone(),
+    };
+    let valid = claims("mgr", prior.clone(), operation.clone());
+
+    // wrong key
+    let signed = sign(&assert, &kid, &valid).await;
+    update("mgr", &did, signed).await.expect_err("should reject the wrong key");
+
+    // not an authentication key
+    let signed = sign(&assert, &format!("{did}#key-1"), &valid).await;
+    update("mgr", &did, signed).await.expect_err("should reject an assertion key");
+
+    // for a different DID
+    let other = self::document("https://credibil.io/manage/other", &auth, &assert).await;
+    let wrong_did = claims("mgr", prior.clone(), WebOperation::Update { document: other });
+    let signed = sign(&auth, &kid, &wrong_did).await;
+    update("mgr", &did, signed).await.expect_err("should reject another DID's document");
+
+    // for a previous version of the document
+    let stale = claims("mgr", Some("z123".to_string()), operation.clone());
+    let signed = sign(&auth, &kid, &stale).await;
+    update("mgr", &did, signed).await.expect_err("should reject a stale request");
+
+    // expired
+    let mut expired = valid.clone();
+    expired.iat -= 120;
+    expired.exp -= 120;
+    let signed = sign(&auth, &kid, &expired).await;
+    update("mgr", &did, signed).await.expect_err("should reject an expired request");
+
+    // for another host
+    let signed = sign(&auth, &kid, &claims("other", prior, operation)).await;
+    update("mgr", &did, signed).await.expect_err("should reject another audience");
+
+    // the valid request is still accepted
+    let signed = sign(&auth, &kid, &valid).await;
+    update("mgr", &did, signed).await.expect("should update");
+}
+
+// A deactivated DID should not be changed.
+#[tokio::test]
+async fn deactivated() {
+    let auth =
+        Keyring::generate(&Vault, "mgd", "auth", Curve::Ed25519).await.expect("should generate");
+    let assert =
+        Keyring::generate(&Vault, "mgd", "assert", Curve::Ed25519).await.expect("should generate");
+    let document = document("https://credibil.io/manage/deactivated", &auth, &assert).await;
+    let did = document.id.clone();
+    let kid = format!("{did}#key-0");
+
+    let operation = WebOperation::Create { document };
+    create("mgd", sign(&auth, &kid, &claims("mgd", None, operation)).await)
+        .await
+        .expect("should create");
+
+    let document = current("mgd", &did).await;
+    let prior = Some(document_hash(&document).expect("should hash"));
+    let operation = WebOperation::Deactivate { did: did.clone() };
+    let signed = sign(&auth, &kid, &claims("mgd", prior, operation)).await;
+    deactivate("mgd", &did, signed).await.expect("should deactivate");
+
+    let deactivated = current("mgd", &did).await;
+    let prior = Some(document_hash(&deactivated).expect("should hash"));
+    let operation = WebOperation::Update { document };
+    let signed = sign(&auth, &kid, &claims("mgd", prior, operation)).await;
+    update("mgd", &did, signed).await.expect_err("should not update a deactivated DID");
+}
+
+// Log entries appended to a hosted `did:webvh` log should be signed by the
+// current update keys. An entry signed by a key it adds to `updateKeys`
+// itself should be rejected.
+#[tokio::test]
+async fn foreign_log_entry() {
+    let signer =
+        Keyring::generate(&Vault, "mgl", "signing", Curve::Ed25519).await.expect("should generate");
+    let update_multi = multikey(&signer).await;
+    let vm = VerificationMethod::build()
+        .key(update_multi.clone())
+        .key_id(KeyId::Index("key-0".to_string()));
+    let created = webvh::CreateBuilder::new("https://credibil.io/manage/log")
+        .document(DocumentBuilder::new().verification_method(vm))
+        .update_keys(vec![update_multi])
+        .signer(&signer)
+        .build()
+        .await
+        .expect("should build document");
+    let did = created.did.clone();
+    create("mgl", Signed::Log(created.log.clone())).await.expect("should create");
+
+    let foreign =
+        Keyring::generate(&Vault, "mgl", "foreign", Curve::Ed25519).await.expect("should generate");
+    let updated = webvh::UpdateBuilder::new()
+        .document(DocumentBuilder::from(created.document.clone()))
+        .log_entries(created.log.clone())
+        .rotate_keys(&[multikey(&foreign).await], &[])
+        .signer(&foreign)
+        .build()
+        .await
+        .expect("should build update");
+    let entries = updated.log_entries[1..].to_vec();
+    update("mgl", &did, Signed::Log(entries)).await.expect_err("should reject a foreign key");
+
+    let log = Proof.read("mgl", &did, ..).await.expect("should read");
+    assert_eq!(log.len(), 1);
+
+    // the current update key can append the same change
+    let updated = webvh::UpdateBuilder::new()
+        .document(DocumentBuilder::from(created.document))
+        .log_entries(created.log)
+        .rotate_keys(&[multikey(&foreign).await], &[])
+        .signer(&signer)
+        .build()
+        .await
+        .expect("should build update");
+    let entries = updated.log_entries[1..].to_vec();
+    update("mgl", &did, Signed::Log(entries)).await.expect("should update");
+}
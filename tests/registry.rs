@@ -0,0 +1,127 @@
+//! Tests for owner scoping of hosted DIDs.
+
+use anyhow::{Result, bail};
+use credibil_core::api::{Handler, NoHeaders, Request};
+use credibil_core::datastore::Datastore;
+use credibil_proof::did::{DocumentBuilder, FromScratch, KeyId, VerificationMethod};
+use credibil_proof::ecc::{Curve, Keyring, Signer};
+use credibil_proof::jose::PublicKeyJwk;
+use credibil_proof::{
+    DocumentRequest, Proof as Documents, Registry, create, location_key, migrate, webvh_create,
+};
+use test_utils::{Proof, Vault};
+
+// A store that cannot save `did:webvh` logs.
+#[derive(Clone)]
+struct NoLogs;
+
+impl Datastore for NoLogs {
+    async fn put(&self, owner: &str, partition: &str, key: &str, data: &[u8]) -> Result<()> {
+        if partition == "log" {
+            bail!("log store unavailable");
+        }
+        Datastore::put(&Proof, owner, partition, key, data).await
+    }
+
+    async fn get(&self, owner: &str, partition: &str, key: &str) -> Result<Option<Vec<u8>>> {
+        Datastore::get(&Proof, owner, partition, key).await
+    }
+
+    async fn delete(&self, owner: &str, partition: &str, key: &str) -> Result<()> {
+        Datastore::delete(&Proof, owner, partition, key).await
+    }
+
+    async fn get_all(&self, owner: &str, partition: &str) -> Result<Vec<(String, Vec<u8>)>> {
+        Datastore::get_all(&Proof, owner, partition).await
+    }
+}
+
+fn builder() -> DocumentBuilder<FromScratch> {
+    let vm = VerificationMethod::build()
+        .key("z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK".to_string())
+        .key_id(KeyId::Index("key-0".to_string()));
+    DocumentBuilder::new().verification_method(vm)
+}
+
+// A location should only be registered to one owner at a time, and only an
+// owner should be able to release it.
+#[tokio::test]
+async fn register() {
+    let did = "did:web:credibil.io:registry:register";
+
+    Proof.register("", did).await.expect_err("should require an owner");
+    Proof.register("rga", did).await.expect("should register");
+    Proof.register("rgb", did).await.expect_err("should not register a hosted location");
+    Proof.register("rga", did).await.expect_err("should not register twice");
+
+    let registration = Proof.hosted(did).await.expect("should look up").expect("should be hosted");
+    assert_eq!(registration.owner, "rga");
+    assert_eq!(Proof.dids("rga").await.expect("should list"), vec![did.to_string()]);
+
+    Proof.unregister("rgb", did).await.expect_err("should only unregister for the owner");
+    Proof.unregister("rga", did).await.expect("should unregister");
+    assert!(Proof.hosted(did).await.expect("should look up").is_none());
+    assert!(Proof.dids("rga").await.expect("should list").is_empty());
+
+    Proof.register("rgb", did).await.expect("should register a free location");
+}
+
+// A DID that cannot be stored should not remain registered.
+#[tokio::test]
+async fn rollback() {
+    let signer =
+        Keyring::generate(&Vault, "rgr", "signing", Curve::Ed25519).await.expect("should generate");
+    let verifying_key = signer.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let update_multi = jwk.to_multibase().expect("should get multibase");
+
+    let url = "https://credibil.io/registry/rollback";
+    webvh_create("rgr", url, builder(), vec![update_multi.clone()], &signer, &NoLogs)
+        .await
+        .expect_err("should fail to store the log");
+    assert!(Proof.dids("rgr").await.expect("should list").is_empty());
+
+    let created = webvh_create("rgr", url, builder(), vec![update_multi], &signer, &Proof)
+        .await
+        .expect("should create once the location is free");
+    let key = location_key(&created.did).expect("should get location key");
+    let registration = Proof.hosted(&key).await.expect("should look up").expect("should be hosted");
+    assert_eq!(registration.did, created.did);
+}
+
+// Documents should only be served to the owner hosting them.
+#[tokio::test]
+async fn scoped() {
+    let url = "https://credibil.io/registry/scoped";
+    create("rgs", url, builder(), &Proof).await.expect("should create");
+
+    let request = |url: &str| Request {
+        body: DocumentRequest {
+            url: format!("{url}/did.json"),
+        },
+        headers: NoHeaders,
+    };
+    let response = request(url).handle("rgs", &Proof).await.expect("should serve");
+    assert_eq!(response.body.0.id, "did:web:credibil.io:registry:scoped");
+
+    request(url).handle("other", &Proof).await.expect_err("should not serve to another owner");
+    request(url).handle("", &Proof).await.expect_err("should require the owner");
+}
+
+// Documents stored using the DID as the owner should be moved to the owner.
+#[tokio::test]
+async fn migrate_owner() {
+    let url = "https://credibil.io/registry/legacy";
+    let document = create("rgm", url, builder(), &Proof).await.expect("should create");
+    let did = document.id.clone();
+
+    // reproduce a document stored before owner scoping
+    Proof.unregister("rgm", &did).await.expect("should unregister");
+    Documents::delete(&Proof, "rgm", &did).await.expect("should delete");
+    Documents::put(&Proof, &did, &document).await.expect("should store");
+
+    migrate("rgl", &did, &Proof).await.expect("should migrate");
+    assert_eq!(Proof.dids("rgl").await.expect("should list"), vec![did.clone()]);
+    assert!(Documents::get(&Proof, "rgl", &did).await.expect("should get").is_some());
+    Documents::get(&Proof, &did, &did).await.expect_err("should remove the legacy document");
+}
@@ -0,0 +1,155 @@
+//! Tests for the DID Resolution endpoint.
+
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Result, anyhow};
+use credibil_core::api::{Handler, NoHeaders, Request};
+use credibil_proof::did::{
+    DocumentBuilder, DocumentMetadataBuilder, KeyId, KeyPurpose, VerificationMethod, web,
+};
+use credibil_proof::{
+    DID_JSON, DID_LD_JSON, DID_RESOLUTION, ResolutionError, ResolveRequest, ResolveResponse,
+    Resolver,
+};
+
+const DID: &str = "did:web:credibil.io:issuers:resolve";
+const MULTIKEY: &str = "z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK";
+
+// Serves a document. An empty body is served as a fetch error.
+#[derive(Clone, Default)]
+struct Host {
+    body: Arc<Mutex<Vec<u8>>>,
+}
+
+impl Host {
+    fn serve(&self, body: Vec<u8>) {
+        *self.body.lock().expect("should lock") = body;
+    }
+}
+
+impl Resolver for Host {
+    async fn resolve(&self, _url: &str) -> Result<Vec<u8>> {
+        let body = self.body.lock().expect("should lock").clone();
+        if body.is_empty() {
+            return Err(anyhow!("host unavailable"));
+        }
+        Ok(body)
+    }
+}
+
+fn document(deactivated: bool) -> Vec<u8> {
+    let vm = VerificationMethod::build()
+        .key(MULTIKEY.to_string())
+        .key_id(KeyId::Index("key-0".to_string()));
+    let mut document = web::CreateBuilder::new("https://credibil.io/issuers/resolve")
+        .document(DocumentBuilder::new().verification_method(vm))
+        .build()
+        .expect("should build document");
+    if deactivated {
+        document.did_document_metadata =
+            Some(DocumentMetadataBuilder::new().deactivated(true).build());
+    }
+    serde_json::to_vec(&document).expect("should serialize")
+}
+
+async fn resolve(host: &Host, did_url: &str, accept: Option<&str>) -> ResolveResponse {
+    let request = Request {
+        body: ResolveRequest {
+            did_url: did_url.to_string(),
+            accept: accept.map(ToString::to_string),
+        },
+        headers: NoHeaders,
+    };
+    request.handle("", host).await.expect("should respond").body
+}
+
+fn error(response: &ResolveResponse) -> Option<ResolutionError> {
+    let ResolveResponse::Result(result) = response else {
+        return None;
+    };
+    result.did_resolution_metadata.error.clone()
+}
+
+// The representation should be selected using the `Accept` header's quality
+// values.
+#[tokio::test]
+async fn negotiate() {
+    let host = Host::default();
+    host.serve(document(false));
+
+    let response = resolve(&host, DID, None).await;
+    assert_eq!(response.content_type(), DID_RESOLUTION);
+    assert_eq!(response.status(), 200);
+
+    let response = resolve(&host, DID, Some(DID_JSON)).await;
+    assert_eq!(response.content_type(), DID_JSON);
+    let ResolveResponse::Json { document, .. } = response else {
+        panic!("should be a DID document");
+    };
+    assert!(document.did_document_metadata.is_none());
+
+    let accept = "application/did+json;q=0.5, application/did+ld+json";
+    let response = resolve(&host, DID, Some(accept)).await;
+    assert_eq!(response.content_type(), DID_LD_JSON);
+
+    let accept = "application/did+json;q=0, text/html";
+    let response = resolve(&host, DID, Some(accept)).await;
+    assert_eq!(error(&response), Some(ResolutionError::RepresentationNotSupported));
+    assert_eq!(response.status(), 406);
+}
+
+// A deactivated DID should be served with a 410 status in every
+// representation.
+#[tokio::test]
+async fn deactivated() {
+    let host = Host::default();
+    host.serve(document(true));
+
+    let response = resolve(&host, &format!("/1.0/identifiers/{DID}"), Some(DID_JSON)).await;
+    assert_eq!(response.status(), 410);
+    let response = resolve(&host, DID, Some(DID_LD_JSON)).await;
+    assert_eq!(response.status(), 410);
+    let response = resolve(&host, DID, None).await;
+    assert_eq!(response.status(), 410);
+}
+
+// Resolution failures should be reported with the matching error and status.
+#[tokio::test]
+async fn failures() {
+    let host = Host::default();
+
+    let response = resolve(&host, "did:web", None).await;
+    assert_eq!(error(&response), Some(ResolutionError::InvalidDid));
+    assert_eq!(response.status(), 400);
+
+    let response = resolve(&host, "did:example:123", None).await;
+    assert_eq!(error(&response), Some(ResolutionError::MethodNotSupported));
+    assert_eq!(response.status(), 501);
+
+    let response = resolve(&host, DID, None).await;
+    assert_eq!(error(&response), Some(ResolutionError::NotFound));
+    assert_eq!(response.status(), 404);
+
+    host.serve(b"not a DID document".to_vec());
+    let response = resolve(&host, DID, None).await;
+    assert_eq!(error(&response), Some(ResolutionError::InvalidDid));
+    assert_eq!(response.status(), 400);
+}
+
+// `did:key` DIDs should be expanded into a DID document.
+#[tokio::test]
+async fn did_key() {
+    let did = format!("did:key:{MULTIKEY}");
+    let response = resolve(&Host::default(), &did, Some(DID_JSON)).await;
+    assert_eq!(response.status(), 200);
+
+    let ResolveResponse::Json { document, .. } = response else {
+        panic!("should be a DID document");
+    };
+    assert_eq!(document.id, did);
+    let kid = format!("{did}#{MULTIKEY}");
+    let vm = document
+        .authorized_method(&kid, &KeyPurpose::AssertionMethod)
+        .expect("should authorize key");
+    assert_eq!(vm.controller, did);
+}
@@ -0,0 +1,158 @@
+//! Tests for historical key validity checks.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use credibil_core::Kind;
+use credibil_proof::did::webvh::{CreateBuilder, LogEntry, UpdateBuilder};
+use credibil_proof::did::{DocumentBuilder, KeyId, SteppingClock, VerificationMethod};
+use credibil_proof::ecc::{Curve, Entry, Keyring, Signer};
+use credibil_proof::jose::PublicKeyJwk;
+use credibil_proof::{Resolver, verify_at};
+use test_utils::Vault;
+
+// Serves a `did:webvh` log for any URL.
+struct Host(Vec<u8>);
+
+impl Resolver for Host {
+    async fn resolve(&self, _url: &str) -> anyhow::Result<Vec<u8>> {
+        Ok(self.0.clone())
+    }
+}
+
+fn start() -> DateTime<Utc> {
+    "2025-01-01T00:00:00Z".parse().expect("should parse")
+}
+
+async fn multikey(entry: &Entry) -> String {
+    let verifying_key = entry.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    jwk.to_multibase().expect("should get multibase")
+}
+
+fn host(log: &[LogEntry]) -> Host {
+    let mut data = vec![];
+    for entry in log {
+        serde_json::to_writer(&mut data, entry).expect("should serialize");
+        data.push(b'\n');
+    }
+    Host(data)
+}
+
+// Create a log with `#key-0` and an `#auth-0` method embedded in the
+// `authentication` relationship, then replace `#key-0` with `#key-1` a day
+// later.
+async fn rotated_log() -> (String, Vec<LogEntry>) {
+    let signer =
+        Keyring::generate(&Vault, "vat", "signing", Curve::Ed25519).await.expect("should generate");
+    let update_multi = multikey(&signer).await;
+
+    let key_0 =
+        Keyring::generate(&Vault, "vat", "key-0", Curve::Ed25519).await.expect("should generate");
+    let auth_0 =
+        Keyring::generate(&Vault, "vat", "auth-0", Curve::Ed25519).await.expect("should generate");
+    let vm = VerificationMethod::build()
+        .key(multikey(&key_0).await)
+        .key_id(KeyId::Index("key-0".to_string()));
+    let embedded = VerificationMethod::build()
+        .key(multikey(&auth_0).await)
+        .key_id(KeyId::Index("auth-0".to_string()));
+    let builder =
+        DocumentBuilder::new().verification_method(vm).authentication(Kind::Object(embedded));
+
+    let created = CreateBuilder::new("https://credibil.io/issuers/example")
+        .document(builder)
+        .update_keys(vec![update_multi])
+        .clock(SteppingClock::new(start()))
+        .signer(&signer)
+        .build()
+        .await
+        .expect("should build document");
+
+    let key_1 =
+        Keyring::generate(&Vault, "vat", "key-1", Curve::Ed25519).await.expect("should generate");
+    let vm = VerificationMethod::build()
+        .key(multikey(&key_1).await)
+        .key_id(KeyId::Index("key-1".to_string()));
+    let mut document = created.document;
+    document.verification_method = None;
+    let builder = DocumentBuilder::from(document).verification_method(vm);
+
+    let updated = UpdateBuilder::new()
+        .document(builder)
+        .log_entries(created.log)
+        .clock(SteppingClock::new(start() + TimeDelta::days(1)))
+        .signer(&signer)
+        .build()
+        .await
+        .expect("should update document");
+
+    (created.did, updated.log_entries)
+}
+
+// A key in force at signing and replaced since should be reported as valid
+// at signing and removed by the later version.
+#[tokio::test]
+async fn removed_since() {
+    let (did, log) = rotated_log().await;
+    let host = host(&log);
+
+    let signed_at = start() + TimeDelta::hours(12);
+    let validity =
+        verify_at(&format!("{did}#key-0"), signed_at, &host).await.expect("should verify");
+    assert!(validity.valid_at_signing);
+    assert!(validity.jwk.is_some());
+    assert_eq!(validity.version_id.as_deref(), Some(log[0].version_id.as_str()));
+
+    let removed = validity.removed.expect("should be removed");
+    assert_eq!(removed.version_id, log[1].version_id);
+    assert!(!removed.deactivated);
+}
+
+// A key that is not in the document in force at signing is not valid.
+#[tokio::test]
+async fn not_yet_or_no_longer_valid() {
+    let (did, log) = rotated_log().await;
+    let host = host(&log);
+
+    // signed before the DID was created
+    let signed_at = start() - TimeDelta::days(1);
+    let validity =
+        verify_at(&format!("{did}#key-0"), signed_at, &host).await.expect("should verify");
+    assert!(!validity.valid_at_signing);
+    assert!(validity.version_id.is_none());
+
+    // signed after the key was replaced
+    let signed_at = start() + TimeDelta::days(2);
+    let validity =
+        verify_at(&format!("{did}#key-0"), signed_at, &host).await.expect("should verify");
+    assert!(!validity.valid_at_signing);
+    assert!(validity.jwk.is_none());
+    assert_eq!(validity.version_id.as_deref(), Some(log[1].version_id.as_str()));
+
+    let validity =
+        verify_at(&format!("{did}#key-1"), signed_at, &host).await.expect("should verify");
+    assert!(validity.valid_at_signing);
+    assert!(!validity.removed_since());
+}
+
+// Keys embedded in a verification relationship should be found.
+#[tokio::test]
+async fn embedded_key() {
+    let (did, log) = rotated_log().await;
+    let host = host(&log);
+
+    let signed_at = start() + TimeDelta::hours(12);
+    let validity =
+        verify_at(&format!("{did}#auth-0"), signed_at, &host).await.expect("should verify");
+    assert!(validity.valid_at_signing);
+    assert!(!validity.removed_since());
+}
+
+// A log that fails verification should not be relied on.
+#[tokio::test]
+async fn tampered_log() {
+    let (did, mut log) = rotated_log().await;
+    log[1].proof.clear();
+    let host = host(&log);
+
+    verify_at(&format!("{did}#key-0"), start(), &host).await.expect_err("should not verify");
+}
@@ -0,0 +1,97 @@
+//! Tests for `did:webvh` lifecycle operations through the `Provider`.
+
+use credibil_proof::did::webvh::{CreateBuilder, KeyRotation, resolve_log};
+use credibil_proof::did::{DocumentBuilder, KeyId, Service, VerificationMethod};
+use credibil_proof::ecc::{Curve, Keyring, Signer};
+use credibil_proof::jose::PublicKeyJwk;
+use credibil_proof::{
+    LogStore, webvh_create, webvh_deactivate, webvh_deactivate_rotated, webvh_update,
+    webvh_update_rotated,
+};
+use test_utils::{Proof, Vault};
+
+// Updates should be built from the stored state, so metadata added by the
+// resolver is not written to the log.
+#[tokio::test]
+async fn update_state() {
+    let signer =
+        Keyring::generate(&Vault, "wvu", "signing", Curve::Ed25519).await.expect("should generate");
+    let verifying_key = signer.verifying_key().await.expect("should get key");
+    let jwk = PublicKeyJwk::from_bytes(&verifying_key.to_bytes()).expect("should convert");
+    let update_multi = jwk.to_multibase().expect("should get multibase");
+
+    let vm = VerificationMethod::build()
+        .key(update_multi.clone())
+        .key_id(KeyId::Index("key-0".to_string()));
+    let builder = DocumentBuilder::new().verification_method(vm);
+    let created = webvh_create(
+        "wvu",
+        "https://credibil.io/issuers/update",
+        builder,
+        vec![update_multi],
+        &signer,
+        &Proof,
+    )
+    .await
+    .expect("should create");
+
+    let service = Service::build()
+        .id("whois")
+        .service_type("LinkedVerifiablePresentation")
+        .endpoint("https://example.com/whois");
+    let updated = webvh_update("wvu", &created.did, |b| b.service(service), &signer, &Proof)
+        .await
+        .expect("should update");
+
+    let state = &updated.log_entries[1].state;
+    assert_eq!(state.service.as_ref().map(Vec::len), Some(1));
+    let metadata = state.did_document_metadata.as_ref().expect("should have metadata");
+    assert!(metadata.additional.is_none());
+
+    let log = Proof.read("wvu", &created.did, ..).await.expect("should read");
+    assert_eq!(log.len(), 2);
+    resolve_log(&log, None, None).await.expect("should resolve");
+}
+
+// A log using key pre-rotation should only be updated or deactivated by
+// rotating to the committed next key.
+#[tokio::test]
+async fn pre_rotation() {
+    let entry =
+        Keyring::generate(&Vault, "wvr", "signing", Curve::Ed25519).await.expect("should generate");
+    let rotation = KeyRotation::new(entry).await.expect("should create rotation");
+
+    let vm = VerificationMethod::build()
+        .key(rotation.update_key().to_string())
+        .key_id(KeyId::Index("key-0".to_string()));
+    let created = CreateBuilder::new("https://credibil.io/issuers/rotation")
+        .document(DocumentBuilder::new().verification_method(vm))
+        .key_rotation(&rotation)
+        .build()
+        .await
+        .expect("should build document");
+    Proof.append("wvr", &created.did, None, &created.log).await.expect("should store log");
+
+    let rotation = rotation.rotate(&Vault).await.expect("should rotate");
+    webvh_update("wvr", &created.did, |b| b, rotation.signer(), &Proof)
+        .await
+        .expect_err("should require key rotation");
+    webvh_update_rotated("wvr", &created.did, |b| b, &rotation, &Proof)
+        .await
+        .expect("should update");
+
+    let rotation = rotation.rotate(&Vault).await.expect("should rotate");
+    webvh_deactivate("wvr", &created.did, rotation.signer(), &Proof)
+        .await
+        .expect_err("should require key rotation");
+    let deactivated = webvh_deactivate_rotated("wvr", &created.did, &rotation, &Proof)
+        .await
+        .expect("should deactivate");
+    assert_eq!(deactivated.log.len(), 4);
+
+    let log = Proof.read("wvr", &created.did, ..).await.expect("should read");
+    assert_eq!(log.len(), 4);
+    let document = resolve_log(&log, None, None).await.expect("should resolve");
+    let metadata = document.did_document_metadata.expect("should have metadata");
+    assert_eq!(metadata.deactivated, Some(true));
+}